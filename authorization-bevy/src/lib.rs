@@ -8,7 +8,7 @@ mod tests;
 
 use std::marker::PhantomData;
 
-use authorization::{evaluate, Context, Effect, Policy, Principal, Resource};
+use authorization::{evaluate, AsPrincipal, AsResource, Context, Effect, Policy, PolicyStore};
 use bevy::prelude::*;
 
 /// Authorization Plugin.
@@ -22,29 +22,31 @@ impl Plugin for AuthorizationPlugin {
 }
 
 /// Authorization Event Plugin.
-pub struct AuthorizationEventPlugin<D, T> {
-    d: PhantomData<D>,
+///
+/// Authorizes events with the policies of the [`Store<S>`] resource.
+pub struct AuthorizationEventPlugin<S, T> {
+    s: PhantomData<S>,
     t: PhantomData<T>,
 }
 
-impl<D, T> Default for AuthorizationEventPlugin<D, T> {
+impl<S, T> Default for AuthorizationEventPlugin<S, T> {
     fn default() -> Self {
         Self {
-            d: PhantomData,
+            s: PhantomData,
             t: PhantomData,
         }
     }
 }
 
-impl<D, T> Plugin for AuthorizationEventPlugin<D, T>
+impl<S, T> Plugin for AuthorizationEventPlugin<S, T>
 where
-    D: Database + bevy::prelude::Resource + Send + Sync + 'static,
+    S: PolicyStore + Send + Sync + 'static,
     T: IntoUnauthorizedContext + Clone + Send + Sync + 'static,
 {
     fn build(&self, app: &mut App) {
         app.add_event::<Authorized<T>>()
             .add_event::<Unauthorized<T>>()
-            .add_systems(Update, authorize::<D, T>.in_set(AuthorizationSet));
+            .add_systems(Update, authorize::<S, T>.in_set(AuthorizationSet));
     }
 }
 
@@ -100,11 +102,13 @@ where
     }
 }
 
-/// Database.
-pub trait Database {
-    /// Returns all [`Policy`] containing the [`Principal`].
-    fn query_by_principal(&self, principal: &Principal) -> Vec<Policy>;
-}
+/// Store.
+///
+/// Resource holding the [`PolicyStore`] of an [`AuthorizationEventPlugin`].
+#[derive(Debug, Clone, Default, Resource, Deref, DerefMut)]
+pub struct Store<S>(pub S)
+where
+    S: PolicyStore + Send + Sync + 'static;
 
 /// Into Unauthorized Context.
 pub trait IntoUnauthorizedContext
@@ -119,53 +123,43 @@ where
 }
 
 /// Identifier.
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, AsPrincipal, AsResource)]
 pub struct Identifier {
     /// Id.
+    #[authorization(id)]
     pub id: String,
 
     /// Noun.
+    #[authorization(noun)]
     pub noun: String,
 
     /// Scope.
+    #[authorization(scope)]
     pub scope: String,
 }
 
-impl From<&Identifier> for Principal {
-    fn from(value: &Identifier) -> Self {
-        Principal {
-            id: value.id.clone(),
-            noun: value.noun.clone(),
-            scope: value.scope.clone(),
-        }
-    }
-}
-
-impl From<&Identifier> for Resource {
-    fn from(value: &Identifier) -> Self {
-        Resource {
-            id: value.id.clone(),
-            noun: value.noun.clone(),
-            scope: value.scope.clone(),
-        }
-    }
-}
-
-/// Checks if an [`Unauthorized`] event can be [`Authorized`] using any [`Policy`] in the [`Database`].
+/// Checks if an [`Unauthorized`] event can be [`Authorized`] using any [`Policy`] in the [`Store`].
+///
+/// A store which fails to be queried authorizes nothing.
 #[allow(clippy::needless_pass_by_value)]
-fn authorize<D, T>(
-    database: Res<D>,
+fn authorize<S, T>(
+    store: Res<Store<S>>,
     mut audit: EventWriter<Audit>,
     mut reader: EventReader<Unauthorized<T>>,
     mut writer: EventWriter<Authorized<T>>,
     identifiers: Query<&Identifier>,
 ) where
-    D: Database + bevy::prelude::Resource,
+    S: PolicyStore + Send + Sync + 'static,
     T: IntoUnauthorizedContext + Clone + Send + Sync + 'static,
 {
     for event in reader.read() {
         if let Some(context) = T::into_unauthorized_context(event, &identifiers) {
-            let policies = database.query_by_principal(&context.principal);
+            let policies = store
+                .query_by_principal(&context.principal)
+                .unwrap_or_else(|error| {
+                    error!("failed to query policies: {error}");
+                    Vec::new()
+                });
             let policy = evaluate(&context, &policies);
 
             audit.send(Audit {
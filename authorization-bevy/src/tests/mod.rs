@@ -1,9 +1,9 @@
-use authorization::{Action, Context, Effect, Policy, Principal, Resource};
+use authorization::{Action, Context, Effect, MemoryStore, Policy, Principal, Resource};
 use bevy::prelude::*;
 
 use crate::{
-    Audit, AuthorizationEventPlugin, AuthorizationPlugin, Authorized, Database,
-    IntoUnauthorizedContext, Unauthorized,
+    Audit, AuthorizationEventPlugin, AuthorizationPlugin, Authorized, IntoUnauthorizedContext,
+    Store, Unauthorized,
 };
 
 #[test]
@@ -14,11 +14,9 @@ fn authorize_should_send_event_when_explicit_allow() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase {
-        policy: Some(test_policy(Effect::Allow)),
-    });
+    .insert_resource(Store(MemoryStore::from_iter([test_policy(Effect::Allow)])));
 
     let entity = app.world.spawn(()).id();
 
@@ -50,11 +48,9 @@ fn authorize_should_not_send_event_when_explicit_deny() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase {
-        policy: Some(test_policy(Effect::Deny)),
-    });
+    .insert_resource(Store(MemoryStore::from_iter([test_policy(Effect::Deny)])));
 
     let entity = app.world.spawn(()).id();
 
@@ -86,9 +82,9 @@ fn authorize_should_not_send_event_when_implicit_deny() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase { policy: None });
+    .insert_resource(Store(MemoryStore::new()));
 
     let entity = app.world.spawn(()).id();
 
@@ -120,11 +116,9 @@ fn authorize_should_send_audit_when_explicit_allow() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase {
-        policy: Some(test_policy(Effect::Allow)),
-    });
+    .insert_resource(Store(MemoryStore::from_iter([test_policy(Effect::Allow)])));
 
     let entity = app.world.spawn(()).id();
 
@@ -154,11 +148,9 @@ fn authorize_should_not_send_audit_when_explicit_deny() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase {
-        policy: Some(test_policy(Effect::Deny)),
-    });
+    .insert_resource(Store(MemoryStore::from_iter([test_policy(Effect::Deny)])));
 
     let entity = app.world.spawn(()).id();
 
@@ -188,9 +180,9 @@ fn authorize_should_not_send_audit_when_implicit_deny() {
     app.add_plugins((
         MinimalPlugins,
         AuthorizationPlugin,
-        AuthorizationEventPlugin::<TestDatabase, TestEvent>::default(),
+        AuthorizationEventPlugin::<MemoryStore, TestEvent>::default(),
     ))
-    .insert_resource(TestDatabase { policy: None });
+    .insert_resource(Store(MemoryStore::new()));
 
     let entity = app.world.spawn(()).id();
 
@@ -212,28 +204,6 @@ fn authorize_should_not_send_audit_when_implicit_deny() {
     assert_eq!(audit.policy, None);
 }
 
-#[derive(Resource)]
-struct TestDatabase {
-    policy: Option<Policy>,
-}
-
-impl Database for TestDatabase {
-    fn query_by_principal(
-        &self,
-        principal: &authorization::Principal,
-    ) -> Vec<authorization::Policy> {
-        assert_eq!(principal.id, "id");
-        assert_eq!(principal.noun, "noun");
-        assert_eq!(principal.scope, "scope");
-
-        if let Some(policy) = &self.policy {
-            vec![policy.clone()]
-        } else {
-            vec![]
-        }
-    }
-}
-
 #[derive(Clone)]
 struct TestEvent;
 
@@ -248,45 +218,16 @@ impl IntoUnauthorizedContext for TestEvent {
 }
 
 fn test_context() -> Context {
-    Context {
-        action: Action {
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-            verb: "verb".to_string(),
-        },
-        data: Default::default(),
-        principal: Principal {
-            id: "id".to_string(),
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-        },
-        resource: Resource {
-            id: "id".to_string(),
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-        },
-    }
+    Context::new(
+        Action::new("scope", "noun", "verb"),
+        Principal::new("scope", "noun", "id"),
+        Resource::new("scope", "noun", "id"),
+    )
 }
 
 fn test_policy(effect: Effect) -> Policy {
-    Policy {
-        actions: vec![Action {
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-            verb: "verb".to_string(),
-        }],
-        conditions: Default::default(),
-        effect,
-        id: "id".to_string(),
-        principals: vec![Principal {
-            id: "id".to_string(),
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-        }],
-        resources: vec![Resource {
-            id: "id".to_string(),
-            noun: "noun".to_string(),
-            scope: "scope".to_string(),
-        }],
-    }
+    Policy::new(effect, "id")
+        .action(Action::new("scope", "noun", "verb"))
+        .principal(Principal::new("scope", "noun", "id"))
+        .resource(Resource::new("scope", "noun", "id"))
 }
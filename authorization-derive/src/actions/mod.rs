@@ -0,0 +1,135 @@
+//! Actions.
+//!
+//! Expands `#[derive(Actions)]`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Expands the derive of actions.
+pub(crate) fn expand(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "expected an enum, found a struct or union",
+        ));
+    };
+
+    let mut noun = None;
+    let mut scope = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("authorization") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            let segment = if meta.path.is_ident("noun") {
+                &mut noun
+            } else if meta.path.is_ident("scope") {
+                &mut scope
+            } else {
+                return Err(meta.error("expected `noun` or `scope`"));
+            };
+
+            if segment.is_some() {
+                return Err(meta.error("duplicate segment"));
+            }
+            *segment = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        })?;
+    }
+
+    let missing = |name: &str| {
+        Error::new_spanned(
+            &input.ident,
+            format!("missing `#[authorization({name} = \"..\")]`"),
+        )
+    };
+    let noun = noun.ok_or_else(|| missing("noun"))?;
+    let scope = scope.ok_or_else(|| missing("scope"))?;
+
+    let mut variants = Vec::new();
+    let mut verbs = Vec::new();
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "expected a unit variant"));
+        }
+
+        let mut verb = None;
+        for attr in &variant.attrs {
+            if !attr.path().is_ident("authorization") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("verb") {
+                    return Err(meta.error("expected `verb`"));
+                }
+                if verb.is_some() {
+                    return Err(meta.error("duplicate segment"));
+                }
+                verb = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            })?;
+        }
+
+        let ident = &variant.ident;
+        verbs.push(
+            verb.unwrap_or_else(|| {
+                LitStr::new(&snake_case(&ident.unraw().to_string()), ident.span())
+            }),
+        );
+        variants.push(ident);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::authorization::Actions for #ident #ty_generics #where_clause {
+            const ALL: &'static [Self] = &[#(Self::#variants),*];
+
+            fn action(&self) -> ::authorization::borrowed::ActionRef<'static> {
+                ::authorization::borrowed::ActionRef {
+                    noun: #noun,
+                    scope: #scope,
+                    verb: match self {
+                        #(Self::#variants => #verbs,)*
+                    },
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for ::authorization::Action #where_clause {
+            fn from(value: #ident #ty_generics) -> Self {
+                ::authorization::Actions::action(&value).into()
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for ::authorization::borrowed::ActionRef<'static> #where_clause {
+            fn from(value: #ident #ty_generics) -> Self {
+                ::authorization::Actions::action(&value)
+            }
+        }
+    })
+}
+
+/// Converts an identifier from `UpperCamelCase` into `snake_case`.
+fn snake_case(ident: &str) -> String {
+    let mut snake = String::new();
+
+    for (index, c) in ident.char_indices() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
@@ -0,0 +1,207 @@
+//! Entity.
+//!
+//! Expands `#[derive(AsPrincipal)]` and `#[derive(AsResource)]`.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{ext::IdentExt, Data, DeriveInput, Error, Fields, Ident, LitStr, Member};
+
+/// Kind of entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// Principal.
+    Principal,
+
+    /// Resource.
+    Resource,
+}
+
+/// Source of a segment.
+enum Source {
+    /// A field of the struct.
+    Field(Member),
+
+    /// A literal from the struct attribute.
+    Literal(LitStr),
+}
+
+impl Source {
+    /// Returns an expression converting the source into a `String`.
+    fn expr(&self) -> TokenStream {
+        match self {
+            Source::Field(member) => {
+                quote!(::authorization::__private::ToString::to_string(&self.#member))
+            }
+            Source::Literal(literal) => {
+                quote!(::authorization::__private::ToString::to_string(#literal))
+            }
+        }
+    }
+}
+
+/// Segments and attributes of an entity, parsed from `#[authorization(..)]`.
+struct Entity {
+    /// Attribute names and the fields they are taken from.
+    attributes: Vec<(LitStr, Member)>,
+
+    /// Id.
+    id: Source,
+
+    /// Noun.
+    noun: Source,
+
+    /// Scope.
+    scope: Source,
+}
+
+/// Expands the derive of an entity.
+pub(crate) fn expand(input: &DeriveInput, kind: Kind) -> Result<TokenStream, Error> {
+    let Entity {
+        attributes,
+        id,
+        noun,
+        scope,
+    } = parse(input)?;
+    let (id, noun, scope) = (id.expr(), noun.expr(), scope.expr());
+
+    let attributes = attributes.iter().map(|(name, member)| {
+        quote! {
+            .attribute(
+                #name,
+                ::authorization::Value::from(::core::clone::Clone::clone(&self.#member)),
+            )
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (entity, method, r#trait) = match kind {
+        Kind::Principal => (
+            quote!(::authorization::Principal),
+            quote!(as_principal),
+            quote!(::authorization::AsPrincipal),
+        ),
+        Kind::Resource => (
+            quote!(::authorization::Resource),
+            quote!(as_resource),
+            quote!(::authorization::AsResource),
+        ),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #r#trait for #ident #ty_generics #where_clause {
+            fn #method(&self) -> #entity {
+                #entity::new(#scope, #noun, #id)
+                    #(#attributes)*
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<&#ident #ty_generics> for #entity #where_clause {
+            fn from(value: &#ident #ty_generics) -> Self {
+                #r#trait::#method(value)
+            }
+        }
+    })
+}
+
+/// Parses the `#[authorization(..)]` attributes of a struct and its fields.
+fn parse(input: &DeriveInput) -> Result<Entity, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "expected a struct, found an enum or union",
+        ));
+    };
+
+    let mut attributes = Vec::new();
+    let mut id = None;
+    let mut noun = None;
+    let mut scope = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("authorization") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("noun") {
+                set(
+                    &mut noun,
+                    Source::Literal(meta.value()?.parse()?),
+                    &meta.path,
+                )
+            } else if meta.path.is_ident("scope") {
+                set(
+                    &mut scope,
+                    Source::Literal(meta.value()?.parse()?),
+                    &meta.path,
+                )
+            } else {
+                Err(meta.error("expected `noun` or `scope`"))
+            }
+        })?;
+    }
+
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = field
+            .ident
+            .clone()
+            .map_or_else(|| Member::from(index), Member::from);
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("authorization") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("attribute") {
+                    let name = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<LitStr>()?
+                    } else if let Member::Named(ident) = &member {
+                        LitStr::new(&ident.unraw().to_string(), ident.span())
+                    } else {
+                        return Err(meta.error("expected `attribute = \"name\"`"));
+                    };
+                    attributes.push((name, member.clone()));
+                    Ok(())
+                } else if meta.path.is_ident("id") {
+                    set(&mut id, Source::Field(member.clone()), &meta.path)
+                } else if meta.path.is_ident("noun") {
+                    set(&mut noun, Source::Field(member.clone()), &meta.path)
+                } else if meta.path.is_ident("scope") {
+                    set(&mut scope, Source::Field(member.clone()), &meta.path)
+                } else {
+                    Err(meta.error("expected `attribute`, `id`, `noun` or `scope`"))
+                }
+            })?;
+        }
+    }
+
+    if matches!(data.fields, Fields::Unit) {
+        return Err(Error::new_spanned(input, "expected a struct with fields"));
+    }
+
+    let missing = |name: &str| {
+        Error::new(
+            Span::call_site(),
+            format!("missing `#[authorization({name})]`"),
+        )
+    };
+    Ok(Entity {
+        attributes,
+        id: id.ok_or_else(|| missing("id"))?,
+        noun: noun.ok_or_else(|| missing("noun"))?,
+        scope: scope.ok_or_else(|| missing("scope"))?,
+    })
+}
+
+/// Sets a segment, returning an error if it is already set.
+fn set(segment: &mut Option<Source>, source: Source, path: &syn::Path) -> Result<(), Error> {
+    if segment.is_some() {
+        let name = path.get_ident().map(Ident::to_string).unwrap_or_default();
+        return Err(Error::new_spanned(path, format!("duplicate `{name}`")));
+    }
+
+    *segment = Some(source);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization Derive.
+//!
+//! Derive macros re-exported by `authorization` behind its `derive` feature.
+
+mod actions;
+mod entity;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives `Actions`, and `From<T>` for `Action` and `ActionRef<'static>`.
+///
+/// See `authorization::Actions` for the accepted attributes.
+#[proc_macro_derive(Actions, attributes(authorization))]
+pub fn derive_actions(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    actions::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `AsPrincipal`, and `From<&T> for Principal`.
+///
+/// See `authorization::AsPrincipal` for the accepted attributes.
+#[proc_macro_derive(AsPrincipal, attributes(authorization))]
+pub fn derive_as_principal(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    entity::expand(&input, entity::Kind::Principal)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `AsResource`, and `From<&T> for Resource`.
+///
+/// See `authorization::AsResource` for the accepted attributes.
+#[proc_macro_derive(AsResource, attributes(authorization))]
+pub fn derive_as_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    entity::expand(&input, entity::Kind::Resource)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,263 @@
+//! Expand.
+//!
+//! Converts parsed policies into the tokens of an expression that builds them.
+
+use std::net::IpAddr;
+
+use authorization::{
+    expression::{Comparison, Entity, Expr},
+    value::Timestamp,
+    Action, Condition, Effect, Metadata, Policy, Principal, Resource,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Expands a [`Policy`].
+pub(crate) fn policy(policy: &Policy) -> TokenStream {
+    let Policy {
+        actions,
+        conditions,
+        effect,
+        id,
+        metadata,
+        principals,
+        resources,
+    } = policy;
+
+    let effect = match effect {
+        Effect::Allow => quote!(::authorization::Effect::Allow),
+        Effect::Deny => quote!(::authorization::Effect::Deny),
+    };
+    let id = string(id);
+    let actions = actions.iter().map(action);
+    let conditions = conditions.iter().map(condition);
+    let metadata = self::metadata(metadata);
+    let principals = principals.iter().map(principal);
+    let resources = resources.iter().map(resource);
+
+    quote! {
+        ::authorization::Policy::new(#effect, #id)
+            #(.action(#actions))*
+            #(.when(#conditions))*
+            .metadata(#metadata)
+            #(.principal(#principals))*
+            #(.resource(#resources))*
+    }
+}
+
+/// Expands an [`Action`].
+fn action(action: &Action) -> TokenStream {
+    let Action { noun, scope, verb } = action;
+
+    quote!(::authorization::Action::new(#scope, #noun, #verb))
+}
+
+/// Expands a [`Condition`].
+fn condition(condition: &Condition) -> TokenStream {
+    let Condition {
+        expression,
+        string_equals,
+    } = condition;
+
+    let expression = expression.as_ref().map(|expression| {
+        let expr = expr(expression.expr());
+        let source = expression.source();
+        quote!(.expression(::authorization::__private::expression(#expr, #source)))
+    });
+
+    let mut entries = string_equals.iter().flatten().collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+    let string_equals = entries.into_iter().map(|(key, values)| {
+        let mut values = values.iter().collect::<Vec<_>>();
+        values.sort_unstable();
+        quote!(.string_equals(#key, [#(#values),*]))
+    });
+
+    // Empty string equals criteria expand to none, which are met alike.
+    quote! {
+        ::authorization::Condition::new()
+            #expression
+            #(#string_equals)*
+    }
+}
+
+/// Expands an [`Expr`].
+fn expr(expr: &Expr) -> TokenStream {
+    let boxed = |expr: &Expr| {
+        let expr = self::expr(expr);
+        quote!(::authorization::__private::Box::new(#expr))
+    };
+
+    match expr {
+        Expr::And(left, right) => {
+            let (left, right) = (boxed(left), boxed(right));
+            quote!(::authorization::expression::Expr::And(#left, #right))
+        }
+        Expr::Attribute(entity, name) => {
+            let entity = match entity {
+                Entity::Action => quote!(Action),
+                Entity::Principal => quote!(Principal),
+                Entity::Resource => quote!(Resource),
+            };
+            let name = string(name);
+            quote!(::authorization::expression::Expr::Attribute(
+                ::authorization::expression::Entity::#entity,
+                #name,
+            ))
+        }
+        Expr::Bool(value) => quote!(::authorization::expression::Expr::Bool(#value)),
+        Expr::Compare(comparison, left, right) => {
+            let comparison = match comparison {
+                Comparison::Equal => quote!(Equal),
+                Comparison::GreaterThan => quote!(GreaterThan),
+                Comparison::GreaterThanOrEqual => quote!(GreaterThanOrEqual),
+                Comparison::LessThan => quote!(LessThan),
+                Comparison::LessThanOrEqual => quote!(LessThanOrEqual),
+                Comparison::NotEqual => quote!(NotEqual),
+            };
+            let (left, right) = (boxed(left), boxed(right));
+            quote!(::authorization::expression::Expr::Compare(
+                ::authorization::expression::Comparison::#comparison,
+                #left,
+                #right,
+            ))
+        }
+        Expr::Data(key) => {
+            let key = string(key);
+            quote!(::authorization::expression::Expr::Data(#key))
+        }
+        Expr::Decimal(value) => {
+            let units = value.units();
+            quote!(::authorization::expression::Expr::Decimal(
+                ::authorization::value::Decimal::from_units(#units),
+            ))
+        }
+        Expr::Field(expr, name) => {
+            let (expr, name) = (boxed(expr), string(name));
+            quote!(::authorization::expression::Expr::Field(#expr, #name))
+        }
+        Expr::Has(key) => {
+            let key = string(key);
+            quote!(::authorization::expression::Expr::Has(#key))
+        }
+        Expr::In(left, right) => {
+            let (left, right) = (boxed(left), boxed(right));
+            quote!(::authorization::expression::Expr::In(#left, #right))
+        }
+        Expr::Int(value) => quote!(::authorization::expression::Expr::Int(#value)),
+        Expr::Ip(value) => {
+            let value = ip(value);
+            quote!(::authorization::expression::Expr::Ip(#value))
+        }
+        Expr::List(items) => {
+            let items = collect(items.iter().map(self::expr));
+            quote!(::authorization::expression::Expr::List(#items))
+        }
+        Expr::Not(expr) => {
+            let expr = boxed(expr);
+            quote!(::authorization::expression::Expr::Not(#expr))
+        }
+        Expr::Or(left, right) => {
+            let (left, right) = (boxed(left), boxed(right));
+            quote!(::authorization::expression::Expr::Or(#left, #right))
+        }
+        Expr::Size(expr) => {
+            let expr = boxed(expr);
+            quote!(::authorization::expression::Expr::Size(#expr))
+        }
+        Expr::String(value) => {
+            let value = string(value);
+            quote!(::authorization::expression::Expr::String(#value))
+        }
+        Expr::Timestamp(value) => {
+            let value = timestamp(*value);
+            quote!(::authorization::expression::Expr::Timestamp(#value))
+        }
+    }
+}
+
+/// Expands a [`Timestamp`].
+fn timestamp(timestamp: Timestamp) -> TokenStream {
+    let millis = timestamp.millis();
+    quote!(::authorization::value::Timestamp::from_millis(#millis).unwrap())
+}
+
+/// Expands an [`IpAddr`].
+fn ip(ip: &IpAddr) -> TokenStream {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            quote!(::core::net::IpAddr::V4(::core::net::Ipv4Addr::from([#(#octets),*])))
+        }
+        IpAddr::V6(ip) => {
+            let octets = ip.octets();
+            quote!(::core::net::IpAddr::V6(::core::net::Ipv6Addr::from([#(#octets),*])))
+        }
+    }
+}
+
+/// Expands [`Metadata`].
+fn metadata(metadata: &Metadata) -> TokenStream {
+    let Metadata {
+        created_at,
+        description,
+        owner,
+        tags,
+        updated_at,
+        version,
+    } = metadata;
+
+    let created_at = created_at.map(|created_at| {
+        let created_at = timestamp(created_at);
+        quote!(.created_at(#created_at))
+    });
+    let description = description
+        .as_deref()
+        .map(|description| quote!(.description(#description)));
+    let owner = owner.as_deref().map(|owner| quote!(.owner(#owner)));
+    let updated_at = updated_at.map(|updated_at| {
+        let updated_at = timestamp(updated_at);
+        quote!(.updated_at(#updated_at))
+    });
+    let version = version.map(|version| quote!(.version(#version)));
+
+    quote! {
+        ::authorization::Metadata::new()
+            #created_at
+            #description
+            #owner
+            #(.tag(#tags))*
+            #updated_at
+            #version
+    }
+}
+
+/// Expands a [`Principal`].
+fn principal(principal: &Principal) -> TokenStream {
+    let Principal {
+        id, noun, scope, ..
+    } = principal;
+
+    quote!(::authorization::Principal::new(#scope, #noun, #id))
+}
+
+/// Expands a [`Resource`].
+fn resource(resource: &Resource) -> TokenStream {
+    let Resource {
+        id, noun, scope, ..
+    } = resource;
+
+    quote!(::authorization::Resource::new(#scope, #noun, #id))
+}
+
+/// Expands items into a collection, such as a `Vec`, inferred from where it is used.
+fn collect(items: impl Iterator<Item = TokenStream>) -> TokenStream {
+    quote!(::core::iter::Iterator::collect(
+        ::core::iter::IntoIterator::into_iter([#(#items),*]),
+    ))
+}
+
+/// Expands a `String`.
+fn string(value: &str) -> TokenStream {
+    quote!(::authorization::__private::ToString::to_string(#value))
+}
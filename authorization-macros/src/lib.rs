@@ -0,0 +1,67 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization Macros.
+//!
+//! Function-like macros that parse the [`dsl`](authorization::dsl) at compile time.
+//!
+//! ```ignore
+//! let policy = authorization_macros::policy! {
+//!     @id("attack-monsters")
+//!     allow player@world:*
+//!       to world:*:attack
+//!       on monster@world:*
+//!       when data["player:level"] >= 10;
+//! };
+//! ```
+//!
+//! Syntax and type errors are reported at compile time, pointing at the offending token. The
+//! expansion builds the [`Policy`](authorization::Policy) directly, so nothing is parsed at
+//! runtime. It cannot be `const`, as a policy owns its strings and lists.
+
+mod expand;
+mod source;
+
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::source::Source;
+
+/// Expands a policy written in the DSL into a `Policy`.
+///
+/// The trailing `;` is optional.
+#[proc_macro]
+pub fn policy(input: TokenStream) -> TokenStream {
+    let mut source = Source::new(input);
+    source.terminate();
+
+    match source.parse() {
+        Ok(policies) => match <[_; 1]>::try_from(policies) {
+            Ok([policy]) => expand::policy(&policy).into(),
+            Err(policies) => source
+                .error(
+                    None,
+                    &format!("expected one policy, found {}", policies.len()),
+                )
+                .into(),
+        },
+        Err(error) => error.into(),
+    }
+}
+
+/// Expands policies written in the DSL into a `Vec<Policy>`.
+#[proc_macro]
+pub fn policies(input: TokenStream) -> TokenStream {
+    let source = Source::new(input);
+
+    match source.parse() {
+        Ok(policies) => {
+            let policies = policies.iter().map(expand::policy);
+            quote!(::core::iter::Iterator::collect::<::authorization::__private::Vec<_>>(
+                ::core::iter::IntoIterator::into_iter([#(#policies),*]),
+            ))
+            .into()
+        }
+        Err(error) => error.into(),
+    }
+}
@@ -0,0 +1,131 @@
+//! Source.
+//!
+//! Rebuilds the DSL source of a macro invocation from its tokens, keeping their layout so that
+//! positions reported by the parser can be traced back to a token.
+
+use authorization::{dsl, Policy};
+use proc_macro::{Delimiter, Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+
+/// Source.
+pub(crate) struct Source {
+    /// Line and column where the previous token ended.
+    end: Option<(usize, usize)>,
+
+    /// Whether the previous token was an identifier or literal.
+    word: bool,
+
+    /// Text.
+    text: String,
+
+    /// Offsets in the text where each token starts, in ascending order.
+    tokens: Vec<(usize, Span)>,
+}
+
+impl Source {
+    /// Rebuilds the source of tokens.
+    pub(crate) fn new(input: TokenStream) -> Self {
+        let mut source = Source {
+            end: None,
+            word: false,
+            text: String::new(),
+            tokens: Vec::new(),
+        };
+        source.extend(input);
+        source
+    }
+
+    /// Appends a `;` unless the source already ends with one.
+    pub(crate) fn terminate(&mut self) {
+        if !self.text.trim_end().ends_with(';') {
+            self.text.push(';');
+        }
+    }
+
+    /// Parses the policies, returning a `compile_error!` at the offending token on failure.
+    pub(crate) fn parse(&self) -> Result<Vec<Policy>, proc_macro2::TokenStream> {
+        dsl::parse(&self.text)
+            .map_err(|error| self.error(self.offset(error.line, error.column), &error.message))
+    }
+
+    /// Returns a `compile_error!` at the token containing an offset, or at the call site.
+    pub(crate) fn error(&self, offset: Option<usize>, message: &str) -> proc_macro2::TokenStream {
+        let span = offset
+            .and_then(|offset| {
+                self.tokens
+                    .iter()
+                    .take_while(|(start, _)| *start <= offset)
+                    .last()
+            })
+            .map_or_else(Span::call_site, |(_, span)| *span);
+
+        quote_spanned!(span.into() => ::core::compile_error!(#message))
+    }
+
+    /// Appends tokens.
+    fn extend(&mut self, input: TokenStream) {
+        for token in input {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                        Delimiter::Parenthesis => ("(", ")"),
+                    };
+                    self.push(open, group.span_open(), false);
+                    self.extend(group.stream());
+                    self.push(close, group.span_close(), false);
+                }
+                TokenTree::Ident(ident) => self.push(&ident.to_string(), ident.span(), true),
+                TokenTree::Literal(literal) => {
+                    self.push(&literal.to_string(), literal.span(), true);
+                }
+                TokenTree::Punct(punct) => {
+                    self.push(&punct.as_char().to_string(), punct.span(), false);
+                }
+            }
+        }
+    }
+
+    /// Appends a token, preceded by the whitespace that separated it from the previous token.
+    fn push(&mut self, text: &str, span: Span, word: bool) {
+        if text.is_empty() {
+            return;
+        }
+
+        let start = span.start();
+        match self.end {
+            Some((line, _)) if start.line() > line => {
+                self.text.push_str(&"\n".repeat(start.line() - line));
+                self.text.push_str(&" ".repeat(start.column()));
+            }
+            Some((line, column)) if start.line() == line && start.column() > column => {
+                self.text.push_str(&" ".repeat(start.column() - column));
+            }
+            Some(_) if word && self.word => self.text.push(' '),
+            _ => {}
+        }
+
+        self.tokens.push((self.text.len(), span));
+        self.text.push_str(text);
+
+        let end = span.end();
+        self.end = Some((end.line(), end.column()));
+        self.word = word;
+    }
+
+    /// Returns the offset of a line and column, both starting at 1.
+    fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let start = if line == 1 {
+            0
+        } else {
+            self.text.match_indices('\n').nth(line - 2)?.0 + 1
+        };
+
+        self.text[start..]
+            .char_indices()
+            .nth(column - 1)
+            .map(|(index, _)| start + index)
+    }
+}
@@ -0,0 +1,121 @@
+use authorization::{dsl, Condition, Context, Effect, Metadata, Policy, Principal, Resource};
+use authorization_macros::{policies, policy};
+use serde_json::json;
+
+#[test]
+fn policy_should_expand_to_parsed_policy() {
+    // Arrange
+    let source = r#"
+        @id("attack-monsters")
+        @tags("pve")
+        @version(2)
+        allow player@world:*
+          to world:*:attack
+          on monster@world:*
+          when data["player:level"] >= 10 && data["time"] < timestamp("2024-01-01T00:00:00Z")
+          when {"request:host": ["localhost:3000"]};
+    "#;
+
+    // Act
+    let result = policy! {
+        @id("attack-monsters")
+        @tags("pve")
+        @version(2)
+        allow player@world:*
+          to world:*:attack
+          on monster@world:*
+          when data["player:level"] >= 10 && data["time"] < timestamp("2024-01-01T00:00:00Z")
+          when {"request:host": ["localhost:3000"]};
+    };
+
+    // Assert
+    assert_eq!(vec![result], dsl::parse(source).unwrap());
+}
+
+#[test]
+fn policy_should_expand_quoted_segments_and_empty_lists() {
+    // Act
+    let result = policy!(@id("admin") deny user@local:"a b" to none on *@*:*);
+
+    // Assert
+    assert_eq!(
+        result,
+        Policy::deny("admin")
+            .principal(Principal::new("local", "user", "a b"))
+            .resource(Resource::new("*", "*", "*"))
+    );
+}
+
+#[test]
+fn policy_should_expand_metadata() {
+    // Act
+    let result = policy! {
+        @id("policy-1")
+        @created_at("2024-01-01T00:00:00Z")
+        @description("Allows everything.")
+        @owner("platform")
+        @tags("a", "b")
+        @updated_at("2024-02-01T00:00:00Z")
+        @version(3)
+        allow *@*:* to *:*:* on *@*:*
+    };
+
+    // Assert
+    assert_eq!(
+        result.metadata,
+        Metadata::new()
+            .created_at("2024-01-01T00:00:00Z".parse().unwrap())
+            .description("Allows everything.")
+            .owner("platform")
+            .tag("a")
+            .tag("b")
+            .updated_at("2024-02-01T00:00:00Z".parse().unwrap())
+            .version(3)
+    );
+}
+
+#[test]
+fn policy_should_evaluate_expression_without_parsing() {
+    // Arrange
+    let policies = [policy! {
+        @id("policy-1")
+        allow player@world:* to world:loot:take on loot@world:*
+          when data["ip"] in [ip("10.0.0.1"), ip("::1")] && data["price"] <= decimal("2.5");
+    }];
+    let context = serde_json::from_value::<Context>(json!({
+        "action": "world:loot:take",
+        "data": { "ip": { "$ip": "::1" }, "price": 2.25 },
+        "principal": "world:player:1",
+        "resource": "world:loot:2",
+    }))
+    .unwrap();
+
+    // Act
+    let result = authorization::evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn policies_should_expand_to_vec() {
+    // Act
+    let result = policies! {
+        @id("policy-1") allow *@*:* to *:*:* on *@*:* when {"a": ["1", "2"]};
+        @id("policy-2") deny *@*:* to *:*:* on *@*:*;
+    };
+
+    // Assert
+    assert_eq!(result.len(), 2);
+    assert_eq!(
+        result[0].conditions,
+        vec![Condition::new().string_equals("a", ["1", "2"])]
+    );
+    assert_eq!(result[1].effect, Effect::Deny);
+}
+
+#[test]
+fn policy_should_report_errors_at_compile_time() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,8 @@
+use authorization_macros::policy;
+
+fn main() {
+    let _ = policy! {
+        @id("policy-1") allow *@*:* to *:*:* on *@*:*;
+        @id("policy-2") deny *@*:* to *:*:* on *@*:*;
+    };
+}
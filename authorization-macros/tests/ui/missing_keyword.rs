@@ -0,0 +1,10 @@
+use authorization_macros::policy;
+
+fn main() {
+    let _ = policy! {
+        @id("policy-1")
+        allow player@world:*
+          too world:*:attack
+          on monster@world:*;
+    };
+}
@@ -0,0 +1,11 @@
+use authorization_macros::policy;
+
+fn main() {
+    let _ = policy! {
+        @id("policy-1")
+        allow player@world:*
+          to world:*:attack
+          on monster@world:*
+          when data["player:level"] === 10;
+    };
+}
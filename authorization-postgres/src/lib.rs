@@ -0,0 +1,505 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization `PostgreSQL`.
+//!
+//! An [`AsyncPolicyStore`] persisted in `PostgreSQL`, for services sharing policies across nodes.
+//!
+//! Actions, principals and resources are normalised into indexed tables, so wildcard lookups
+//! are index searches rather than scans of every policy. Every write increases the revision,
+//! which a trigger publishes on [`CHANNEL`], so each node can refresh its
+//! [`Snapshot`](authorization::store::Snapshot) as soon as another node writes.
+//!
+//! The tables are created in the first schema of the `search_path` of the connection. Stores in
+//! other schemas of the database share the channel, so each notification names its schema.
+
+mod migrations;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    future,
+    sync::{Arc, OnceLock},
+};
+
+use authorization::{
+    store::Change, Action, AsyncPolicyStore, Condition, Effect, Metadata, Policy, Principal,
+    Resource,
+};
+use tokio::sync::watch;
+use tokio_postgres::{
+    tls::MakeTlsConnect, types::Json, AsyncMessage, Client, Config, Row, Socket, Transaction,
+};
+
+/// Channel on which the revision is published after each write, as `<revision>:<schema>`.
+pub const CHANNEL: &str = "authorization_policy_revision";
+
+/// Policies with their actions, principals and resources as JSON arrays, ordered by position.
+const SELECT: &str = "\
+    SELECT p.id, p.effect, p.conditions, p.metadata, \
+        COALESCE((SELECT jsonb_agg(jsonb_build_object( \
+            'noun', a.noun, 'scope', a.scope, 'verb', a.verb) ORDER BY a.position) \
+            FROM policy_actions a WHERE a.policy_id = p.id), '[]'), \
+        COALESCE((SELECT jsonb_agg(jsonb_build_object( \
+            'attributes', e.attributes, 'id', e.id, 'noun', e.noun, 'scope', e.scope) \
+            ORDER BY e.position) \
+            FROM policy_principals e WHERE e.policy_id = p.id), '[]'), \
+        COALESCE((SELECT jsonb_agg(jsonb_build_object( \
+            'attributes', e.attributes, 'id', e.id, 'noun', e.noun, 'scope', e.scope) \
+            ORDER BY e.position) \
+            FROM policy_resources e WHERE e.policy_id = p.id), '[]') \
+    FROM policies p";
+
+/// `PostgreSQL` store.
+#[derive(Debug)]
+pub struct PostgresStore {
+    /// Client.
+    client: Client,
+}
+
+/// Notifications of the revisions written by any node.
+///
+/// Successive revisions received before [`recv`](Notifications::recv) is called are coalesced
+/// into the latest.
+#[derive(Debug, Clone)]
+pub struct Notifications {
+    /// Receiver of the latest revision.
+    receiver: watch::Receiver<u64>,
+}
+
+/// Error.
+#[derive(Debug)]
+pub enum Error {
+    /// A policy with the id already exists.
+    Conflict(String),
+
+    /// A policy cannot be decoded from its rows.
+    Encoding {
+        /// Policy id.
+        id: String,
+
+        /// Message.
+        message: String,
+    },
+
+    /// The revision of the database is negative.
+    InvalidRevision(i64),
+
+    /// No policy with the id exists.
+    NotFound(String),
+
+    /// `PostgreSQL` failed.
+    Postgres(tokio_postgres::Error),
+
+    /// The database was migrated by a newer release.
+    UnsupportedVersion(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Conflict(id) => write!(f, "policy `{id}` already exists"),
+            Error::Encoding { id, message } => write!(f, "policy `{id}` is malformed: {message}"),
+            Error::InvalidRevision(revision) => write!(f, "revision {revision} is negative"),
+            Error::NotFound(id) => write!(f, "policy `{id}` does not exist"),
+            Error::Postgres(error) => write!(f, "postgres: {error}"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported schema version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Postgres(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(error: tokio_postgres::Error) -> Self {
+        Error::Postgres(error)
+    }
+}
+
+impl PostgresStore {
+    /// Connects to a database, migrating it as needed, and listens for the revisions written by
+    /// any node to the schema of the store.
+    ///
+    /// The connection is driven by a task spawned on the current Tokio runtime, until the store
+    /// is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be connected to, migrated or listened to.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of a Tokio runtime.
+    pub async fn connect<T>(config: &Config, tls: T) -> Result<(Self, Notifications), Error>
+    where
+        T: MakeTlsConnect<Socket>,
+        T::Stream: Send + 'static,
+    {
+        let (client, mut connection) = config.connect(tls).await?;
+        let (sender, receiver) = watch::channel(0);
+        let schema = Arc::new(OnceLock::<String>::new());
+
+        tokio::spawn({
+            let schema = Arc::clone(&schema);
+            async move {
+                while let Some(Ok(message)) =
+                    future::poll_fn(|context| connection.poll_message(context)).await
+                {
+                    if let AsyncMessage::Notification(notification) = message {
+                        let revision = schema
+                            .get()
+                            .and_then(|schema| revision(notification.payload(), schema));
+                        if let Some(revision) = revision {
+                            sender.send_replace(revision);
+                        }
+                    }
+                }
+            }
+        });
+
+        let store = PostgresStore::new(client).await?;
+        let current = store
+            .client
+            .query_one("SELECT current_schema()", &[])
+            .await?
+            .try_get(0)?;
+        let _ = schema.set(current);
+        store
+            .client
+            .batch_execute(&format!("LISTEN {CHANNEL}"))
+            .await?;
+        Ok((store, Notifications { receiver }))
+    }
+
+    /// Creates a store over a client, migrating its database as needed.
+    ///
+    /// To be notified of writes, the connection of the client must `LISTEN` on [`CHANNEL`], and
+    /// ignore the revisions of other schemas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be migrated.
+    pub async fn new(mut client: Client) -> Result<Self, Error> {
+        migrations::migrate(&mut client).await?;
+        Ok(PostgresStore { client })
+    }
+
+    /// Applies changes in one transaction, increasing the revision once if any policy changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, applying none of the changes, if any change fails.
+    pub async fn apply(&mut self, changes: impl IntoIterator<Item = Change>) -> Result<(), Error> {
+        let transaction = self.client.transaction().await?;
+
+        let mut modified = false;
+        for change in changes {
+            modified |= match change {
+                Change::Delete(id) => remove(&transaction, &id).await?,
+                Change::Insert(policy) => {
+                    if !write(&transaction, &policy).await? {
+                        return Err(Error::Conflict(policy.id));
+                    }
+                    true
+                }
+                Change::Update(policy) => {
+                    if !remove(&transaction, &policy.id).await? {
+                        return Err(Error::NotFound(policy.id));
+                    }
+                    write(&transaction, &policy).await?;
+                    true
+                }
+            };
+        }
+
+        if modified {
+            increment(&transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Returns the client.
+    #[must_use]
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns the policies with an entity of the table matching the segments, where `*` in a
+    /// row matches any segment.
+    ///
+    /// Each segment is one of two values, so the lookup index is searched rather than scanned.
+    async fn query(
+        &self,
+        table: &str,
+        last: &str,
+        segments: [&str; 3],
+    ) -> Result<Vec<Policy>, Error> {
+        let [scope, noun, value] = segments;
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "{SELECT} WHERE p.id IN (SELECT policy_id FROM {table} \
+                     WHERE scope IN ($1, '*') AND noun IN ($2, '*') AND {last} IN ($3, '*')) \
+                     ORDER BY p.id"
+                ),
+                &[&scope, &noun, &value],
+            )
+            .await?;
+        rows.iter().map(read).collect()
+    }
+}
+
+impl AsyncPolicyStore for PostgresStore {
+    type Error = Error;
+
+    async fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        let row = self
+            .client
+            .query_opt(&format!("{SELECT} WHERE p.id = $1"), &[&id])
+            .await?;
+        row.as_ref().map(read).transpose()
+    }
+
+    async fn insert(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Insert(policy)]).await
+    }
+
+    async fn update(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Update(policy)]).await
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<Option<Policy>, Error> {
+        let transaction = self.client.transaction().await?;
+
+        let row = transaction
+            .query_opt(&format!("{SELECT} WHERE p.id = $1 FOR UPDATE OF p"), &[&id])
+            .await?;
+        let policy = row.as_ref().map(read).transpose()?;
+        if policy.is_some() {
+            remove(&transaction, id).await?;
+            increment(&transaction).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(policy)
+    }
+
+    async fn list(&self) -> Result<Vec<Policy>, Error> {
+        let rows = self
+            .client
+            .query(&format!("{SELECT} ORDER BY p.id"), &[])
+            .await?;
+        rows.iter().map(read).collect()
+    }
+
+    async fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        self.query(
+            "policy_actions",
+            "verb",
+            [&action.scope, &action.noun, &action.verb],
+        )
+        .await
+    }
+
+    async fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        self.query(
+            "policy_principals",
+            "id",
+            [&principal.scope, &principal.noun, &principal.id],
+        )
+        .await
+    }
+
+    async fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        self.query(
+            "policy_resources",
+            "id",
+            [&resource.scope, &resource.noun, &resource.id],
+        )
+        .await
+    }
+
+    async fn revision(&self) -> Result<u64, Error> {
+        let revision: i64 = self
+            .client
+            .query_one("SELECT value FROM policy_revision", &[])
+            .await?
+            .get(0);
+        u64::try_from(revision).map_err(|_| Error::InvalidRevision(revision))
+    }
+}
+
+impl Notifications {
+    /// Waits for a revision newer than the last one received, returning it.
+    ///
+    /// Returns `None` once the connection is closed, after which writes are no longer notified.
+    pub async fn recv(&mut self) -> Option<u64> {
+        self.receiver.changed().await.ok()?;
+        Some(*self.receiver.borrow_and_update())
+    }
+}
+
+/// Returns the revision of a notification, if written to the schema.
+fn revision(payload: &str, schema: &str) -> Option<u64> {
+    let (revision, written) = payload.split_once(':')?;
+    if written == schema {
+        revision.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Increments the revision, notifying the listeners once committed.
+async fn increment(transaction: &Transaction<'_>) -> Result<(), Error> {
+    transaction
+        .execute("UPDATE policy_revision SET value = value + 1", &[])
+        .await?;
+    Ok(())
+}
+
+/// Reads a policy from a row of [`SELECT`].
+fn read(row: &Row) -> Result<Policy, Error> {
+    let id: String = row.try_get(0)?;
+    let malformed = |column: &str, message: String| Error::Encoding {
+        id: id.clone(),
+        message: format!("{column}: {message}"),
+    };
+
+    let effect = match row.try_get::<_, &str>(1)? {
+        "allow" => Effect::Allow,
+        "deny" => Effect::Deny,
+        effect => return Err(malformed("effect", format!("unknown effect `{effect}`"))),
+    };
+    let Json(conditions) = row
+        .try_get::<_, Json<Vec<Condition>>>(2)
+        .map_err(|error| malformed("conditions", error.to_string()))?;
+    let Json(metadata) = row
+        .try_get::<_, Json<Metadata>>(3)
+        .map_err(|error| malformed("metadata", error.to_string()))?;
+    let Json(actions) = row
+        .try_get::<_, Json<Vec<Action>>>(4)
+        .map_err(|error| malformed("actions", error.to_string()))?;
+    let Json(principals) = row
+        .try_get::<_, Json<Vec<Principal>>>(5)
+        .map_err(|error| malformed("principals", error.to_string()))?;
+    let Json(resources) = row
+        .try_get::<_, Json<Vec<Resource>>>(6)
+        .map_err(|error| malformed("resources", error.to_string()))?;
+
+    let mut policy = Policy::new(effect, id).metadata(metadata);
+    policy.actions = actions;
+    policy.conditions = conditions;
+    policy.principals = principals;
+    policy.resources = resources;
+    Ok(policy)
+}
+
+/// Removes the rows of the policy with the id, returning true if it existed.
+async fn remove(transaction: &Transaction<'_>, id: &str) -> Result<bool, Error> {
+    let removed = transaction
+        .execute("DELETE FROM policies WHERE id = $1", &[&id])
+        .await?;
+    Ok(removed > 0)
+}
+
+/// Writes the rows of a policy, returning false if a policy with the id exists.
+async fn write(transaction: &Transaction<'_>, policy: &Policy) -> Result<bool, Error> {
+    let effect = match policy.effect {
+        Effect::Allow => "allow",
+        Effect::Deny => "deny",
+    };
+    let inserted = transaction
+        .execute(
+            "INSERT INTO policies (id, effect, conditions, metadata) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (id) DO NOTHING",
+            &[
+                &policy.id,
+                &effect,
+                &Json(&policy.conditions),
+                &Json(&policy.metadata),
+            ],
+        )
+        .await?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    let statement = transaction
+        .prepare(
+            "INSERT INTO policy_actions (policy_id, position, scope, noun, verb) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .await?;
+    for (position, action) in (0_i32..).zip(&policy.actions) {
+        transaction
+            .execute(
+                &statement,
+                &[
+                    &policy.id,
+                    &position,
+                    &action.scope,
+                    &action.noun,
+                    &action.verb,
+                ],
+            )
+            .await?;
+    }
+
+    let statement = transaction
+        .prepare(
+            "INSERT INTO policy_principals (policy_id, position, scope, noun, id, attributes) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .await?;
+    for (position, principal) in (0_i32..).zip(&policy.principals) {
+        transaction
+            .execute(
+                &statement,
+                &[
+                    &policy.id,
+                    &position,
+                    &principal.scope,
+                    &principal.noun,
+                    &principal.id,
+                    &Json(&principal.attributes),
+                ],
+            )
+            .await?;
+    }
+
+    let statement = transaction
+        .prepare(
+            "INSERT INTO policy_resources (policy_id, position, scope, noun, id, attributes) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .await?;
+    for (position, resource) in (0_i32..).zip(&policy.resources) {
+        transaction
+            .execute(
+                &statement,
+                &[
+                    &policy.id,
+                    &position,
+                    &resource.scope,
+                    &resource.noun,
+                    &resource.id,
+                    &Json(&resource.attributes),
+                ],
+            )
+            .await?;
+    }
+
+    Ok(true)
+}
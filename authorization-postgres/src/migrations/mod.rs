@@ -0,0 +1,115 @@
+//! Migrations.
+//!
+//! Each migration upgrades the schema by one version, tracked in the `policy_migrations` table.
+//! Migrations are append-only: a released migration is never edited.
+
+use tokio_postgres::Client;
+
+use crate::{Error, CHANNEL};
+
+/// Key of the advisory lock serialising the migrations of nodes starting together.
+const LOCK: i64 = 0x6175_7468_7a6e;
+
+/// Migrations, in order. The schema version is the number applied.
+const MIGRATIONS: &[&str] = &[
+    // 1: policies with normalised actions, principals and resources, notifying on each revision
+    // with the schema, as the channel is shared by the database.
+    r"
+    CREATE TABLE policies (
+        id TEXT PRIMARY KEY,
+        effect TEXT NOT NULL,
+        conditions JSONB NOT NULL,
+        metadata JSONB NOT NULL
+    );
+
+    CREATE TABLE policy_actions (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        verb TEXT NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_actions_lookup ON policy_actions (scope, noun, verb, policy_id);
+
+    CREATE TABLE policy_principals (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        id TEXT NOT NULL,
+        attributes JSONB NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_principals_lookup ON policy_principals (scope, noun, id, policy_id);
+
+    CREATE TABLE policy_resources (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        id TEXT NOT NULL,
+        attributes JSONB NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_resources_lookup ON policy_resources (scope, noun, id, policy_id);
+
+    CREATE TABLE policy_revision (
+        singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+        value BIGINT NOT NULL
+    );
+    INSERT INTO policy_revision (value) VALUES (0);
+
+    CREATE FUNCTION notify_policy_revision() RETURNS TRIGGER AS $$
+    BEGIN
+        PERFORM pg_notify('{channel}', NEW.value::TEXT || ':' || TG_TABLE_SCHEMA);
+        RETURN NEW;
+    END;
+    $$ LANGUAGE plpgsql;
+
+    CREATE TRIGGER notify_policy_revision AFTER UPDATE ON policy_revision
+        FOR EACH ROW EXECUTE FUNCTION notify_policy_revision();
+    ",
+];
+
+/// Applies the migrations newer than the schema version of the database, in one transaction.
+///
+/// Returns an error if the database was migrated by a newer release.
+pub(crate) async fn migrate(client: &mut Client) -> Result<(), Error> {
+    let transaction = client.transaction().await?;
+
+    transaction
+        .execute("SELECT pg_advisory_xact_lock($1)", &[&LOCK])
+        .await?;
+    transaction
+        .batch_execute("CREATE TABLE IF NOT EXISTS policy_migrations (version INTEGER PRIMARY KEY)")
+        .await?;
+
+    let version: i32 = transaction
+        .query_one(
+            "SELECT COALESCE(MAX(version), 0) FROM policy_migrations",
+            &[],
+        )
+        .await?
+        .get(0);
+    let version = usize::try_from(version).unwrap_or_default();
+    if version > MIGRATIONS.len() {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let applied = i32::try_from(index + 1).unwrap_or(i32::MAX);
+        transaction
+            .batch_execute(&migration.replace("{channel}", CHANNEL))
+            .await?;
+        transaction
+            .execute(
+                "INSERT INTO policy_migrations (version) VALUES ($1)",
+                &[&applied],
+            )
+            .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::{env, process, time::Duration};
+
+use authorization::{
+    store::{conformance, Change, Snapshot},
+    Action, AsyncPolicyStore, Policy, PolicyStore, Principal, Resource,
+};
+use tokio_postgres::{Config, NoTls};
+
+use crate::{revision, Error, PostgresStore};
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn postgres_store_should_conform() {
+    conformance::run_async(|| async { given_store("conformance").await }).await;
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn apply_should_change_nothing_when_a_change_fails() {
+    // Arrange
+    let mut store = given_store("apply").await;
+    store.insert(given_policy("a")).await.unwrap();
+
+    // Act
+    let result = store
+        .apply([
+            Change::Insert(given_policy("b")),
+            Change::Delete("a".to_string()),
+            Change::Update(given_policy("missing")),
+        ])
+        .await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::NotFound(id)) if id == "missing"));
+    assert_eq!(AsyncPolicyStore::revision(&store).await.unwrap(), 1);
+    assert_eq!(ids(&AsyncPolicyStore::list(&store).await.unwrap()), ["a"]);
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn get_should_return_policy_with_attributes_and_order() {
+    // Arrange
+    let mut store = given_store("get").await;
+    let mut principal = "world:player:1".parse::<Principal>().unwrap();
+    principal.attributes.insert("level".to_string(), 10.into());
+    let policy = Policy::deny("a")
+        .action("world:loot:take".parse::<Action>().unwrap())
+        .action("world:loot:drop".parse::<Action>().unwrap())
+        .principal(principal)
+        .resource("world:loot:*".parse::<Resource>().unwrap());
+    store.insert(policy.clone()).await.unwrap();
+
+    // Act
+    let result = AsyncPolicyStore::get(&store, "a").await.unwrap();
+
+    // Assert
+    assert_eq!(result, Some(policy));
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn connect_should_notify_other_nodes_of_writes() {
+    // Arrange
+    let config = given_schema("notify").await;
+    let (mut writer, _) = PostgresStore::connect(&config, NoTls).await.unwrap();
+    let (reader, mut notifications) = PostgresStore::connect(&config, NoTls).await.unwrap();
+    let mut snapshot = Snapshot::load(reader).await.unwrap();
+
+    // Act
+    writer.insert(given_policy("a")).await.unwrap();
+    let revision = tokio::time::timeout(Duration::from_secs(10), notifications.recv())
+        .await
+        .unwrap();
+    let refreshed = snapshot.refresh().await.unwrap();
+
+    // Assert
+    assert_eq!(revision, Some(1));
+    assert!(refreshed);
+    assert_eq!(
+        PolicyStore::get(&snapshot, "a").unwrap(),
+        Some(given_policy("a"))
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn connect_should_ignore_writes_to_other_schemas() {
+    // Arrange
+    let (mut writer, _) = PostgresStore::connect(&given_schema("written").await, NoTls)
+        .await
+        .unwrap();
+    let (_reader, mut notifications) = PostgresStore::connect(&given_schema("other").await, NoTls)
+        .await
+        .unwrap();
+
+    // Act
+    writer.insert(given_policy("a")).await.unwrap();
+    let result = tokio::time::timeout(Duration::from_millis(500), notifications.recv()).await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn revision_should_return_error_when_negative() {
+    // Arrange
+    let store = given_store("negative").await;
+    store
+        .client()
+        .execute("UPDATE policy_revision SET value = -1", &[])
+        .await
+        .unwrap();
+
+    // Act
+    let result = store.revision().await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::InvalidRevision(-1))));
+}
+
+#[test]
+fn revision_should_parse_payload_of_schema() {
+    // Act
+    let result = (
+        revision("12:tenant:a", "tenant:a"),
+        revision("12:tenant_b", "tenant:a"),
+        revision("12", "tenant:a"),
+    );
+
+    // Assert
+    assert_eq!(result, (Some(12), None, None));
+}
+
+#[tokio::test]
+#[ignore = "requires a PostgreSQL server at AUTHORIZATION_POSTGRES_URL"]
+async fn connect_should_return_error_when_schema_is_newer() {
+    // Arrange
+    let config = given_schema("newer").await;
+    let (store, _) = PostgresStore::connect(&config, NoTls).await.unwrap();
+    store
+        .client()
+        .execute("INSERT INTO policy_migrations (version) VALUES (99)", &[])
+        .await
+        .unwrap();
+    drop(store);
+
+    // Act
+    let result = PostgresStore::connect(&config, NoTls).await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::UnsupportedVersion(99))));
+}
+
+/// Returns the config of an empty schema of the test server, dropping any left by a previous run.
+async fn given_schema(name: &str) -> Config {
+    let url = env::var("AUTHORIZATION_POSTGRES_URL").expect("AUTHORIZATION_POSTGRES_URL");
+    let schema = format!("authorization_test_{}_{name}", process::id());
+
+    let (client, connection) = tokio_postgres::connect(&url, NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema}"
+        ))
+        .await
+        .unwrap();
+
+    let mut config = url.parse::<Config>().unwrap();
+    config.options(format!("-c search_path={schema}"));
+    config
+}
+
+async fn given_store(name: &str) -> PostgresStore {
+    let config = given_schema(name).await;
+    PostgresStore::connect(&config, NoTls).await.unwrap().0
+}
+
+fn given_policy(id: &str) -> Policy {
+    Policy::allow(id)
+        .action("*:*:*".parse::<Action>().unwrap())
+        .principal("*:*:*".parse::<Principal>().unwrap())
+        .resource("*:*:*".parse::<Resource>().unwrap())
+}
+
+fn ids(policies: &[Policy]) -> Vec<&str> {
+    policies.iter().map(|policy| policy.id.as_str()).collect()
+}
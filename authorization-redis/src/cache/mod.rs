@@ -0,0 +1,179 @@
+//! Cache.
+//!
+//! A [`DecisionCache`] shares the decisions evaluated by the nodes of a service, keyed by the
+//! revision of the policies and a canonical hash of the [`Context`].
+//!
+//! A write to the policies increases their revision, so later lookups miss every decision made
+//! before it, which then expire.
+
+use std::{fmt::Write, time::Duration};
+
+use authorization::{Context, Policy};
+use redis::aio::MultiplexedConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::Error;
+
+/// Decision cache.
+#[derive(Debug, Clone)]
+pub struct DecisionCache {
+    /// Connection.
+    connection: MultiplexedConnection,
+
+    /// Key prefix.
+    prefix: String,
+
+    /// Time to live of each decision.
+    ttl: Duration,
+}
+
+/// Decision.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Decision {
+    /// Policy that matched the context, or `None` if none did.
+    #[serde(rename = "policy")]
+    pub policy: Option<Policy>,
+}
+
+impl DecisionCache {
+    /// Creates a cache over a connection, with keys starting with a prefix, keeping each decision
+    /// for a time to live.
+    #[must_use]
+    pub fn new(
+        connection: MultiplexedConnection,
+        prefix: impl Into<String>,
+        ttl: Duration,
+    ) -> Self {
+        DecisionCache {
+            connection,
+            prefix: prefix.into(),
+            ttl,
+        }
+    }
+
+    /// Returns the decision for a context made at a revision of the policies, if cached.
+    ///
+    /// A decision which cannot be decoded, such as one cached by an incompatible release, is a
+    /// miss.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context cannot be encoded, or Redis cannot be read.
+    pub async fn get(&self, revision: u64, context: &Context) -> Result<Option<Decision>, Error> {
+        let json: Option<String> = redis::cmd("GET")
+            .arg(self.key(revision, context)?)
+            .query_async(&mut self.connection.clone())
+            .await?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Caches the decision for a context made at a revision of the policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, caching nothing, if the context or decision cannot be encoded, or Redis
+    /// cannot be written.
+    pub async fn insert(
+        &self,
+        revision: u64,
+        context: &Context,
+        decision: &Decision,
+    ) -> Result<(), Error> {
+        let key = self.key(revision, context)?;
+        let json = serde_json::to_string(decision).map_err(|error| Error::Encoding {
+            id: decision
+                .policy
+                .as_ref()
+                .map(|policy| policy.id.clone())
+                .unwrap_or_default(),
+            message: error.to_string(),
+        })?;
+        let ttl = u64::try_from(self.ttl.as_millis())
+            .unwrap_or(u64::MAX)
+            .max(1);
+
+        redis::cmd("SET")
+            .arg(key)
+            .arg(json)
+            .arg("PX")
+            .arg(ttl)
+            .query_async::<()>(&mut self.connection.clone())
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the key of a context at a revision.
+    ///
+    /// Decisions are read and written one key at a time, so their keys are not hash tagged and
+    /// spread across the slots of a cluster.
+    fn key(&self, revision: u64, context: &Context) -> Result<String, Error> {
+        Ok(format!(
+            "{}:decisions:{revision}:{}",
+            self.prefix,
+            key(context)?
+        ))
+    }
+}
+
+impl From<Option<&Policy>> for Decision {
+    fn from(policy: Option<&Policy>) -> Self {
+        Decision {
+            policy: policy.cloned(),
+        }
+    }
+}
+
+/// Returns the canonical hash of a context, as lowercase hex.
+///
+/// Contexts which are equal have the same hash, whatever the order their attributes and data
+/// were inserted in.
+///
+/// # Errors
+///
+/// Returns an error if the context cannot be encoded into JSON.
+pub fn key(context: &Context) -> Result<String, Error> {
+    let value = serde_json::to_value(context).map_err(|error| Error::Context(error.to_string()))?;
+    let mut json = String::new();
+    canonicalize(&value, &mut json);
+
+    Ok(Sha256::digest(json.as_bytes())
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        }))
+}
+
+/// Writes JSON with the keys of every object sorted.
+fn canonicalize(value: &Value, json: &mut String) {
+    match value {
+        Value::Array(values) => {
+            json.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                canonicalize(value, json);
+            }
+            json.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+
+            json.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                json.push_str(&Value::from(key.as_str()).to_string());
+                json.push(':');
+                canonicalize(value, json);
+            }
+            json.push('}');
+        }
+        value => json.push_str(&value.to_string()),
+    }
+}
@@ -0,0 +1,335 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization Redis.
+//!
+//! An [`AsyncPolicyStore`] persisted in Redis, and a [`DecisionCache`] shared by the nodes of a
+//! service.
+//!
+//! The store keeps the policies in a hash, and the ids of the policies with an action, principal
+//! or resource of a scope and noun in a set, so a lookup reads four sets rather than every
+//! policy. Writes run as scripts, updating the sets and increasing the revision atomically.
+//!
+//! Keys start with a prefix, so several stores can share a database. The keys of a store start
+//! with the prefix in braces, a hash tag, so that they share one slot of a Redis Cluster, as its
+//! scripts read and write keys they compute. A store is then served by a single node of the
+//! cluster, while the decisions of a [`DecisionCache`] spread across every node.
+
+mod cache;
+mod scripts;
+#[cfg(test)]
+mod tests;
+
+pub use cache::{key, Decision, DecisionCache};
+
+use std::fmt::{self, Display};
+
+use authorization::{store::Change, Action, AsyncPolicyStore, Policy, Principal, Resource};
+use redis::{aio::MultiplexedConnection, RedisError, Script};
+
+/// Redis store.
+#[derive(Debug, Clone)]
+pub struct RedisStore {
+    /// Script applying changes.
+    apply: Script,
+
+    /// Connection.
+    connection: MultiplexedConnection,
+
+    /// Key prefix.
+    prefix: String,
+
+    /// Script reading the policies of sets.
+    query: Script,
+}
+
+/// Error.
+#[derive(Debug)]
+pub enum Error {
+    /// A policy with the id already exists.
+    Conflict(String),
+
+    /// A context cannot be encoded into JSON.
+    Context(String),
+
+    /// A policy cannot be decoded from its JSON.
+    Encoding {
+        /// Policy id.
+        id: String,
+
+        /// Message.
+        message: String,
+    },
+
+    /// No policy with the id exists.
+    NotFound(String),
+
+    /// Redis failed.
+    Redis(RedisError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Conflict(id) => write!(f, "policy `{id}` already exists"),
+            Error::Context(message) => write!(f, "context cannot be encoded: {message}"),
+            Error::Encoding { id, message } => write!(f, "policy `{id}` is malformed: {message}"),
+            Error::NotFound(id) => write!(f, "policy `{id}` does not exist"),
+            Error::Redis(error) => write!(f, "redis: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Redis(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<RedisError> for Error {
+    fn from(error: RedisError) -> Self {
+        Error::Redis(error)
+    }
+}
+
+impl RedisStore {
+    /// Creates a store over a connection, with keys starting with a prefix.
+    #[must_use]
+    pub fn new(connection: MultiplexedConnection, prefix: impl Into<String>) -> Self {
+        RedisStore {
+            apply: Script::new(scripts::APPLY),
+            connection,
+            prefix: prefix.into(),
+            query: Script::new(scripts::QUERY),
+        }
+    }
+
+    /// Returns the key prefix.
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Applies changes atomically, increasing the revision once if any policy changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, applying none of the changes, if any change fails.
+    pub async fn apply(&mut self, changes: impl IntoIterator<Item = Change>) -> Result<(), Error> {
+        self.invoke(changes).await.map(drop)
+    }
+
+    /// Invokes the apply script, returning the previous policy of each change.
+    async fn invoke(
+        &mut self,
+        changes: impl IntoIterator<Item = Change>,
+    ) -> Result<Vec<Option<Policy>>, Error> {
+        let mut invocation = self.apply.key(self.key("policies"));
+        invocation.key(self.key("revision")).arg(self.tag());
+        for change in changes {
+            let (change, id, json) = match change {
+                Change::Delete(id) => ("delete", id, String::new()),
+                Change::Insert(policy) => ("insert", policy.id.clone(), encode(&policy)?),
+                Change::Update(policy) => ("update", policy.id.clone(), encode(&policy)?),
+            };
+            invocation.arg(change).arg(id).arg(json);
+        }
+
+        let reply: Vec<String> = invocation.invoke_async(&mut self.connection).await?;
+        match reply.split_first() {
+            Some((status, [id])) if status == "conflict" => Err(Error::Conflict(id.clone())),
+            Some((status, [id])) if status == "not_found" => Err(Error::NotFound(id.clone())),
+            Some((_, previous)) => previous
+                .iter()
+                .map(|json| (!json.is_empty()).then(|| decode(json)).transpose())
+                .collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the policies with an entity of the kind matching the segments, where `*` in a
+    /// policy matches any segment.
+    ///
+    /// The sets of the scope and noun, and of their wildcards, hold every candidate.
+    async fn query(&self, kind: Kind, segments: [&str; 3]) -> Result<Vec<Policy>, Error> {
+        let [scope, noun, _] = segments;
+
+        let mut invocation = self.query.key(self.key("policies"));
+        for (scope, noun) in [(scope, noun), (scope, "*"), ("*", noun), ("*", "*")] {
+            invocation.key(self.key(&format!("{}:{scope}:{noun}", kind.name())));
+        }
+        let reply: Vec<Option<String>> = invocation
+            .invoke_async(&mut self.connection.clone())
+            .await?;
+
+        let mut policies = reply
+            .iter()
+            .flatten()
+            .map(|json| decode(json))
+            .collect::<Result<Vec<_>, _>>()?;
+        policies.retain(|policy| {
+            kind.entities(policy).any(|entity| {
+                entity
+                    .iter()
+                    .zip(segments)
+                    .all(|(segment, value)| matches(segment, value))
+            })
+        });
+        policies.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(policies)
+    }
+
+    /// Returns the key with the hash tagged prefix.
+    fn key(&self, key: &str) -> String {
+        format!("{}:{key}", self.tag())
+    }
+
+    /// Returns the prefix as a hash tag, `{prefix}`.
+    fn tag(&self) -> String {
+        format!("{{{}}}", self.prefix)
+    }
+}
+
+impl AsyncPolicyStore for RedisStore {
+    type Error = Error;
+
+    async fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        let json: Option<String> = redis::cmd("HGET")
+            .arg(self.key("policies"))
+            .arg(id)
+            .query_async(&mut self.connection.clone())
+            .await?;
+        json.as_deref().map(decode).transpose()
+    }
+
+    async fn insert(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Insert(policy)]).await
+    }
+
+    async fn update(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Update(policy)]).await
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<Option<Policy>, Error> {
+        let previous = self.invoke([Change::Delete(id.to_string())]).await?;
+        Ok(previous.into_iter().next().flatten())
+    }
+
+    async fn list(&self) -> Result<Vec<Policy>, Error> {
+        let policies: Vec<(String, String)> = redis::cmd("HGETALL")
+            .arg(self.key("policies"))
+            .query_async(&mut self.connection.clone())
+            .await?;
+
+        let mut policies = policies
+            .iter()
+            .map(|(_, json)| decode(json))
+            .collect::<Result<Vec<_>, _>>()?;
+        policies.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(policies)
+    }
+
+    async fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        self.query(Kind::Actions, [&action.scope, &action.noun, &action.verb])
+            .await
+    }
+
+    async fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        self.query(
+            Kind::Principals,
+            [&principal.scope, &principal.noun, &principal.id],
+        )
+        .await
+    }
+
+    async fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        self.query(
+            Kind::Resources,
+            [&resource.scope, &resource.noun, &resource.id],
+        )
+        .await
+    }
+
+    async fn revision(&self) -> Result<u64, Error> {
+        let revision: Option<u64> = redis::cmd("GET")
+            .arg(self.key("revision"))
+            .query_async(&mut self.connection.clone())
+            .await?;
+        Ok(revision.unwrap_or_default())
+    }
+}
+
+/// Kind of entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Actions.
+    Actions,
+
+    /// Principals.
+    Principals,
+
+    /// Resources.
+    Resources,
+}
+
+impl Kind {
+    /// Returns the name, as in the keys of the sets and the JSON of policies.
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Actions => "actions",
+            Kind::Principals => "principals",
+            Kind::Resources => "resources",
+        }
+    }
+
+    /// Returns the segments of the entities of the kind of a policy.
+    fn entities(self, policy: &Policy) -> Box<dyn Iterator<Item = [&str; 3]> + '_> {
+        match self {
+            Kind::Actions => Box::new(
+                policy
+                    .actions
+                    .iter()
+                    .map(|action| [&*action.scope, &*action.noun, &*action.verb]),
+            ),
+            Kind::Principals => Box::new(
+                policy
+                    .principals
+                    .iter()
+                    .map(|principal| [&*principal.scope, &*principal.noun, &*principal.id]),
+            ),
+            Kind::Resources => Box::new(
+                policy
+                    .resources
+                    .iter()
+                    .map(|resource| [&*resource.scope, &*resource.noun, &*resource.id]),
+            ),
+        }
+    }
+}
+
+/// Decodes a policy from its JSON.
+fn decode(json: &str) -> Result<Policy, Error> {
+    serde_json::from_str(json).map_err(|error| Error::Encoding {
+        id: serde_json::from_str::<serde_json::Value>(json)
+            .ok()
+            .and_then(|value| value.get("id")?.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        message: error.to_string(),
+    })
+}
+
+/// Encodes a policy into its JSON.
+fn encode(policy: &Policy) -> Result<String, Error> {
+    serde_json::to_string(policy).map_err(|error| Error::Encoding {
+        id: policy.id.clone(),
+        message: error.to_string(),
+    })
+}
+
+/// Returns true if a segment of a policy matches a value, where `*` matches any value.
+fn matches(segment: &str, value: &str) -> bool {
+    segment == "*" || segment == value
+}
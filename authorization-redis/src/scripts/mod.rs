@@ -0,0 +1,86 @@
+//! Scripts.
+//!
+//! Redis runs each script atomically, so other clients never see a write half-applied. A script
+//! is not rolled back when it fails, so [`APPLY`] checks every change before writing any.
+
+/// Applies changes, increasing the revision once if any policy changed.
+///
+/// `KEYS`: the policies hash, then the revision.
+/// `ARGV`: the hash tagged key prefix, then `delete`, `insert` or `update`, the id and the policy
+/// JSON (empty for deletes) of each change.
+///
+/// The keys of the sets are computed from the prefix rather than passed in `KEYS`, as they depend
+/// on the previous policies, so they are only in the slot of `KEYS` when the prefix is a hash tag.
+///
+/// Returns `conflict` or `not_found` with the id of the failed change, or `ok` with the previous
+/// JSON of each change, empty if none.
+pub(crate) const APPLY: &str = r"
+local policies, revision, prefix = KEYS[1], KEYS[2], ARGV[1]
+
+local present = {}
+for i = 2, #ARGV, 3 do
+    local change, id = ARGV[i], ARGV[i + 1]
+    if present[id] == nil then
+        present[id] = redis.call('HEXISTS', policies, id) == 1
+    end
+
+    if change == 'insert' then
+        if present[id] then
+            return {'conflict', id}
+        end
+        present[id] = true
+    elseif change == 'update' then
+        if not present[id] then
+            return {'not_found', id}
+        end
+    else
+        present[id] = false
+    end
+end
+
+local function index(id, json, command)
+    local policy = cjson.decode(json)
+    for _, kind in ipairs({'actions', 'principals', 'resources'}) do
+        for _, entity in ipairs(policy[kind]) do
+            redis.call(command, prefix .. ':' .. kind .. ':' .. entity.scope .. ':' .. entity.noun, id)
+        end
+    end
+end
+
+local result = {'ok'}
+local modified = false
+for i = 2, #ARGV, 3 do
+    local change, id, json = ARGV[i], ARGV[i + 1], ARGV[i + 2]
+
+    local previous = redis.call('HGET', policies, id)
+    if previous then
+        index(id, previous, 'SREM')
+        redis.call('HDEL', policies, id)
+        modified = true
+    end
+
+    if change ~= 'delete' then
+        index(id, json, 'SADD')
+        redis.call('HSET', policies, id, json)
+        modified = true
+    end
+
+    table.insert(result, previous or '')
+end
+
+if modified then
+    redis.call('INCR', revision)
+end
+return result
+";
+
+/// Returns the JSON of the policies in any of the sets.
+///
+/// `KEYS`: the policies hash, then the sets.
+pub(crate) const QUERY: &str = r"
+local ids = redis.call('SUNION', unpack(KEYS, 2))
+if #ids == 0 then
+    return {}
+end
+return redis.call('HMGET', KEYS[1], unpack(ids))
+";
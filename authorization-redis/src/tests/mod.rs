@@ -0,0 +1,205 @@
+use std::{env, process, time::Duration};
+
+use authorization::{
+    store::{conformance, Change},
+    Action, AsyncPolicyStore, Context, Policy, Principal, Resource, Value,
+};
+use redis::aio::MultiplexedConnection;
+
+use crate::{key, Decision, DecisionCache, Error, RedisStore};
+
+#[test]
+fn key_should_ignore_insertion_order() {
+    // Arrange
+    let mut a = given_context();
+    a.data.insert("region".to_string(), Value::from("eu"));
+    a.data.insert("tier".to_string(), Value::from("gold"));
+    let mut b = given_context();
+    b.data.insert("tier".to_string(), Value::from("gold"));
+    b.data.insert("region".to_string(), Value::from("eu"));
+
+    // Act
+    let result = (key(&a).unwrap(), key(&b).unwrap());
+
+    // Assert
+    assert_eq!(result.0, result.1);
+    assert_eq!(result.0.len(), 64);
+}
+
+#[test]
+fn key_should_differ_when_context_differs() {
+    // Arrange
+    let a = given_context();
+    let mut b = given_context();
+    b.principal.id = "2".to_string();
+
+    // Act
+    let result = (key(&a).unwrap(), key(&b).unwrap());
+
+    // Assert
+    assert_ne!(result.0, result.1);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server at AUTHORIZATION_REDIS_URL"]
+async fn redis_store_should_conform() {
+    conformance::run_async(|| async { given_store("conformance").await }).await;
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server at AUTHORIZATION_REDIS_URL"]
+async fn apply_should_change_nothing_when_a_change_fails() {
+    // Arrange
+    let mut store = given_store("apply").await;
+    store.insert(given_policy("a")).await.unwrap();
+
+    // Act
+    let result = store
+        .apply([
+            Change::Insert(given_policy("b")),
+            Change::Delete("a".to_string()),
+            Change::Update(given_policy("missing")),
+        ])
+        .await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::NotFound(id)) if id == "missing"));
+    assert_eq!(store.revision().await.unwrap(), 1);
+    assert_eq!(ids(&store.list().await.unwrap()), ["a"]);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server at AUTHORIZATION_REDIS_URL"]
+async fn apply_should_hash_tag_every_key() {
+    // Arrange
+    let mut store = given_store("slot").await;
+    let mut connection = given_connection().await;
+
+    // Act
+    store.insert(given_policy("a")).await.unwrap();
+
+    // Assert
+    let mut keys: Vec<String> = redis::cmd("KEYS")
+        .arg(format!("*{}*", store.prefix()))
+        .query_async(&mut connection)
+        .await
+        .unwrap();
+    keys.sort();
+    let tag = format!("{{{}}}", store.prefix());
+    assert_eq!(
+        keys,
+        [
+            format!("{tag}:actions:*:*"),
+            format!("{tag}:policies"),
+            format!("{tag}:principals:*:*"),
+            format!("{tag}:resources:*:*"),
+            format!("{tag}:revision"),
+        ]
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server at AUTHORIZATION_REDIS_URL"]
+async fn decision_cache_should_miss_when_revision_changes() {
+    // Arrange
+    let mut store = given_store("decisions").await;
+    let cache = DecisionCache::new(
+        given_connection().await,
+        store.prefix(),
+        Duration::from_mins(1),
+    );
+    let context = given_context();
+    let revision = store.revision().await.unwrap();
+    let decision = Decision {
+        policy: Some(given_policy("a")),
+    };
+    cache.insert(revision, &context, &decision).await.unwrap();
+
+    // Act
+    let hit = cache.get(revision, &context).await.unwrap();
+    store.insert(given_policy("a")).await.unwrap();
+    let miss = cache
+        .get(store.revision().await.unwrap(), &context)
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(hit, Some(decision));
+    assert_eq!(miss, None);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server at AUTHORIZATION_REDIS_URL"]
+async fn decision_cache_should_expire_decisions() {
+    // Arrange
+    let cache = DecisionCache::new(
+        given_connection().await,
+        given_prefix("expire"),
+        Duration::from_millis(50),
+    );
+    let context = given_context();
+    cache
+        .insert(0, &context, &Decision { policy: None })
+        .await
+        .unwrap();
+
+    // Act
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let result = cache.get(0, &context).await.unwrap();
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+async fn given_connection() -> MultiplexedConnection {
+    let url = env::var("AUTHORIZATION_REDIS_URL").expect("AUTHORIZATION_REDIS_URL");
+    redis::Client::open(url)
+        .unwrap()
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap()
+}
+
+fn given_context() -> Context {
+    Context::new(
+        "world:loot:take".parse::<Action>().unwrap(),
+        "world:player:1".parse::<Principal>().unwrap(),
+        "world:loot:1".parse::<Resource>().unwrap(),
+    )
+}
+
+fn given_policy(id: &str) -> Policy {
+    Policy::allow(id)
+        .action("*:*:*".parse::<Action>().unwrap())
+        .principal("*:*:*".parse::<Principal>().unwrap())
+        .resource("*:*:*".parse::<Resource>().unwrap())
+}
+
+fn given_prefix(name: &str) -> String {
+    format!("authorization-test:{}:{name}", process::id())
+}
+
+/// Returns an empty store of the test server, deleting any keys left by a previous run.
+async fn given_store(name: &str) -> RedisStore {
+    let mut connection = given_connection().await;
+    let prefix = given_prefix(name);
+
+    let keys: Vec<String> = redis::cmd("KEYS")
+        .arg(format!("{{{prefix}}}:*"))
+        .query_async(&mut connection)
+        .await
+        .unwrap();
+    if !keys.is_empty() {
+        redis::cmd("DEL")
+            .arg(keys)
+            .query_async::<()>(&mut connection)
+            .await
+            .unwrap();
+    }
+
+    RedisStore::new(connection, prefix)
+}
+
+fn ids(policies: &[Policy]) -> Vec<&str> {
+    policies.iter().map(|policy| policy.id.as_str()).collect()
+}
@@ -0,0 +1,167 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization SQL.
+//!
+//! Translates a residual [`Filter`] into a parameterised SQL `WHERE` clause.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use authorization::partial::{Field, Filter};
+
+/// Mapping.
+///
+/// Maps the fields of a resource and data keys onto columns.
+///
+/// Column names are inserted into the SQL verbatim and must come from a trusted source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    /// Data key columns.
+    ///
+    /// A data column holds a single value per row, and may be `NULL` when the key is absent.
+    pub data: HashMap<String, String>,
+
+    /// Id column.
+    pub id: String,
+
+    /// Noun column.
+    pub noun: String,
+
+    /// Placeholder.
+    pub placeholder: Placeholder,
+
+    /// Scope column.
+    pub scope: String,
+}
+
+/// Placeholder style of a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// Numbered, such as `$1` and `$2`.
+    Dollar,
+
+    /// Positional, `?`.
+    Question,
+}
+
+/// Where.
+///
+/// A parameterised SQL fragment, suitable for a `WHERE` clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Where {
+    /// Parameters, in placeholder order.
+    pub parameters: Vec<String>,
+
+    /// SQL.
+    pub sql: String,
+}
+
+/// Error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The filter references a data key without a column.
+    UnmappedDataKey(String),
+
+    /// The filter contains an expression which cannot be translated.
+    UnsupportedExpression,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnmappedDataKey(key) => write!(f, "unmapped data key `{key}`"),
+            Error::UnsupportedExpression => write!(f, "unsupported expression"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Translates a [`Filter`] into a [`Where`].
+///
+/// # Errors
+///
+/// Returns [`Error`] if the filter contains an unresolved expression or an unmapped data key.
+pub fn translate(filter: &Filter, mapping: &Mapping) -> Result<Where, Error> {
+    let mut parameters = Vec::new();
+    let sql = translate_filter(filter, mapping, &mut parameters)?;
+
+    Ok(Where { parameters, sql })
+}
+
+/// Translates a [`Filter`] into SQL, appending its parameters.
+fn translate_filter(
+    filter: &Filter,
+    mapping: &Mapping,
+    parameters: &mut Vec<String>,
+) -> Result<String, Error> {
+    Ok(match filter {
+        Filter::And(filters) => join(filters, " AND ", mapping, parameters)?,
+        Filter::Bool(true) => "1 = 1".to_string(),
+        Filter::Bool(false) => "1 = 0".to_string(),
+        Filter::Equals(field, value) => {
+            let column = match field {
+                Field::Id => &mapping.id,
+                Field::Noun => &mapping.noun,
+                Field::Scope => &mapping.scope,
+            };
+            format!("{column} = {}", parameter(value, mapping, parameters))
+        }
+        Filter::Expr(_) => return Err(Error::UnsupportedExpression),
+        Filter::Not(filter) => format!("NOT ({})", translate_filter(filter, mapping, parameters)?),
+        Filter::Or(filters) => join(filters, " OR ", mapping, parameters)?,
+        Filter::Subset(key, values) => {
+            let column = mapping
+                .data
+                .get(key)
+                .ok_or_else(|| Error::UnmappedDataKey(key.clone()))?;
+
+            if values.is_empty() {
+                return Ok("1 = 0".to_string());
+            }
+
+            // `Set` is a `HashSet` with `std`, so sort to bind parameters in a stable order.
+            let mut values = values.iter().collect::<Vec<_>>();
+            values.sort();
+
+            let placeholders = values
+                .into_iter()
+                .map(|value| parameter(value, mapping, parameters))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("({column} IS NOT NULL AND {column} IN ({placeholders}))")
+        }
+    })
+}
+
+/// Translates and joins filters with an operator.
+fn join(
+    filters: &[Filter],
+    operator: &str,
+    mapping: &Mapping,
+    parameters: &mut Vec<String>,
+) -> Result<String, Error> {
+    let filters = filters
+        .iter()
+        .map(|filter| translate_filter(filter, mapping, parameters))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(format!("({})", filters.join(operator)))
+}
+
+/// Appends a parameter, returning its placeholder.
+fn parameter(value: &str, mapping: &Mapping, parameters: &mut Vec<String>) -> String {
+    parameters.push(value.to_string());
+
+    match mapping.placeholder {
+        Placeholder::Dollar => format!("${}", parameters.len()),
+        Placeholder::Question => "?".to_string(),
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, HashSet};
+
+use authorization::{
+    partial::{Field, Filter},
+    partial_evaluate, Action, Condition, Effect, Expression, PartialContext, Policy, Principal,
+    Resource,
+};
+use rusqlite::{params_from_iter, Connection};
+
+use crate::{translate, Error, Mapping, Placeholder, Where};
+
+#[test]
+fn translate_should_return_parameterised_sql() {
+    // Arrange
+    let filter = Filter::And(vec![
+        Filter::Equals(Field::Noun, "user".to_string()),
+        Filter::Not(Box::new(Filter::Equals(Field::Id, "1".to_string()))),
+    ]);
+
+    // Act
+    let result = translate(&filter, &given_mapping(Placeholder::Dollar));
+
+    // Assert
+    assert_eq!(
+        result,
+        Ok(Where {
+            parameters: vec!["user".to_string(), "1".to_string()],
+            sql: "(resource_noun = $1 AND NOT (resource_id = $2))".to_string(),
+        })
+    );
+}
+
+#[test]
+fn translate_should_return_error_when_data_key_is_unmapped() {
+    // Arrange
+    let filter = Filter::Subset("unknown".to_string(), HashSet::new());
+
+    // Act
+    let result = translate(&filter, &given_mapping(Placeholder::Question));
+
+    // Assert
+    assert_eq!(result, Err(Error::UnmappedDataKey("unknown".to_string())));
+}
+
+#[test]
+fn sqlite_should_return_allowed_users() {
+    // Arrange
+    let connection = given_database();
+    let policies = [
+        given_policy(
+            Effect::Allow,
+            "allow",
+            given_resource("identity", "user", "*"),
+        ),
+        given_policy(
+            Effect::Deny,
+            "deny",
+            given_resource("identity", "user", "2"),
+        ),
+    ];
+
+    // Act
+    let result = query(&connection, &policies);
+
+    // Assert
+    assert_eq!(result, vec!["1", "3", "4"]);
+}
+
+#[test]
+fn sqlite_should_return_users_matching_unresolved_condition_key() {
+    // Arrange
+    let connection = given_database();
+    let policies = [
+        given_policy(Effect::Allow, "allow", given_resource("*", "*", "*"))
+            .when(Condition::new().string_equals("user:region", ["eu", "uk"])),
+    ];
+
+    // Act
+    let result = query(&connection, &policies);
+
+    // Assert
+    assert_eq!(result, vec!["1", "3"]);
+}
+
+#[test]
+fn sqlite_should_return_users_not_matching_denied_condition_key() {
+    // Arrange
+    let connection = given_database();
+    let policies = [
+        given_policy(
+            Effect::Allow,
+            "allow",
+            given_resource("identity", "user", "*"),
+        ),
+        given_policy(Effect::Deny, "deny", given_resource("*", "*", "*"))
+            .when(Condition::new().string_equals("user:region", ["us"])),
+    ];
+
+    // Act
+    let result = query(&connection, &policies);
+
+    // Assert
+    assert_eq!(result, vec!["1", "3", "4"]);
+}
+
+#[test]
+fn sqlite_should_return_users_matching_expression() {
+    // Arrange
+    let connection = given_database();
+    let policies = [given_policy(
+        Effect::Allow,
+        "allow",
+        given_resource("identity", "user", "*"),
+    )
+    .when(
+        r#"resource.id == principal.id || resource.id in ["3", "4"]"#
+            .parse::<Expression>()
+            .unwrap(),
+    )];
+
+    // Act
+    let result = query(&connection, &policies);
+
+    // Assert
+    assert_eq!(result, vec!["1", "3", "4"]);
+}
+
+#[test]
+fn sqlite_should_return_no_users_when_not_allowed() {
+    // Arrange
+    let connection = given_database();
+    let policies = [given_policy(
+        Effect::Allow,
+        "allow",
+        given_resource("identity", "group", "*"),
+    )];
+
+    // Act
+    let result = query(&connection, &policies);
+
+    // Assert
+    assert!(result.is_empty());
+}
+
+fn given_action() -> Action {
+    Action::new("identity", "user", "list")
+}
+
+fn given_database() -> Connection {
+    let connection = Connection::open_in_memory().unwrap();
+
+    connection
+        .execute_batch(
+            "CREATE TABLE users (id TEXT NOT NULL, noun TEXT NOT NULL, scope TEXT NOT NULL, region TEXT);
+            INSERT INTO users VALUES ('1', 'user', 'identity', 'eu');
+            INSERT INTO users VALUES ('2', 'user', 'identity', 'us');
+            INSERT INTO users VALUES ('3', 'user', 'identity', 'uk');
+            INSERT INTO users VALUES ('4', 'user', 'identity', NULL);",
+        )
+        .unwrap();
+
+    connection
+}
+
+fn given_mapping(placeholder: Placeholder) -> Mapping {
+    Mapping {
+        data: HashMap::from([("user:region".to_string(), "region".to_string())]),
+        id: "resource_id".to_string(),
+        noun: "resource_noun".to_string(),
+        placeholder,
+        scope: "resource_scope".to_string(),
+    }
+}
+
+fn given_policy(effect: Effect, id: &str, resource: Resource) -> Policy {
+    Policy::new(effect, id)
+        .action(given_action())
+        .principal(given_principal())
+        .resource(resource)
+}
+
+fn given_principal() -> Principal {
+    Principal::new("identity", "user", "1")
+}
+
+fn given_resource(scope: &str, noun: &str, id: &str) -> Resource {
+    Resource::new(scope, noun, id)
+}
+
+fn query(connection: &Connection, policies: &[Policy]) -> Vec<String> {
+    let context = PartialContext {
+        action: given_action(),
+        data: HashMap::new(),
+        principal: given_principal(),
+    };
+    let filter = partial_evaluate(&context, policies);
+    let mapping = Mapping {
+        data: HashMap::from([("user:region".to_string(), "region".to_string())]),
+        id: "id".to_string(),
+        noun: "noun".to_string(),
+        placeholder: Placeholder::Question,
+        scope: "scope".to_string(),
+    };
+    let clause = translate(&filter, &mapping).unwrap();
+
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT id FROM users WHERE {} ORDER BY id",
+            clause.sql
+        ))
+        .unwrap();
+
+    statement
+        .query_map(params_from_iter(clause.parameters), |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
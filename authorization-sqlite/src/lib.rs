@@ -0,0 +1,467 @@
+#![deny(clippy::pedantic, missing_docs)]
+#![forbid(unsafe_code)]
+
+//! Authorization `SQLite`.
+//!
+//! A [`PolicyStore`] persisted in `SQLite`, for single-node deployments without a database
+//! server.
+//!
+//! Actions, principals and resources are normalised into indexed tables, so wildcard lookups
+//! are index searches rather than scans of every policy.
+
+mod migrations;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    path::Path,
+};
+
+use authorization::{store::Change, Action, Effect, Policy, PolicyStore, Principal, Resource};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+
+/// `SQLite` store.
+#[derive(Debug)]
+pub struct SqliteStore {
+    /// Connection.
+    connection: Connection,
+}
+
+/// Error.
+#[derive(Debug)]
+pub enum Error {
+    /// A policy with the id already exists.
+    Conflict(String),
+
+    /// A policy cannot be encoded into or decoded from its rows.
+    Encoding {
+        /// Policy id.
+        id: String,
+
+        /// Message.
+        message: String,
+    },
+
+    /// The revision of the database is negative.
+    InvalidRevision(i64),
+
+    /// No policy with the id exists.
+    NotFound(String),
+
+    /// `SQLite` failed.
+    Sqlite(rusqlite::Error),
+
+    /// The database was migrated by a newer release.
+    UnsupportedVersion(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Conflict(id) => write!(f, "policy `{id}` already exists"),
+            Error::Encoding { id, message } => write!(f, "policy `{id}` is malformed: {message}"),
+            Error::InvalidRevision(revision) => write!(f, "revision {revision} is negative"),
+            Error::NotFound(id) => write!(f, "policy `{id}` does not exist"),
+            Error::Sqlite(error) => write!(f, "sqlite: {error}"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported schema version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sqlite(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Sqlite(error)
+    }
+}
+
+impl SqliteStore {
+    /// Opens a database file, creating and migrating it as needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or migrated.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        SqliteStore::new(Connection::open(path)?)
+    }
+
+    /// Opens a database in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or migrated.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        SqliteStore::new(Connection::open_in_memory()?)
+    }
+
+    /// Creates a store over a connection, migrating its database as needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be migrated.
+    pub fn new(mut connection: Connection) -> Result<Self, Error> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        migrations::migrate(&mut connection)?;
+        Ok(SqliteStore { connection })
+    }
+
+    /// Applies changes in one transaction, increasing the revision once if any policy changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, applying none of the changes, if any change fails.
+    pub fn apply(&mut self, changes: impl IntoIterator<Item = Change>) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+
+        let mut modified = false;
+        for change in changes {
+            modified |= match change {
+                Change::Delete(id) => remove(&transaction, &id)?,
+                Change::Insert(policy) => {
+                    if exists(&transaction, &policy.id)? {
+                        return Err(Error::Conflict(policy.id));
+                    }
+                    write(&transaction, &policy)?;
+                    true
+                }
+                Change::Update(policy) => {
+                    if !remove(&transaction, &policy.id)? {
+                        return Err(Error::NotFound(policy.id));
+                    }
+                    write(&transaction, &policy)?;
+                    true
+                }
+            };
+        }
+
+        if modified {
+            increment(&transaction)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Returns the connection.
+    #[must_use]
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Returns the policies with an entity matching the segments, in one read transaction.
+    fn query(&self, table: Table, segments: [&str; 3]) -> Result<Vec<Policy>, Error> {
+        let transaction = self.connection.unchecked_transaction()?;
+
+        let ids = transaction
+            .prepare_cached(table.query())?
+            .query_map(segments, |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let policies = ids
+            .iter()
+            .filter_map(|id| read(&transaction, id).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transaction.commit()?;
+        Ok(policies)
+    }
+}
+
+impl PolicyStore for SqliteStore {
+    type Error = Error;
+
+    fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        read(&self.connection, id)
+    }
+
+    fn insert(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Insert(policy)])
+    }
+
+    fn update(&mut self, policy: Policy) -> Result<(), Error> {
+        self.apply([Change::Update(policy)])
+    }
+
+    fn delete(&mut self, id: &str) -> Result<Option<Policy>, Error> {
+        let transaction = self.connection.transaction()?;
+
+        let policy = read(&transaction, id)?;
+        if policy.is_some() {
+            remove(&transaction, id)?;
+            increment(&transaction)?;
+        }
+
+        transaction.commit()?;
+        Ok(policy)
+    }
+
+    fn list(&self) -> Result<Vec<Policy>, Error> {
+        let transaction = self.connection.unchecked_transaction()?;
+
+        let ids = transaction
+            .prepare_cached("SELECT id FROM policies ORDER BY id")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let policies = ids
+            .iter()
+            .filter_map(|id| read(&transaction, id).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transaction.commit()?;
+        Ok(policies)
+    }
+
+    fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        self.query(Table::Actions, [&action.scope, &action.noun, &action.verb])
+    }
+
+    fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        self.query(
+            Table::Principals,
+            [&principal.scope, &principal.noun, &principal.id],
+        )
+    }
+
+    fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        self.query(
+            Table::Resources,
+            [&resource.scope, &resource.noun, &resource.id],
+        )
+    }
+
+    fn revision(&self) -> Result<u64, Error> {
+        let revision: i64 = self
+            .connection
+            .query_row("SELECT value FROM revision", [], |row| row.get(0))?;
+        u64::try_from(revision).map_err(|_| Error::InvalidRevision(revision))
+    }
+}
+
+/// Table of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    /// Actions.
+    Actions,
+
+    /// Principals.
+    Principals,
+
+    /// Resources.
+    Resources,
+}
+
+impl Table {
+    /// Returns the query of the ids of the policies with an entity matching the segments, where
+    /// `*` in a row matches any segment.
+    ///
+    /// Each segment is one of two values, so the lookup index is searched rather than scanned.
+    fn query(self) -> &'static str {
+        match self {
+            Table::Actions => {
+                "SELECT DISTINCT policy_id FROM policy_actions \
+                 WHERE scope IN (?1, '*') AND noun IN (?2, '*') AND verb IN (?3, '*') \
+                 ORDER BY policy_id"
+            }
+            Table::Principals => {
+                "SELECT DISTINCT policy_id FROM policy_principals \
+                 WHERE scope IN (?1, '*') AND noun IN (?2, '*') AND id IN (?3, '*') \
+                 ORDER BY policy_id"
+            }
+            Table::Resources => {
+                "SELECT DISTINCT policy_id FROM policy_resources \
+                 WHERE scope IN (?1, '*') AND noun IN (?2, '*') AND id IN (?3, '*') \
+                 ORDER BY policy_id"
+            }
+        }
+    }
+}
+
+/// Returns true if a policy with the id exists.
+fn exists(connection: &Connection, id: &str) -> Result<bool, Error> {
+    Ok(connection
+        .prepare_cached("SELECT 1 FROM policies WHERE id = ?1")?
+        .exists([id])?)
+}
+
+/// Increments the revision.
+fn increment(transaction: &Transaction) -> Result<(), Error> {
+    transaction.execute("UPDATE revision SET value = value + 1", [])?;
+    Ok(())
+}
+
+/// Reads the policy with the id from its rows.
+fn read(connection: &Connection, id: &str) -> Result<Option<Policy>, Error> {
+    let row = connection
+        .prepare_cached("SELECT effect, conditions, metadata FROM policies WHERE id = ?1")?
+        .query_row([id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .optional()?;
+    let Some((effect, conditions, metadata)) = row else {
+        return Ok(None);
+    };
+
+    let effect = match effect.as_str() {
+        "allow" => Effect::Allow,
+        "deny" => Effect::Deny,
+        _ => {
+            return Err(Error::Encoding {
+                id: id.to_string(),
+                message: format!("effect: unknown effect `{effect}`"),
+            })
+        }
+    };
+
+    let actions = connection
+        .prepare_cached(
+            "SELECT scope, noun, verb FROM policy_actions WHERE policy_id = ?1 ORDER BY position",
+        )?
+        .query_map([id], |row| {
+            Ok(Action::new(
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut principals = Vec::new();
+    let mut statement = connection.prepare_cached(
+        "SELECT scope, noun, id, attributes FROM policy_principals \
+         WHERE policy_id = ?1 ORDER BY position",
+    )?;
+    let mut rows = statement.query([id])?;
+    while let Some(row) = rows.next()? {
+        let mut principal = Principal::new(
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        );
+        principal.attributes = decode(id, "attributes", &row.get::<_, String>(3)?)?;
+        principals.push(principal);
+    }
+
+    let mut resources = Vec::new();
+    let mut statement = connection.prepare_cached(
+        "SELECT scope, noun, id, attributes FROM policy_resources \
+         WHERE policy_id = ?1 ORDER BY position",
+    )?;
+    let mut rows = statement.query([id])?;
+    while let Some(row) = rows.next()? {
+        let mut resource = Resource::new(
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        );
+        resource.attributes = decode(id, "attributes", &row.get::<_, String>(3)?)?;
+        resources.push(resource);
+    }
+
+    let mut policy = Policy::new(effect, id).metadata(decode(id, "metadata", &metadata)?);
+    policy.actions = actions;
+    policy.conditions = decode(id, "conditions", &conditions)?;
+    policy.principals = principals;
+    policy.resources = resources;
+    Ok(Some(policy))
+}
+
+/// Decodes a JSON column of the policy with the id.
+fn decode<T: DeserializeOwned>(id: &str, column: &str, json: &str) -> Result<T, Error> {
+    serde_json::from_str(json).map_err(|error| Error::Encoding {
+        id: id.to_string(),
+        message: format!("{column}: {error}"),
+    })
+}
+
+/// Removes the rows of the policy with the id, returning true if it existed.
+fn remove(transaction: &Transaction, id: &str) -> Result<bool, Error> {
+    let removed = transaction
+        .prepare_cached("DELETE FROM policies WHERE id = ?1")?
+        .execute([id])?;
+    Ok(removed > 0)
+}
+
+/// Writes the rows of a policy.
+fn write(transaction: &Transaction, policy: &Policy) -> Result<(), Error> {
+    let encode = |column: &str, json: serde_json::Result<String>| {
+        json.map_err(|error| Error::Encoding {
+            id: policy.id.clone(),
+            message: format!("{column}: {error}"),
+        })
+    };
+
+    let effect = match policy.effect {
+        Effect::Allow => "allow",
+        Effect::Deny => "deny",
+    };
+    transaction
+        .prepare_cached(
+            "INSERT INTO policies (id, effect, conditions, metadata) VALUES (?1, ?2, ?3, ?4)",
+        )?
+        .execute(params![
+            policy.id,
+            effect,
+            encode("conditions", serde_json::to_string(&policy.conditions))?,
+            encode("metadata", serde_json::to_string(&policy.metadata))?,
+        ])?;
+
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO policy_actions (policy_id, position, scope, noun, verb) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (position, action) in policy.actions.iter().enumerate() {
+        statement.execute(params![
+            policy.id,
+            position,
+            action.scope,
+            action.noun,
+            action.verb
+        ])?;
+    }
+
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO policy_principals (policy_id, position, scope, noun, id, attributes) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (position, principal) in policy.principals.iter().enumerate() {
+        statement.execute(params![
+            policy.id,
+            position,
+            principal.scope,
+            principal.noun,
+            principal.id,
+            encode("attributes", serde_json::to_string(&principal.attributes))?,
+        ])?;
+    }
+
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO policy_resources (policy_id, position, scope, noun, id, attributes) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (position, resource) in policy.resources.iter().enumerate() {
+        statement.execute(params![
+            policy.id,
+            position,
+            resource.scope,
+            resource.noun,
+            resource.id,
+            encode("attributes", serde_json::to_string(&resource.attributes))?,
+        ])?;
+    }
+
+    Ok(())
+}
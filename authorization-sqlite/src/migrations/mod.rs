@@ -0,0 +1,79 @@
+//! Migrations.
+//!
+//! Each migration upgrades the schema by one version, tracked in `PRAGMA user_version`.
+//! Migrations are append-only: a released migration is never edited.
+
+use rusqlite::{Connection, TransactionBehavior};
+
+use crate::Error;
+
+/// Migrations, in order. The schema version is the number applied.
+const MIGRATIONS: &[&str] = &[
+    // 1: policies with normalised actions, principals and resources.
+    r"
+    CREATE TABLE policies (
+        id TEXT PRIMARY KEY NOT NULL,
+        effect TEXT NOT NULL,
+        conditions TEXT NOT NULL,
+        metadata TEXT NOT NULL
+    );
+
+    CREATE TABLE policy_actions (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        verb TEXT NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_actions_lookup ON policy_actions (scope, noun, verb, policy_id);
+
+    CREATE TABLE policy_principals (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        id TEXT NOT NULL,
+        attributes TEXT NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_principals_lookup ON policy_principals (scope, noun, id, policy_id);
+
+    CREATE TABLE policy_resources (
+        policy_id TEXT NOT NULL REFERENCES policies (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        scope TEXT NOT NULL,
+        noun TEXT NOT NULL,
+        id TEXT NOT NULL,
+        attributes TEXT NOT NULL,
+        PRIMARY KEY (policy_id, position)
+    );
+    CREATE INDEX policy_resources_lookup ON policy_resources (scope, noun, id, policy_id);
+
+    CREATE TABLE revision (
+        singleton INTEGER PRIMARY KEY NOT NULL CHECK (singleton = 0),
+        value INTEGER NOT NULL
+    );
+    INSERT INTO revision (singleton, value) VALUES (0, 0);
+    ",
+];
+
+/// Applies the migrations newer than the schema version of the database, in one transaction.
+///
+/// Returns an error if the database was migrated by a newer release.
+pub(crate) fn migrate(connection: &mut Connection) -> Result<(), Error> {
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let version: usize = transaction.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version..] {
+        transaction.execute_batch(migration)?;
+    }
+    transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+
+    transaction.commit()?;
+    Ok(())
+}
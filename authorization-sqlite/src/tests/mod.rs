@@ -0,0 +1,180 @@
+use std::{fs, path::PathBuf, process};
+
+use authorization::{
+    store::{conformance, Change},
+    Action, Policy, PolicyStore, Principal, Resource,
+};
+use rusqlite::Connection;
+
+use crate::{Error, SqliteStore};
+
+#[test]
+fn sqlite_store_should_conform() {
+    conformance::run(|| SqliteStore::open_in_memory().unwrap());
+}
+
+#[test]
+fn apply_should_increase_revision_once() {
+    // Arrange
+    let mut store = SqliteStore::open_in_memory().unwrap();
+    store.insert(given_policy("c")).unwrap();
+
+    // Act
+    store
+        .apply([
+            Change::Insert(given_policy("a")),
+            Change::Insert(given_policy("b")),
+            Change::Update(given_policy("c")),
+        ])
+        .unwrap();
+
+    // Assert
+    assert_eq!(store.revision().unwrap(), 2);
+    assert_eq!(ids(&store.list().unwrap()), ["a", "b", "c"]);
+}
+
+#[test]
+fn apply_should_change_nothing_when_a_change_fails() {
+    // Arrange
+    let mut store = SqliteStore::open_in_memory().unwrap();
+    store.insert(given_policy("a")).unwrap();
+
+    // Act
+    let result = store.apply([
+        Change::Insert(given_policy("b")),
+        Change::Delete("a".to_string()),
+        Change::Update(given_policy("missing")),
+    ]);
+
+    // Assert
+    assert!(matches!(result, Err(Error::NotFound(id)) if id == "missing"));
+    assert_eq!(store.revision().unwrap(), 1);
+    assert_eq!(ids(&store.list().unwrap()), ["a"]);
+}
+
+#[test]
+fn apply_should_not_increase_revision_when_nothing_changed() {
+    // Arrange
+    let mut store = SqliteStore::open_in_memory().unwrap();
+
+    // Act
+    store
+        .apply([Change::Delete("missing".to_string())])
+        .unwrap();
+
+    // Assert
+    assert_eq!(store.revision().unwrap(), 0);
+}
+
+#[test]
+fn revision_should_return_error_when_negative() {
+    // Arrange
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .connection()
+        .execute("UPDATE revision SET value = -1", [])
+        .unwrap();
+
+    // Act
+    let result = store.revision();
+
+    // Assert
+    assert!(matches!(result, Err(Error::InvalidRevision(-1))));
+}
+
+#[test]
+fn get_should_return_policy_with_attributes_and_order() {
+    // Arrange
+    let mut store = SqliteStore::open_in_memory().unwrap();
+    let mut principal = "world:player:1".parse::<Principal>().unwrap();
+    principal.attributes.insert("level".to_string(), 10.into());
+    let policy = Policy::deny("a")
+        .action("world:loot:take".parse::<Action>().unwrap())
+        .action("world:loot:drop".parse::<Action>().unwrap())
+        .principal(principal)
+        .resource("world:loot:*".parse::<Resource>().unwrap());
+    store.insert(policy.clone()).unwrap();
+
+    // Act
+    let result = store.get("a").unwrap();
+
+    // Assert
+    assert_eq!(result, Some(policy));
+}
+
+#[test]
+fn open_should_keep_policies_when_reopened() {
+    // Arrange
+    let path = given_path("reopen");
+    let mut store = SqliteStore::open(&path).unwrap();
+    store.insert(given_policy("a")).unwrap();
+    drop(store);
+
+    // Act
+    let store = SqliteStore::open(&path).unwrap();
+
+    // Assert
+    assert_eq!(store.get("a").unwrap(), Some(given_policy("a")));
+    assert_eq!(store.revision().unwrap(), 1);
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn open_should_return_error_when_schema_is_newer() {
+    // Arrange
+    let path = given_path("newer");
+    let connection = Connection::open(&path).unwrap();
+    connection.pragma_update(None, "user_version", 99).unwrap();
+    drop(connection);
+
+    // Act
+    let result = SqliteStore::open(&path);
+
+    // Assert
+    assert!(matches!(result, Err(Error::UnsupportedVersion(99))));
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn query_by_principal_should_search_index() {
+    // Arrange
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    // Act
+    let plan = store
+        .connection()
+        .prepare(&format!(
+            "EXPLAIN QUERY PLAN {}",
+            crate::Table::Principals.query()
+        ))
+        .unwrap()
+        .query_map(["world", "player", "1"], |row| row.get::<_, String>(3))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    // Assert
+    assert!(
+        plan.iter()
+            .any(|step| step.contains("SEARCH") && step.contains("policy_principals_lookup")),
+        "{plan:?}"
+    );
+}
+
+fn given_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("authorization-sqlite-{}-{name}.db", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn given_policy(id: &str) -> Policy {
+    Policy::allow(id)
+        .action("*:*:*".parse::<Action>().unwrap())
+        .principal("*:*:*".parse::<Principal>().unwrap())
+        .resource("*:*:*".parse::<Resource>().unwrap())
+}
+
+fn ids(policies: &[Policy]) -> Vec<&str> {
+    policies.iter().map(|policy| policy.id.as_str()).collect()
+}
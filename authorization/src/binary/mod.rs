@@ -0,0 +1,326 @@
+//! Binary.
+//!
+//! A compact encoding of a [`PolicySet`], including its indexes.
+//!
+//! | Bytes | Content                                  |
+//! | ----- | ---------------------------------------- |
+//! | 4     | Magic, `AZPS`.                           |
+//! | 2     | Version, little endian.                  |
+//! | 4     | CRC-32 of the payload, little endian.    |
+//! | ..    | Payload, [postcard] encoded.             |
+//!
+//! Expressions are stored as source, and parsed again when decoded. A [`Schema`](crate::Schema)
+//! is not encoded.
+//!
+//! [postcard]: https://docs.rs/postcard
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collections::Map,
+    set::{Index, PolicySet},
+    Action, Condition, Effect, Metadata, Policy, Principal, Resource, Value,
+};
+
+/// Magic.
+pub const MAGIC: [u8; 4] = *b"AZPS";
+
+/// Current version.
+pub const VERSION: u16 = 2;
+
+/// Length of the header.
+const HEADER_LENGTH: usize = 10;
+
+/// Error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The checksum does not match the payload.
+    Checksum,
+
+    /// The payload is not a valid policy set.
+    Payload(String),
+
+    /// The bytes do not start with [`MAGIC`].
+    Magic,
+
+    /// The bytes are shorter than the header.
+    Truncated,
+
+    /// The version is unknown, usually written by a newer release.
+    UnsupportedVersion(u16),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Checksum => write!(f, "checksum mismatch"),
+            Error::Payload(message) => write!(f, "invalid payload: {message}"),
+            Error::Magic => write!(f, "not a policy set"),
+            Error::Truncated => write!(f, "truncated header"),
+            Error::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported policy set version {version}, expected {VERSION}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Encodes a [`PolicySet`].
+///
+/// # Panics
+///
+/// Panics if the allocator fails, as encoding to a vector is otherwise infallible.
+#[must_use]
+pub fn encode(set: &PolicySet) -> Vec<u8> {
+    let payload = Payload {
+        allow: set.allow.clone(),
+        deny: set.deny.clone(),
+        policies: set.policies.iter().cloned().map(Record::from).collect(),
+    };
+    let payload = postcard::to_allocvec(&payload).expect("encoding to a vector is infallible");
+
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH + payload.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    bytes
+}
+
+/// Decodes a [`PolicySet`], checking its indexes rather than rebuilding them.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the header, checksum or payload is invalid.
+pub fn decode(bytes: &[u8]) -> Result<PolicySet, Error> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(Error::Truncated);
+    }
+    let (header, payload) = bytes.split_at(HEADER_LENGTH);
+
+    if header[0..4] != MAGIC {
+        return Err(Error::Magic);
+    }
+
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    let checksum = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+    if checksum != crc32fast::hash(payload) {
+        return Err(Error::Checksum);
+    }
+
+    let payload = postcard::from_bytes::<Payload>(payload)
+        .map_err(|error| Error::Payload(error.to_string()))?;
+
+    let policies = payload
+        .policies
+        .into_iter()
+        .map(Policy::from)
+        .collect::<Vec<_>>();
+
+    validate(&payload.allow, &policies, &Effect::Allow)?;
+    validate(&payload.deny, &policies, &Effect::Deny)?;
+
+    Ok(PolicySet {
+        allow: payload.allow,
+        deny: payload.deny,
+        policies,
+        schema: None,
+    })
+}
+
+/// Returns an error unless an index lists, in ascending order, exactly the policies of the effect
+/// with an action of each scope.
+///
+/// Evaluation merges the lists assuming this order, so an index which passes the checksum but
+/// breaks it would otherwise skip or repeat policies.
+fn validate(index: &Index, policies: &[Policy], effect: &Effect) -> Result<(), Error> {
+    let lists = index
+        .scopes
+        .iter()
+        .map(|(scope, positions)| (scope.as_str(), positions))
+        .chain([("*", &index.wildcard)]);
+    for (scope, positions) in lists {
+        if positions.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Error::Payload(format!(
+                "index of scope `{scope}` is not in ascending order"
+            )));
+        }
+
+        for position in positions {
+            let listed = policies.get(*position).is_some_and(|policy| {
+                &policy.effect == effect
+                    && policy.actions.iter().any(|action| action.scope == scope)
+            });
+            if !listed {
+                return Err(Error::Payload(format!(
+                    "index of scope `{scope}` lists policy {position}, which has no action in it"
+                )));
+            }
+        }
+    }
+
+    for (position, policy) in policies.iter().enumerate() {
+        if &policy.effect != effect {
+            continue;
+        }
+
+        for action in &policy.actions {
+            let positions = if action.scope == "*" {
+                Some(&index.wildcard)
+            } else {
+                index.scopes.get(&action.scope)
+            };
+            if positions.is_none_or(|positions| positions.binary_search(&position).is_err()) {
+                return Err(Error::Payload(format!(
+                    "index of scope `{}` is missing policy `{}`",
+                    action.scope, policy.id
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Payload.
+#[derive(Deserialize, Serialize)]
+struct Payload {
+    #[serde(rename = "allow")]
+    allow: Index,
+
+    #[serde(rename = "deny")]
+    deny: Index,
+
+    #[serde(rename = "policies")]
+    policies: Vec<Record>,
+}
+
+/// Record of a [`Policy`].
+///
+/// Unlike [`Policy`], every field is always present.
+#[derive(Deserialize, Serialize)]
+struct Record {
+    #[serde(rename = "actions")]
+    actions: Vec<Action>,
+
+    #[serde(rename = "conditions")]
+    conditions: Vec<Condition>,
+
+    #[serde(rename = "effect")]
+    effect: Effect,
+
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "metadata")]
+    metadata: Metadata,
+
+    #[serde(rename = "principals")]
+    principals: Vec<Entity>,
+
+    #[serde(rename = "resources")]
+    resources: Vec<Entity>,
+}
+
+impl From<Policy> for Record {
+    fn from(policy: Policy) -> Self {
+        Record {
+            actions: policy.actions,
+            conditions: policy.conditions,
+            effect: policy.effect,
+            id: policy.id,
+            metadata: policy.metadata,
+            principals: policy.principals.into_iter().map(Into::into).collect(),
+            resources: policy.resources.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Record> for Policy {
+    fn from(record: Record) -> Self {
+        Policy {
+            actions: record.actions,
+            conditions: record.conditions,
+            effect: record.effect,
+            id: record.id,
+            metadata: record.metadata,
+            principals: record.principals.into_iter().map(Into::into).collect(),
+            resources: record.resources.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Record of a [`Principal`] or [`Resource`].
+///
+/// Unlike [`Principal`] and [`Resource`], every field is always present.
+#[derive(Deserialize, Serialize)]
+struct Entity {
+    #[serde(rename = "attributes")]
+    attributes: Map<String, Value>,
+
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "noun")]
+    noun: String,
+
+    #[serde(rename = "scope")]
+    scope: String,
+}
+
+impl From<Principal> for Entity {
+    fn from(principal: Principal) -> Self {
+        Entity {
+            attributes: principal.attributes,
+            id: principal.id,
+            noun: principal.noun,
+            scope: principal.scope,
+        }
+    }
+}
+
+impl From<Entity> for Principal {
+    fn from(entity: Entity) -> Self {
+        Principal {
+            attributes: entity.attributes,
+            id: entity.id,
+            noun: entity.noun,
+            scope: entity.scope,
+        }
+    }
+}
+
+impl From<Resource> for Entity {
+    fn from(resource: Resource) -> Self {
+        Entity {
+            attributes: resource.attributes,
+            id: resource.id,
+            noun: resource.noun,
+            scope: resource.scope,
+        }
+    }
+}
+
+impl From<Entity> for Resource {
+    fn from(entity: Entity) -> Self {
+        Resource {
+            attributes: entity.attributes,
+            id: entity.id,
+            noun: entity.noun,
+            scope: entity.scope,
+        }
+    }
+}
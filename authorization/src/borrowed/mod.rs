@@ -0,0 +1,252 @@
+//! Borrowed.
+//!
+//! A [`ContextRef`] borrows its fields and looks data up through the [`Data`] trait, so a
+//! context can be evaluated without allocating.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::{
+    collections::{Map, Set},
+    Action, Context, Principal, Resource, Value,
+};
+
+/// Borrowed [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionRef<'a> {
+    /// Noun.
+    pub noun: &'a str,
+
+    /// Scope.
+    pub scope: &'a str,
+
+    /// Verb.
+    pub verb: &'a str,
+}
+
+impl<'a> From<&'a Action> for ActionRef<'a> {
+    fn from(action: &'a Action) -> Self {
+        ActionRef {
+            noun: &action.noun,
+            scope: &action.scope,
+            verb: &action.verb,
+        }
+    }
+}
+
+impl From<ActionRef<'_>> for Action {
+    fn from(action: ActionRef<'_>) -> Self {
+        Action {
+            noun: action.noun.to_string(),
+            scope: action.scope.to_string(),
+            verb: action.verb.to_string(),
+        }
+    }
+}
+
+/// Borrowed [`Context`].
+#[derive(Clone, Copy)]
+pub struct ContextRef<'a> {
+    /// Action.
+    pub action: ActionRef<'a>,
+
+    /// Data.
+    pub data: &'a dyn Data,
+
+    /// Principal.
+    pub principal: PrincipalRef<'a>,
+
+    /// Resource.
+    pub resource: ResourceRef<'a>,
+}
+
+impl fmt::Debug for ContextRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextRef")
+            .field("action", &self.action)
+            .field("principal", &self.principal)
+            .field("resource", &self.resource)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> From<&'a Context> for ContextRef<'a> {
+    fn from(context: &'a Context) -> Self {
+        ContextRef {
+            action: (&context.action).into(),
+            data: &context.data,
+            principal: (&context.principal).into(),
+            resource: (&context.resource).into(),
+        }
+    }
+}
+
+/// Borrowed [`Principal`].
+#[derive(Clone, Copy)]
+pub struct PrincipalRef<'a> {
+    /// Attributes.
+    ///
+    /// `&()` when there are none.
+    pub attributes: &'a dyn Data,
+
+    /// Id.
+    pub id: &'a str,
+
+    /// Noun.
+    pub noun: &'a str,
+
+    /// Scope.
+    pub scope: &'a str,
+}
+
+impl fmt::Debug for PrincipalRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrincipalRef")
+            .field("id", &self.id)
+            .field("noun", &self.noun)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> From<&'a Principal> for PrincipalRef<'a> {
+    fn from(principal: &'a Principal) -> Self {
+        PrincipalRef {
+            attributes: &principal.attributes,
+            id: &principal.id,
+            noun: &principal.noun,
+            scope: &principal.scope,
+        }
+    }
+}
+
+/// Borrowed [`Resource`].
+#[derive(Clone, Copy)]
+pub struct ResourceRef<'a> {
+    /// Attributes.
+    ///
+    /// `&()` when there are none.
+    pub attributes: &'a dyn Data,
+
+    /// Id.
+    pub id: &'a str,
+
+    /// Noun.
+    pub noun: &'a str,
+
+    /// Scope.
+    pub scope: &'a str,
+}
+
+impl fmt::Debug for ResourceRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceRef")
+            .field("id", &self.id)
+            .field("noun", &self.noun)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> From<&'a Resource> for ResourceRef<'a> {
+    fn from(resource: &'a Resource) -> Self {
+        ResourceRef {
+            attributes: &resource.attributes,
+            id: &resource.id,
+            noun: &resource.noun,
+            scope: &resource.scope,
+        }
+    }
+}
+
+/// Data.
+///
+/// Looks up the values of a data key.
+pub trait Data {
+    /// Returns the values of a key, or `None` if the key is absent.
+    fn get(&self, key: &str) -> Option<Values<'_>>;
+}
+
+impl Data for () {
+    fn get(&self, _: &str) -> Option<Values<'_>> {
+        None
+    }
+}
+
+#[allow(clippy::implicit_hasher)]
+impl Data for Map<String, Set<String>> {
+    fn get(&self, key: &str) -> Option<Values<'_>> {
+        Map::get(self, key).map(Values::Set)
+    }
+}
+
+#[allow(clippy::implicit_hasher)]
+impl Data for Map<String, Value> {
+    fn get(&self, key: &str) -> Option<Values<'_>> {
+        Map::get(self, key).map(Values::Value)
+    }
+}
+
+/// Values.
+///
+/// The values of a data key, compared as a set.
+///
+/// A typed [`Value`] other than a set is a single value, and is compared with strings by its string
+/// form.
+#[derive(Debug, Clone, Copy)]
+pub enum Values<'a> {
+    /// A single value.
+    One(&'a str),
+
+    /// A set of values.
+    Set(&'a Set<String>),
+
+    /// A slice of distinct values.
+    Slice(&'a [&'a str]),
+
+    /// A typed value.
+    Value(&'a Value),
+}
+
+impl Values<'_> {
+    /// Returns true if the values contain a value.
+    #[must_use]
+    pub fn contains(&self, value: &str) -> bool {
+        match self {
+            Values::One(one) => *one == value,
+            Values::Set(set) => set.contains(value),
+            Values::Slice(slice) => slice.contains(&value),
+            Values::Value(Value::Set(set)) => set.iter().any(|item| item.is_str(value)),
+            Values::Value(typed) => typed.is_str(value),
+        }
+    }
+
+    /// Returns true if there are no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if every value is contained by a set.
+    #[must_use]
+    pub fn is_subset(&self, set: &Set<String>) -> bool {
+        match self {
+            Values::One(one) => set.contains(*one),
+            Values::Set(values) => values.is_subset(set),
+            Values::Slice(slice) => slice.iter().all(|value| set.contains(*value)),
+            Values::Value(Value::Set(values)) => values.iter().all(|value| value.is_in(set)),
+            Values::Value(typed) => typed.is_in(set),
+        }
+    }
+
+    /// Returns the number of values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Values::Set(set) => set.len(),
+            Values::Slice(slice) => slice.len(),
+            Values::Value(Value::Set(set)) => set.len(),
+            Values::One(_) | Values::Value(_) => 1,
+        }
+    }
+}
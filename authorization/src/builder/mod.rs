@@ -0,0 +1,262 @@
+//! Builder.
+//!
+//! Fluent construction of [`Policy`], [`Context`], [`Condition`], [`Metadata`] and their entities,
+//! so that callers do not depend on the fields of each type.
+//!
+//! Prefer these constructors and builder methods over struct literals, as the types will become
+//! `#[non_exhaustive]` in a future release, after which struct literals no longer compile outside
+//! of this crate.
+//!
+//! ```
+//! # use authorization::{Action, Context, Expression, Policy, Principal, Resource};
+//! let policy = Policy::allow("attack-monsters")
+//!     .action("world:*:attack".parse::<Action>().unwrap())
+//!     .principal("world:player:*".parse::<Principal>().unwrap())
+//!     .resource("world:monster:*".parse::<Resource>().unwrap())
+//!     .when(r#"data["player:level"] >= 10"#.parse::<Expression>().unwrap());
+//!
+//! let context = Context::new(
+//!     "world:monster:attack".parse::<Action>().unwrap(),
+//!     "world:player:1".parse::<Principal>().unwrap(),
+//!     "world:monster:2".parse::<Resource>().unwrap(),
+//! )
+//! .data("player:level", 12);
+//!
+//! assert_eq!(authorization::evaluate(&context, &[policy]).unwrap().id, "attack-monsters");
+//! ```
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    collections::Map, value::Timestamp, Action, Condition, Context, Effect, Expression, Metadata,
+    Policy, Principal, Resource, Value,
+};
+
+impl Action {
+    /// Creates an action.
+    #[must_use]
+    pub fn new(scope: impl Into<String>, noun: impl Into<String>, verb: impl Into<String>) -> Self {
+        Action {
+            noun: noun.into(),
+            scope: scope.into(),
+            verb: verb.into(),
+        }
+    }
+}
+
+impl Policy {
+    /// Creates a policy that allows its actions, matching nothing until actions, principals and
+    /// resources are added.
+    #[must_use]
+    pub fn allow(id: impl Into<String>) -> Self {
+        Policy::new(Effect::Allow, id)
+    }
+
+    /// Creates a policy that denies its actions, matching nothing until actions, principals and
+    /// resources are added.
+    #[must_use]
+    pub fn deny(id: impl Into<String>) -> Self {
+        Policy::new(Effect::Deny, id)
+    }
+
+    /// Creates a policy with an effect.
+    #[must_use]
+    pub fn new(effect: Effect, id: impl Into<String>) -> Self {
+        Policy {
+            actions: Vec::new(),
+            conditions: Vec::new(),
+            effect,
+            id: id.into(),
+            metadata: Metadata::default(),
+            principals: Vec::new(),
+            resources: Vec::new(),
+        }
+    }
+
+    /// Adds an action.
+    #[must_use]
+    pub fn action(mut self, action: impl Into<Action>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    /// Sets the metadata.
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Adds a principal.
+    #[must_use]
+    pub fn principal(mut self, principal: impl Into<Principal>) -> Self {
+        self.principals.push(principal.into());
+        self
+    }
+
+    /// Adds a resource.
+    #[must_use]
+    pub fn resource(mut self, resource: impl Into<Resource>) -> Self {
+        self.resources.push(resource.into());
+        self
+    }
+
+    /// Adds a condition.
+    ///
+    /// The policy matches when any of its conditions is met.
+    #[must_use]
+    pub fn when(mut self, condition: impl Into<Condition>) -> Self {
+        self.conditions.push(condition.into());
+        self
+    }
+}
+
+impl Context {
+    /// Creates a context without data.
+    #[must_use]
+    pub fn new(
+        action: impl Into<Action>,
+        principal: impl Into<Principal>,
+        resource: impl Into<Resource>,
+    ) -> Self {
+        Context {
+            action: action.into(),
+            data: Map::new(),
+            principal: principal.into(),
+            resource: resource.into(),
+        }
+    }
+
+    /// Inserts a data value, replacing any previous value of the key.
+    #[must_use]
+    pub fn data(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.data.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl Principal {
+    /// Creates a principal without attributes.
+    #[must_use]
+    pub fn new(scope: impl Into<String>, noun: impl Into<String>, id: impl Into<String>) -> Self {
+        Principal {
+            attributes: Map::new(),
+            id: id.into(),
+            noun: noun.into(),
+            scope: scope.into(),
+        }
+    }
+
+    /// Inserts an attribute, replacing any previous value of the name.
+    #[must_use]
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl Resource {
+    /// Creates a resource without attributes.
+    #[must_use]
+    pub fn new(scope: impl Into<String>, noun: impl Into<String>, id: impl Into<String>) -> Self {
+        Resource {
+            attributes: Map::new(),
+            id: id.into(),
+            noun: noun.into(),
+            scope: scope.into(),
+        }
+    }
+
+    /// Inserts an attribute, replacing any previous value of the name.
+    #[must_use]
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl Metadata {
+    /// Creates empty metadata.
+    #[must_use]
+    pub fn new() -> Self {
+        Metadata::default()
+    }
+
+    /// Sets the creation time.
+    #[must_use]
+    pub fn created_at(mut self, created_at: Timestamp) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// Sets the description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the owner.
+    #[must_use]
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Adds a tag.
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Sets the time of the last update.
+    #[must_use]
+    pub fn updated_at(mut self, updated_at: Timestamp) -> Self {
+        self.updated_at = Some(updated_at);
+        self
+    }
+
+    /// Sets the version.
+    #[must_use]
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+impl Condition {
+    /// Creates a condition that is always met.
+    #[must_use]
+    pub fn new() -> Self {
+        Condition::default()
+    }
+
+    /// Sets the expression.
+    #[must_use]
+    pub fn expression(mut self, expression: Expression) -> Self {
+        self.expression = Some(expression);
+        self
+    }
+
+    /// Adds string equals criteria, requiring the data key to have one of the values.
+    #[must_use]
+    pub fn string_equals<I>(mut self, key: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.string_equals
+            .get_or_insert_with(Map::new)
+            .entry(key.into())
+            .or_default()
+            .extend(values.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl From<Expression> for Condition {
+    fn from(expression: Expression) -> Self {
+        Condition::new().expression(expression)
+    }
+}
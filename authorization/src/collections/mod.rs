@@ -0,0 +1,15 @@
+//! Collections.
+//!
+//! The map and set of the public API. With the `std` feature, these are [`HashMap`] and
+//! [`HashSet`], otherwise [`BTreeMap`] and [`BTreeSet`]. Code which supports both should name
+//! them through these aliases.
+//!
+//! [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+//! [`HashSet`]: https://doc.rust-lang.org/std/collections/struct.HashSet.html
+//! [`BTreeMap`]: alloc::collections::BTreeMap
+//! [`BTreeSet`]: alloc::collections::BTreeSet
+
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap as Map, HashSet as Set};
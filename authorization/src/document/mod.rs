@@ -0,0 +1,466 @@
+//! Document.
+//!
+//! A versioned envelope for storing policies.
+//!
+//! | Version | Changes                                                                  |
+//! | ------- | ------------------------------------------------------------------------ |
+//! | `1`     | Initial format.                                                          |
+//! | `2`     | Adds [`Condition::expression`], [`Policy::metadata`] and [attributes]. |
+//!
+//! The version is read before the policies, which are then decoded in the shape of their version
+//! and upgraded to the current version, rejecting or rewriting anything which changed meaning. A
+//! document without a version, or a bare array of policies, is version 1.
+//!
+//! With the `toml` or `yaml` features, documents can be loaded from sources with errors pointing
+//! at the offending line and column.
+//!
+//! [`Condition::expression`]: crate::Condition::expression
+//! [attributes]: crate::Principal::attributes
+
+mod raw;
+mod v2;
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use serde::{
+    de::{
+        self, value::SeqAccessDeserializer, DeserializeSeed, IntoDeserializer, MapAccess,
+        SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::Policy;
+use raw::Raw;
+
+/// Policy document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyDocument {
+    /// Policies.
+    #[serde(rename = "policies")]
+    pub policies: Vec<Policy>,
+
+    /// Version.
+    #[serde(rename = "version")]
+    pub version: u64,
+}
+
+impl PolicyDocument {
+    /// Current version.
+    pub const VERSION: u64 = 2;
+
+    /// Creates a document of the current version.
+    #[must_use]
+    pub fn new(policies: Vec<Policy>) -> Self {
+        PolicyDocument {
+            policies,
+            version: Self::VERSION,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PolicyDocument {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DocumentVisitor { version: None })
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PolicyDocument {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "PolicyDocument".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let policies = generator.subschema_for::<Vec<Policy>>();
+
+        schemars::json_schema!({
+            "description": "Policy document.",
+            "oneOf": [
+                {
+                    "additionalProperties": false,
+                    "properties": {
+                        "policies": policies,
+                        "version": {
+                            "maximum": PolicyDocument::VERSION,
+                            "minimum": 1,
+                            "type": "integer",
+                        },
+                    },
+                    "required": ["policies"],
+                    "type": "object",
+                },
+                policies,
+            ],
+        })
+    }
+}
+
+/// Error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A policy uses a feature introduced after the version of its document.
+    UnsupportedFeature {
+        /// Feature.
+        feature: &'static str,
+
+        /// Policy id.
+        id: String,
+
+        /// Version of the document.
+        version: u64,
+    },
+
+    /// The version of the document is unknown, usually written by a newer release.
+    UnsupportedVersion(u64),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnsupportedFeature {
+                feature,
+                id,
+                version,
+            } => write!(
+                f,
+                "policy `{id}` uses `{feature}` which is not supported by version {version}"
+            ),
+            Error::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported policy document version {version}, expected 1 to {}",
+                PolicyDocument::VERSION
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Source error.
+///
+/// Returned when a document cannot be loaded from a source.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    /// Column, or zero if unknown.
+    pub column: usize,
+
+    /// File, if loaded from one.
+    pub file: Option<PathBuf>,
+
+    /// Line, or zero if unknown.
+    pub line: usize,
+
+    /// Message.
+    pub message: String,
+}
+
+#[cfg(feature = "std")]
+impl Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        if self.line > 0 {
+            write!(f, "{}:{}:", self.line, self.column)?;
+        }
+        if self.file.is_some() || self.line > 0 {
+            f.write_str(" ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for SourceError {}
+
+/// Loads a document from a file, choosing the format by its extension.
+///
+/// # Errors
+///
+/// Returns [`SourceError`] if the file cannot be read, its extension is not an enabled format,
+/// or the document is invalid.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<PolicyDocument, SourceError> {
+    let path = path.as_ref();
+    let with_file = |error: SourceError| SourceError {
+        file: Some(path.to_path_buf()),
+        ..error
+    };
+
+    let source = std::fs::read_to_string(path).map_err(|error| {
+        with_file(SourceError {
+            column: 0,
+            file: None,
+            line: 0,
+            message: error.to_string(),
+        })
+    })?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        #[cfg(feature = "toml")]
+        Some("toml") => from_toml(&source),
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => from_yaml(&source),
+        _ => Err(SourceError {
+            column: 0,
+            file: None,
+            line: 0,
+            message: "unsupported file extension".to_string(),
+        }),
+    }
+    .map_err(with_file)
+}
+
+/// Loads a document from TOML.
+///
+/// # Errors
+///
+/// Returns [`SourceError`] if the document is invalid.
+#[cfg(feature = "toml")]
+pub fn from_toml(source: &str) -> Result<PolicyDocument, SourceError> {
+    toml::from_str::<Header>(source)
+        .and_then(|header| {
+            toml::Deserializer::parse(source)?.deserialize_any(DocumentVisitor {
+                version: Some(header.version.unwrap_or(1)),
+            })
+        })
+        .map_err(|error: toml::de::Error| {
+            let (line, column) = error
+                .span()
+                .map_or((0, 0), |span| position(source, span.start));
+            SourceError {
+                column,
+                file: None,
+                line,
+                message: error.message().to_string(),
+            }
+        })
+}
+
+/// Loads a document from YAML.
+///
+/// # Errors
+///
+/// Returns [`SourceError`] if the document is invalid.
+#[cfg(feature = "yaml")]
+pub fn from_yaml(source: &str) -> Result<PolicyDocument, SourceError> {
+    serde_norway::from_str::<Header>(source)
+        .and_then(|header| {
+            serde_norway::Deserializer::from_str(source).deserialize_any(DocumentVisitor {
+                version: Some(header.version.unwrap_or(1)),
+            })
+        })
+        .map_err(|error| {
+            let message = error.to_string();
+            match error.location() {
+                Some(location) => {
+                    let (line, column) = position(source, location.index());
+                    let message = message
+                        .rsplit_once(" at line ")
+                        .map_or(message.as_str(), |(message, _)| message)
+                        .to_string();
+                    SourceError {
+                        column,
+                        file: None,
+                        line,
+                        message,
+                    }
+                }
+                None => SourceError {
+                    column: 0,
+                    file: None,
+                    line: 0,
+                    message,
+                },
+            }
+        })
+}
+
+/// Saves a document as TOML.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be represented in TOML.
+#[cfg(feature = "toml")]
+pub fn to_toml(document: &PolicyDocument) -> Result<String, toml::ser::Error> {
+    toml::to_string(document)
+}
+
+/// Saves a document as YAML.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be represented in YAML.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(document: &PolicyDocument) -> Result<String, serde_norway::Error> {
+    serde_norway::to_string(document)
+}
+
+/// Returns the line and column of an offset.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before, |index| &before[index + 1..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Version of a document, read by the loaders before the document so that the policies are
+/// decoded as they are read, keeping the position of their errors, whatever the order of fields.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default, rename = "version")]
+    version: Option<u64>,
+}
+
+/// Visitor of a [`PolicyDocument`] of any version.
+struct DocumentVisitor {
+    /// Version, if known before the document is read.
+    version: Option<u64>,
+}
+
+impl<'de> Visitor<'de> for DocumentVisitor {
+    type Value = PolicyDocument;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a policy document or an array of policies")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<PolicyDocument, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let policies = Versioned(1).deserialize(SeqAccessDeserializer::new(seq))?;
+        Ok(PolicyDocument::new(policies))
+    }
+
+    /// Decodes the policies as they are read once the version is known, or buffers them until it
+    /// is.
+    fn visit_map<A>(self, mut map: A) -> Result<PolicyDocument, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut policies = None;
+        let mut raw = None;
+        let mut version = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Policies if policies.is_some() || raw.is_some() => {
+                    return Err(de::Error::duplicate_field("policies"));
+                }
+                Field::Policies => match version.or(self.version) {
+                    Some(version) => policies = Some(map.next_value_seed(Versioned(version))?),
+                    None => raw = Some(map.next_value::<Raw>()?),
+                },
+                Field::Version if version.is_some() => {
+                    return Err(de::Error::duplicate_field("version"));
+                }
+                Field::Version => {
+                    let value = map.next_value()?;
+                    if value == 0 || value > PolicyDocument::VERSION {
+                        return Err(de::Error::custom(Error::UnsupportedVersion(value)));
+                    }
+                    version = Some(value);
+                }
+            }
+        }
+
+        let policies = match (policies, raw) {
+            (Some(policies), _) => policies,
+            (None, Some(raw)) => {
+                Versioned(version.unwrap_or(1)).deserialize(raw.into_deserializer())?
+            }
+            (None, None) => return Err(de::Error::missing_field("policies")),
+        };
+        Ok(PolicyDocument::new(policies))
+    }
+}
+
+/// Field of a [`PolicyDocument`].
+#[derive(Deserialize)]
+#[serde(field_identifier)]
+enum Field {
+    #[serde(rename = "policies")]
+    Policies,
+
+    #[serde(rename = "version")]
+    Version,
+}
+
+/// Decodes the policies of a version, upgraded to the current version.
+struct Versioned(u64);
+
+impl<'de> DeserializeSeed<'de> for Versioned {
+    type Value = Vec<Policy>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Vec<Policy>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let policies = Vec::<v2::Policy>::deserialize;
+
+        match self.0 {
+            // Version 1 is read in the shape of version 2, rejecting its additions.
+            1 => policies(deserializer)?
+                .into_iter()
+                .map(|policy| migrate_v1(policy.into()))
+                .collect::<Result<_, _>>()
+                .map_err(de::Error::custom),
+            2 => Ok(policies(deserializer)?
+                .into_iter()
+                .map(Into::into)
+                .collect()),
+            version => Err(de::Error::custom(Error::UnsupportedVersion(version))),
+        }
+    }
+}
+
+/// Upgrades a version 1 policy to version 2.
+fn migrate_v1(policy: Policy) -> Result<Policy, Error> {
+    let unsupported = |feature| Error::UnsupportedFeature {
+        feature,
+        id: policy.id.clone(),
+        version: 1,
+    };
+
+    if !policy.metadata.is_empty() {
+        return Err(unsupported("metadata"));
+    }
+
+    if policy
+        .conditions
+        .iter()
+        .any(|condition| condition.expression.is_some())
+    {
+        return Err(unsupported("expression"));
+    }
+
+    if policy
+        .principals
+        .iter()
+        .any(|principal| !principal.attributes.is_empty())
+        || policy
+            .resources
+            .iter()
+            .any(|resource| !resource.attributes.is_empty())
+    {
+        return Err(unsupported("attributes"));
+    }
+
+    Ok(policy)
+}
@@ -0,0 +1,210 @@
+//! Raw.
+//!
+//! A buffered value of any self-describing format, so the policies of a document can be read
+//! before its version and decoded once the version is known.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+/// Raw value.
+#[derive(Debug)]
+pub(super) enum Raw {
+    /// Boolean.
+    Bool(bool),
+
+    /// Floating point number.
+    F64(f64),
+
+    /// Negative integer.
+    I64(i64),
+
+    /// Map, in the order of its entries.
+    Map(Vec<(Raw, Raw)>),
+
+    /// Sequence.
+    Seq(Vec<Raw>),
+
+    /// String.
+    String(String),
+
+    /// Non-negative integer.
+    U64(u64),
+
+    /// Unit, or a missing optional value.
+    Unit,
+}
+
+impl<'de> Deserialize<'de> for Raw {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(RawVisitor)
+    }
+}
+
+impl<E: de::Error> IntoDeserializer<'_, E> for Raw {
+    type Deserializer = RawDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        RawDeserializer {
+            error: PhantomData,
+            raw: self,
+        }
+    }
+}
+
+/// Visitor of a [`Raw`].
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = Raw;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Raw, E> {
+        Ok(Raw::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Raw, E> {
+        Ok(u64::try_from(value).map_or(Raw::I64(value), Raw::U64))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Raw, E> {
+        Ok(Raw::U64(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Raw, E> {
+        Ok(Raw::F64(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Raw, E> {
+        Ok(Raw::String(value.into()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Raw, E> {
+        Ok(Raw::String(value))
+    }
+
+    fn visit_none<E>(self) -> Result<Raw, E> {
+        Ok(Raw::Unit)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Raw, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Raw::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Raw, E> {
+        Ok(Raw::Unit)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Raw, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Raw::Seq(items))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Raw, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Raw::Map(entries))
+    }
+}
+
+/// Deserializer of a [`Raw`].
+pub(super) struct RawDeserializer<E> {
+    error: PhantomData<E>,
+    raw: Raw,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for RawDeserializer<E> {
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.raw {
+            Raw::Bool(value) => visitor.visit_bool(value),
+            Raw::F64(value) => visitor.visit_f64(value),
+            Raw::I64(value) => visitor.visit_i64(value),
+            Raw::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Raw::Seq(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Raw::String(value) => visitor.visit_string(value),
+            Raw::U64(value) => visitor.visit_u64(value),
+            Raw::Unit => visitor.visit_unit(),
+        }
+    }
+
+    /// Deserializes a unit variant from its name.
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.raw {
+            Raw::String(value) => visitor.visit_enum(value.into_deserializer()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.raw {
+            Raw::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
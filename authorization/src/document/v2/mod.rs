@@ -0,0 +1,111 @@
+//! Version 2.
+//!
+//! The shape of the policies of a version 2 document, which rejects unknown fields to catch
+//! misspellings in hand-written documents. [`Policy`](crate::Policy) itself ignores them, so
+//! stores and services can read policies written by a newer release.
+
+use alloc::{string::String, vec::Vec};
+
+use serde::Deserialize;
+
+use crate::{
+    collections::{Map, Set},
+    value::Timestamp,
+    Action, Effect, Expression, Principal, Resource,
+};
+
+/// Policy.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Policy {
+    #[serde(rename = "actions")]
+    actions: Vec<Action>,
+
+    #[serde(rename = "conditions")]
+    conditions: Vec<Condition>,
+
+    #[serde(rename = "effect")]
+    effect: Effect,
+
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(default, rename = "metadata")]
+    metadata: Metadata,
+
+    #[serde(rename = "principals")]
+    principals: Vec<Principal>,
+
+    #[serde(rename = "resources")]
+    resources: Vec<Resource>,
+}
+
+/// Condition.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Condition {
+    #[serde(rename = "expression")]
+    expression: Option<Expression>,
+
+    #[serde(rename = "string_equals")]
+    string_equals: Option<Map<String, Set<String>>>,
+}
+
+/// Metadata.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Metadata {
+    #[serde(rename = "created_at")]
+    created_at: Option<Timestamp>,
+
+    #[serde(rename = "description")]
+    description: Option<String>,
+
+    #[serde(rename = "owner")]
+    owner: Option<String>,
+
+    #[serde(default, rename = "tags")]
+    tags: Vec<String>,
+
+    #[serde(rename = "updated_at")]
+    updated_at: Option<Timestamp>,
+
+    #[serde(rename = "version")]
+    version: Option<u64>,
+}
+
+impl From<Policy> for crate::Policy {
+    fn from(policy: Policy) -> Self {
+        crate::Policy {
+            actions: policy.actions,
+            conditions: policy.conditions.into_iter().map(Into::into).collect(),
+            effect: policy.effect,
+            id: policy.id,
+            metadata: policy.metadata.into(),
+            principals: policy.principals,
+            resources: policy.resources,
+        }
+    }
+}
+
+impl From<Condition> for crate::Condition {
+    fn from(condition: Condition) -> Self {
+        crate::Condition {
+            expression: condition.expression,
+            string_equals: condition.string_equals,
+        }
+    }
+}
+
+impl From<Metadata> for crate::Metadata {
+    fn from(metadata: Metadata) -> Self {
+        crate::Metadata {
+            created_at: metadata.created_at,
+            description: metadata.description,
+            owner: metadata.owner,
+            tags: metadata.tags,
+            updated_at: metadata.updated_at,
+            version: metadata.version,
+        }
+    }
+}
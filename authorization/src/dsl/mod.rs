@@ -0,0 +1,708 @@
+//! DSL.
+//!
+//! A compact text language for policies.
+//!
+//! ```text
+//! # any player in the world can attack any monster in the world.
+//! @id("attack-monsters")
+//! @tags("pve")
+//! allow player@world:*
+//!   to world:*:attack
+//!   on monster@world:*
+//!   when data["player:level"] >= 10;
+//! ```
+//!
+//! Principals and resources are written as `noun@scope:id`, and actions as `scope:noun:verb`.
+//! Segments containing characters other than letters, digits, `_`, `-`, `.` and `*` are quoted.
+//! Each `when` clause is a [`Condition`], containing string equals criteria in braces, an
+//! [`Expression`], or both joined by `and`. An empty list is written as `none`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display, Write};
+
+use crate::{
+    collections::{Map, Set},
+    Action, Condition, Effect, Expression, Metadata, Policy, Principal, Resource,
+};
+
+/// Error.
+///
+/// Returned when a policy fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Column, starting at 1.
+    pub column: usize,
+
+    /// Line, starting at 1.
+    pub line: usize,
+
+    /// Message.
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Format Error.
+///
+/// Returned when a policy cannot be written in the DSL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    /// Policy id.
+    pub id: String,
+
+    /// Message.
+    pub message: String,
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "policy `{}`: {}", self.id, self.message)
+    }
+}
+
+impl core::error::Error for FormatError {}
+
+/// Parses policies.
+///
+/// # Errors
+///
+/// Returns [`Error`] with the line and column of the first syntax error.
+pub fn parse(source: &str) -> Result<Vec<Policy>, Error> {
+    let mut parser = Parser { offset: 0, source };
+
+    let mut policies = Vec::new();
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            return Ok(policies);
+        }
+        policies.push(parser.parse_policy()?);
+    }
+}
+
+/// Formats policies.
+///
+/// The output can be parsed back into the same policies with [`parse`].
+///
+/// # Errors
+///
+/// Returns [`FormatError`] if a principal or resource of a policy has attributes, which the DSL
+/// cannot express.
+pub fn format(policies: &[Policy]) -> Result<String, FormatError> {
+    let mut output = String::new();
+
+    for (index, policy) in policies.iter().enumerate() {
+        let attributes = policy
+            .principals
+            .iter()
+            .map(|principal| &principal.attributes)
+            .chain(policy.resources.iter().map(|resource| &resource.attributes));
+        for attributes in attributes {
+            if !attributes.is_empty() {
+                return Err(FormatError {
+                    id: policy.id.clone(),
+                    message: "principals and resources cannot have attributes".to_string(),
+                });
+            }
+        }
+
+        if index > 0 {
+            output.push('\n');
+        }
+        format_policy(&mut output, policy);
+    }
+
+    Ok(output)
+}
+
+/// Formats a [`Policy`].
+fn format_policy(output: &mut String, policy: &Policy) {
+    let metadata = &policy.metadata;
+
+    annotation(output, "id", [&policy.id]);
+    if let Some(value) = metadata.created_at {
+        annotation(output, "created_at", [&value.to_string()]);
+    }
+    if let Some(value) = &metadata.description {
+        annotation(output, "description", [value]);
+    }
+    if let Some(value) = &metadata.owner {
+        annotation(output, "owner", [value]);
+    }
+    if !metadata.tags.is_empty() {
+        annotation(output, "tags", &metadata.tags);
+    }
+    if let Some(value) = metadata.updated_at {
+        annotation(output, "updated_at", [&value.to_string()]);
+    }
+    if let Some(value) = metadata.version {
+        let _ = writeln!(output, "@version({value})");
+    }
+
+    output.push_str(match policy.effect {
+        Effect::Allow => "allow ",
+        Effect::Deny => "deny ",
+    });
+    list(output, &policy.principals, |output, principal| {
+        entity(output, &principal.noun, &principal.scope, &principal.id);
+    });
+
+    output.push_str("\n  to ");
+    list(output, &policy.actions, |output, action| {
+        segment(output, &action.scope);
+        output.push(':');
+        segment(output, &action.noun);
+        output.push(':');
+        segment(output, &action.verb);
+    });
+
+    output.push_str("\n  on ");
+    list(output, &policy.resources, |output, resource| {
+        entity(output, &resource.noun, &resource.scope, &resource.id);
+    });
+
+    for condition in &policy.conditions {
+        output.push_str("\n  when");
+        if let Some(string_equals) = &condition.string_equals {
+            output.push_str(" {");
+            // `Map` and `Set` are hash based with `std`, so sort for a stable output.
+            let mut keys = string_equals.keys().collect::<Vec<_>>();
+            keys.sort();
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    output.push_str(", ");
+                }
+                string(output, key);
+                output.push_str(": [");
+                let mut values = string_equals[key].iter().collect::<Vec<_>>();
+                values.sort();
+                for (index, value) in values.into_iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(", ");
+                    }
+                    string(output, value);
+                }
+                output.push(']');
+            }
+            output.push('}');
+            if condition.expression.is_some() {
+                output.push_str(" and");
+            }
+        }
+        if let Some(expression) = &condition.expression {
+            output.push(' ');
+            output.push_str(expression.source());
+        }
+    }
+
+    output.push_str(";\n");
+}
+
+/// Formats an annotation.
+fn annotation<'a>(output: &mut String, name: &str, values: impl IntoIterator<Item = &'a String>) {
+    output.push('@');
+    output.push_str(name);
+    output.push('(');
+    for (index, value) in values.into_iter().enumerate() {
+        if index > 0 {
+            output.push_str(", ");
+        }
+        string(output, value);
+    }
+    output.push_str(")\n");
+}
+
+/// Formats a principal or resource.
+fn entity(output: &mut String, noun: &str, scope: &str, id: &str) {
+    segment(output, noun);
+    output.push('@');
+    segment(output, scope);
+    output.push(':');
+    segment(output, id);
+}
+
+/// Formats a comma separated list, or `none`.
+fn list<T>(output: &mut String, items: &[T], mut item: impl FnMut(&mut String, &T)) {
+    if items.is_empty() {
+        output.push_str("none");
+    }
+    for (index, value) in items.iter().enumerate() {
+        if index > 0 {
+            output.push_str(", ");
+        }
+        item(output, value);
+    }
+}
+
+/// Formats a segment, quoting it if required.
+fn segment(output: &mut String, value: &str) {
+    if !value.is_empty() && value.chars().all(is_segment_char) {
+        output.push_str(value);
+    } else {
+        string(output, value);
+    }
+}
+
+/// Formats a quoted string.
+fn string(output: &mut String, value: &str) {
+    output.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            char => output.push(char),
+        }
+    }
+    output.push('"');
+}
+
+/// Returns true if the character can appear in an unquoted segment.
+fn is_segment_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || matches!(char, '_' | '-' | '.' | '*')
+}
+
+/// Parser.
+struct Parser<'a> {
+    offset: usize,
+    source: &'a str,
+}
+
+impl Parser<'_> {
+    /// `policy := annotation* ("allow" | "deny") principals "to" actions "on" resources condition* ";"`
+    fn parse_policy(&mut self) -> Result<Policy, Error> {
+        let mut id = None;
+        let mut metadata = Metadata::default();
+
+        self.skip_whitespace();
+        while self.peek() == Some('@') {
+            let offset = self.offset;
+            self.offset += 1;
+            let name = self.word()?;
+            let values = self.parse_annotation_values()?;
+            let single = |values: Vec<String>| match <[String; 1]>::try_from(values) {
+                Ok([value]) => Ok(value),
+                Err(_) => Err(self.error_at(offset, format!("`@{name}` expects one value"))),
+            };
+            match name.as_str() {
+                "created_at" | "updated_at" => {
+                    let timestamp = single(values)?.parse().map_err(|_| {
+                        self.error_at(offset, format!("`@{name}` expects an RFC 3339 timestamp"))
+                    })?;
+                    if name == "created_at" {
+                        metadata.created_at = Some(timestamp);
+                    } else {
+                        metadata.updated_at = Some(timestamp);
+                    }
+                }
+                "description" => metadata.description = Some(single(values)?),
+                "id" => id = Some(single(values)?),
+                "owner" => metadata.owner = Some(single(values)?),
+                "tags" => metadata.tags = values,
+                "version" => {
+                    let version = single(values)?;
+                    metadata.version = Some(version.parse().map_err(|_| {
+                        self.error_at(offset, "`@version` expects an integer".to_string())
+                    })?);
+                }
+                _ => return Err(self.error_at(offset, format!("unknown annotation `@{name}`"))),
+            }
+            self.skip_whitespace();
+        }
+
+        let offset = self.offset;
+        let effect = match self.word()?.as_str() {
+            "allow" => Effect::Allow,
+            "deny" => Effect::Deny,
+            _ => return Err(self.error_at(offset, "expected `allow` or `deny`".to_string())),
+        };
+        let Some(id) = id else {
+            return Err(self.error_at(offset, "missing `@id`".to_string()));
+        };
+
+        let principals = self.parse_list(|parser| {
+            let (noun, scope, id) = parser.parse_entity()?;
+            Ok(Principal {
+                attributes: Map::new(),
+                id,
+                noun,
+                scope,
+            })
+        })?;
+
+        self.keyword("to")?;
+        let actions = self.parse_list(|parser| {
+            let scope = parser.segment()?;
+            parser.expect(':')?;
+            let noun = parser.segment()?;
+            parser.expect(':')?;
+            let verb = parser.segment()?;
+            Ok(Action { noun, scope, verb })
+        })?;
+
+        self.keyword("on")?;
+        let resources = self.parse_list(|parser| {
+            let (noun, scope, id) = parser.parse_entity()?;
+            Ok(Resource {
+                attributes: Map::new(),
+                id,
+                noun,
+                scope,
+            })
+        })?;
+
+        let mut conditions = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(';') {
+                self.offset += 1;
+                break;
+            }
+            self.keyword("when")?;
+            conditions.push(self.parse_condition()?);
+        }
+
+        Ok(Policy {
+            actions,
+            conditions,
+            effect,
+            id,
+            metadata,
+            principals,
+            resources,
+        })
+    }
+
+    /// `annotation_values := "(" value ("," value)* ")"`
+    fn parse_annotation_values(&mut self) -> Result<Vec<String>, Error> {
+        self.expect('(')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            values.push(match self.peek() {
+                Some('"') => self.string()?,
+                _ => self.word()?,
+            });
+            self.skip_whitespace();
+            if self.peek() != Some(',') {
+                break;
+            }
+            self.offset += 1;
+        }
+        self.expect(')')?;
+        Ok(values)
+    }
+
+    /// `condition := ["{" entries "}" ["and"]] [expression]`
+    fn parse_condition(&mut self) -> Result<Condition, Error> {
+        let mut condition = Condition::default();
+
+        self.skip_whitespace();
+        if self.peek() == Some('{') {
+            condition.string_equals = Some(self.parse_string_equals()?);
+            self.skip_whitespace();
+            if !self.rest().starts_with("and") || !self.is_keyword_boundary(self.offset + 3) {
+                return Ok(condition);
+            }
+            self.offset += 3;
+        }
+
+        let start = self.offset;
+        let end = self.scan_expression();
+        let source = self.source[start..end].trim_end();
+        if !source.trim().is_empty() {
+            let expression = source.trim_start();
+            let start = start + (source.len() - expression.len());
+            condition.expression = Some(expression.parse::<Expression>().map_err(|error| {
+                let (line, column) = self.position(start);
+                Error {
+                    column: if error.line == 1 {
+                        column + error.column - 1
+                    } else {
+                        error.column
+                    },
+                    line: line + error.line - 1,
+                    message: error.message,
+                }
+            })?);
+        }
+        self.offset = end;
+
+        Ok(condition)
+    }
+
+    /// `string_equals := "{" (string ":" "[" string ("," string)* "]") ("," ...)* "}"`
+    fn parse_string_equals(&mut self) -> Result<Map<String, Set<String>>, Error> {
+        let mut string_equals = Map::new();
+
+        self.expect('{')?;
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.offset += 1;
+            return Ok(string_equals);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            self.expect('[')?;
+            let mut values = Set::new();
+            self.skip_whitespace();
+            if self.peek() != Some(']') {
+                loop {
+                    self.skip_whitespace();
+                    values.insert(self.string()?);
+                    self.skip_whitespace();
+                    if self.peek() != Some(',') {
+                        break;
+                    }
+                    self.offset += 1;
+                }
+            }
+            self.expect(']')?;
+            string_equals.insert(key, values);
+
+            self.skip_whitespace();
+            if self.peek() != Some(',') {
+                break;
+            }
+            self.offset += 1;
+        }
+        self.expect('}')?;
+
+        Ok(string_equals)
+    }
+
+    /// `entity := segment "@" segment ":" segment`
+    fn parse_entity(&mut self) -> Result<(String, String, String), Error> {
+        let noun = self.segment()?;
+        self.expect('@')?;
+        let scope = self.segment()?;
+        self.expect(':')?;
+        let id = self.segment()?;
+        Ok((noun, scope, id))
+    }
+
+    /// `list := "none" | item ("," item)*`
+    fn parse_list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        self.skip_whitespace();
+        if self.rest().starts_with("none") && self.is_keyword_boundary(self.offset + 4) {
+            let offset = self.offset;
+            self.offset += 4;
+            self.skip_whitespace();
+            if !matches!(self.peek(), Some('@' | ':')) {
+                return Ok(Vec::new());
+            }
+            self.offset = offset;
+        }
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            items.push(item(self)?);
+            self.skip_whitespace();
+            if self.peek() != Some(',') {
+                return Ok(items);
+            }
+            self.offset += 1;
+        }
+    }
+
+    /// Returns the end offset of an expression starting at the current offset.
+    ///
+    /// An expression ends before the next `;` or `when` that is not inside a string.
+    fn scan_expression(&self) -> usize {
+        let mut chars = self.source[self.offset..].char_indices().peekable();
+        let mut previous = ' ';
+
+        while let Some((index, char)) = chars.next() {
+            let offset = self.offset + index;
+            match char {
+                ';' => return offset,
+                '"' => {
+                    while let Some((_, char)) = chars.next() {
+                        match char {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                'w' if !is_identifier_char(previous)
+                    && self.source[offset..].starts_with("when")
+                    && self.is_keyword_boundary(offset + 4) =>
+                {
+                    return offset;
+                }
+                _ => {}
+            }
+            previous = char;
+        }
+
+        self.source.len()
+    }
+
+    /// Consumes a keyword.
+    fn keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        self.skip_whitespace();
+        let offset = self.offset;
+        if self.rest().starts_with(keyword) && self.is_keyword_boundary(offset + keyword.len()) {
+            self.offset += keyword.len();
+            return Ok(());
+        }
+        Err(self.error_at(offset, format!("expected `{keyword}`")))
+    }
+
+    /// Consumes a segment, either a quoted string or a run of segment characters.
+    fn segment(&mut self) -> Result<String, Error> {
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+
+        let start = self.offset;
+        while self.peek().is_some_and(is_segment_char) {
+            self.offset += 1;
+        }
+        if start == self.offset {
+            return Err(self.error_at(start, "expected segment".to_string()));
+        }
+        Ok(self.source[start..self.offset].to_string())
+    }
+
+    /// Consumes a quoted string.
+    fn string(&mut self) -> Result<String, Error> {
+        let start = self.offset;
+        self.expect('"')?;
+
+        let mut value = String::new();
+        loop {
+            let Some(char) = self.peek() else {
+                return Err(self.error_at(start, "unterminated string".to_string()));
+            };
+            self.offset += char.len_utf8();
+            match char {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escape = self.offset;
+                    let char = self.peek();
+                    self.offset += char.map_or(0, char::len_utf8);
+                    match char {
+                        Some('"') => value.push('"'),
+                        Some('\\') => value.push('\\'),
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        _ => {
+                            return Err(self.error_at(escape, "unknown escape sequence".to_string()))
+                        }
+                    }
+                }
+                char => value.push(char),
+            }
+        }
+    }
+
+    /// Consumes an identifier.
+    fn word(&mut self) -> Result<String, Error> {
+        let start = self.offset;
+        while self.peek().is_some_and(is_identifier_char) {
+            self.offset += 1;
+        }
+        if start == self.offset {
+            return Err(self.error_at(start, "expected identifier".to_string()));
+        }
+        Ok(self.source[start..self.offset].to_string())
+    }
+
+    /// Consumes an expected character, after any whitespace.
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.offset += 1;
+            return Ok(());
+        }
+        Err(self.error_at(self.offset, format!("expected `{expected}`")))
+    }
+
+    /// Skips whitespace and `#` comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(char) = self.peek() {
+            if char == '#' {
+                self.offset = self
+                    .rest()
+                    .find('\n')
+                    .map_or(self.source.len(), |index| self.offset + index);
+            } else if char.is_whitespace() {
+                self.offset += char.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns true if a keyword ending at the offset is not followed by an identifier character.
+    fn is_keyword_boundary(&self, offset: usize) -> bool {
+        !self.source[offset..]
+            .chars()
+            .next()
+            .is_some_and(is_identifier_char)
+    }
+
+    /// Returns the next character.
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Returns the remaining source.
+    fn rest(&self) -> &str {
+        &self.source[self.offset..]
+    }
+
+    /// Returns the line and column of an offset.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rfind('\n')
+            .map_or(before, |index| &before[index + 1..])
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+
+    /// Returns an [`Error`] at an offset.
+    fn error_at(&self, offset: usize, message: String) -> Error {
+        let (line, column) = self.position(offset);
+        Error {
+            column,
+            line,
+            message,
+        }
+    }
+}
+
+/// Returns true if the character can appear in an identifier.
+fn is_identifier_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
@@ -0,0 +1,548 @@
+//! Expression.
+//!
+//! A small, side-effect-free expression language used by [`Condition`](crate::Condition).
+//!
+//! ```text
+//! data["player:level"] >= 10 && principal.scope == resource.scope
+//! ```
+//!
+//! Typed data values are compared natively, and `decimal("1.5")`, `ip("127.0.0.1")` and
+//! `timestamp("2023-11-01T12:00:00Z")` construct literals of the matching types. A set holding a
+//! single string is converted to the type of the value it is compared with, so untyped data
+//! written as a set of strings keeps working.
+//!
+//! Besides `id`, `noun` and `scope`, `principal.<attribute>` and `resource.<attribute>` address the
+//! attributes of the principal and resource.
+//!
+//! Expressions are parsed and type-checked once, when they are constructed or deserialized.
+//! Evaluation has no loops or user-defined functions, and the size and nesting of an expression
+//! are capped, so the cost of evaluating an expression is bounded by its size.
+
+mod parser;
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    net::IpAddr,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    borrowed::{ContextRef, Values},
+    value::{self, Decimal, Timestamp},
+};
+
+/// Maximum length, in bytes, of an expression source.
+pub const MAX_LENGTH: usize = 4096;
+
+/// Maximum number of nodes in an expression.
+pub const MAX_NODES: usize = 256;
+
+/// Maximum nesting depth of an expression.
+pub const MAX_DEPTH: usize = 32;
+
+/// Expression.
+///
+/// Holds the source of an expression alongside its type-checked syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    expr: Expr,
+    source: String,
+}
+
+impl Expression {
+    /// Creates an expression from a syntax tree already type-checked from its source.
+    pub(crate) fn from_parts(expr: Expr, source: String) -> Self {
+        Expression { expr, source }
+    }
+
+    /// Returns the syntax tree.
+    #[must_use]
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// Returns the source, without surrounding whitespace.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns true if the expression evaluates to `true` against the [`Context`](crate::Context).
+    ///
+    /// A runtime error, such as a missing data key or a data value that cannot be converted to
+    /// the type required by an operator, makes the expression evaluate to `false`. As in CEL,
+    /// `&&` and `||` absorb an error of one operand when the other decides the result, so
+    /// `data["missing"] == "x" || true` is `true`.
+    #[must_use]
+    pub fn evaluate<'c>(&self, context: impl Into<ContextRef<'c>>) -> bool {
+        matches(&self.expr, &context.into())
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = parser::parse(s)?;
+
+        Ok(Expression {
+            expr,
+            source: s.trim().to_string(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        source.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Expression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Expression {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Expression".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Expression.",
+            "maxLength": MAX_LENGTH,
+            "type": "string",
+        })
+    }
+}
+
+/// Expr.
+///
+/// A node of a type-checked syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// Logical and, `left && right`.
+    And(Box<Expr>, Box<Expr>),
+
+    /// Attribute of an entity, `principal.id` or `resource.level`.
+    Attribute(Entity, String),
+
+    /// Boolean literal, `true`.
+    Bool(bool),
+
+    /// Comparison, `left == right`.
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+
+    /// Data value, `data["key"]`.
+    Data(String),
+
+    /// Decimal literal, `decimal("1.5")`.
+    Decimal(Decimal),
+
+    /// Field of a record, `data["key"]["field"]`.
+    Field(Box<Expr>, String),
+
+    /// Presence of a data value, `has(data["key"])`.
+    Has(String),
+
+    /// Membership, `left in right`.
+    In(Box<Expr>, Box<Expr>),
+
+    /// Integer literal, `10`.
+    Int(i64),
+
+    /// IP address literal, `ip("127.0.0.1")`.
+    Ip(IpAddr),
+
+    /// List literal, `["a", "b"]`.
+    List(Vec<Expr>),
+
+    /// Logical not, `!expr`.
+    Not(Box<Expr>),
+
+    /// Logical or, `left || right`.
+    Or(Box<Expr>, Box<Expr>),
+
+    /// Size of a list, string or data value, `size(expr)`.
+    Size(Box<Expr>),
+
+    /// String literal, `"a"`.
+    String(String),
+
+    /// Timestamp literal, `timestamp("2023-11-01T12:00:00Z")`.
+    Timestamp(Timestamp),
+}
+
+/// Comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `==`.
+    Equal,
+
+    /// `>`.
+    GreaterThan,
+
+    /// `>=`.
+    GreaterThanOrEqual,
+
+    /// `<`.
+    LessThan,
+
+    /// `<=`.
+    LessThanOrEqual,
+
+    /// `!=`.
+    NotEqual,
+}
+
+/// Entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    /// `action`.
+    Action,
+
+    /// `principal`.
+    Principal,
+
+    /// `resource`.
+    Resource,
+}
+
+/// Error.
+///
+/// Returned when an expression fails to parse or type-check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Column, starting at 1.
+    pub column: usize,
+
+    /// Line, starting at 1.
+    pub line: usize,
+
+    /// Message.
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Runtime value.
+#[derive(Debug, Clone)]
+enum Value<'a> {
+    Bool(bool),
+    Decimal(Decimal),
+    Int(i64),
+    Ip(IpAddr),
+    List(Vec<Value<'a>>),
+    Record(&'a BTreeMap<String, value::Value>),
+    Set(Values<'a>),
+    String(&'a str),
+    Timestamp(Timestamp),
+}
+
+/// Returns true if an [`Expr`] evaluates to `true` against the [`ContextRef`].
+pub(crate) fn matches(expr: &Expr, context: &ContextRef) -> bool {
+    matches!(eval(expr, context), Ok(Value::Bool(true)))
+}
+
+/// Evaluates an [`Expr`] against a [`ContextRef`].
+///
+/// Returns `Err` if a data value is missing or cannot be converted.
+fn eval<'a>(expr: &'a Expr, context: &ContextRef<'a>) -> Result<Value<'a>, ()> {
+    Ok(match expr {
+        Expr::And(left, right) => Value::Bool(logical(false, eval_bool(left, context), || {
+            eval_bool(right, context)
+        })?),
+        Expr::Attribute(entity, attribute) => attribute_of(context, *entity, attribute)?,
+        Expr::Bool(value) => Value::Bool(*value),
+        Expr::Compare(comparison, left, right) => Value::Bool(compare(
+            *comparison,
+            eval(left, context)?,
+            eval(right, context)?,
+        )?),
+        Expr::Data(key) => from_values(context.data.get(key).ok_or(())?),
+        Expr::Decimal(value) => Value::Decimal(*value),
+        Expr::Field(expr, field) => match eval(expr, context)? {
+            Value::Record(record) => from_data(record.get(field).ok_or(())?),
+            Value::Set(set) => match single(set)? {
+                Value::Record(record) => from_data(record.get(field).ok_or(())?),
+                _ => return Err(()),
+            },
+            _ => return Err(()),
+        },
+        Expr::Has(key) => Value::Bool(context.data.get(key).is_some()),
+        Expr::In(left, right) => {
+            Value::Bool(contains(&eval(right, context)?, &eval(left, context)?)?)
+        }
+        Expr::Int(value) => Value::Int(*value),
+        Expr::Ip(value) => Value::Ip(*value),
+        Expr::List(items) => Value::List(
+            items
+                .iter()
+                .map(|item| eval(item, context))
+                .collect::<Result<_, _>>()?,
+        ),
+        Expr::Not(expr) => Value::Bool(!to_bool(&eval(expr, context)?)?),
+        Expr::Or(left, right) => Value::Bool(logical(true, eval_bool(left, context), || {
+            eval_bool(right, context)
+        })?),
+        Expr::Size(expr) => Value::Int(size(&eval(expr, context)?)?),
+        Expr::String(value) => Value::String(value),
+        Expr::Timestamp(value) => Value::Timestamp(*value),
+    })
+}
+
+/// Evaluates an [`Expr`] into a boolean.
+fn eval_bool(expr: &Expr, context: &ContextRef) -> Result<bool, ()> {
+    to_bool(&eval(expr, context)?)
+}
+
+/// Combines the operands of `&&`, absorbed by `false`, or `||`, absorbed by `true`.
+///
+/// As in CEL, an operand equal to the absorbing value decides the result even if the other
+/// operand fails, so `data["missing"] == "x" || true` is `true`.
+fn logical(
+    absorbing: bool,
+    left: Result<bool, ()>,
+    right: impl FnOnce() -> Result<bool, ()>,
+) -> Result<bool, ()> {
+    match (left, right) {
+        (Ok(left), _) if left == absorbing => Ok(absorbing),
+        (Ok(_), right) => right(),
+        (Err(()), right) => match right() {
+            Ok(right) if right == absorbing => Ok(absorbing),
+            _ => Err(()),
+        },
+    }
+}
+
+/// Returns true if any element of a list or set satisfies a predicate.
+fn any<'a>(
+    collection: &Value<'a>,
+    mut predicate: impl FnMut(Value<'a>) -> bool,
+) -> Result<bool, ()> {
+    Ok(match collection {
+        Value::List(items) => items.iter().cloned().any(predicate),
+        Value::Set(Values::One(one)) => predicate(Value::String(one)),
+        Value::Set(Values::Set(set)) => set.iter().any(|item| predicate(Value::String(item))),
+        Value::Set(Values::Slice(slice)) => slice.iter().any(|item| predicate(Value::String(item))),
+        Value::Set(Values::Value(value::Value::Set(set))) => {
+            set.iter().any(|item| predicate(from_data(item)))
+        }
+        Value::Set(Values::Value(value)) => predicate(from_data(value)),
+        _ => return Err(()),
+    })
+}
+
+/// Returns the attribute of an entity in the [`ContextRef`].
+///
+/// Attributes other than `id`, `noun` and `scope` are looked up in the attributes of the
+/// principal or resource.
+fn attribute_of<'a>(
+    context: &ContextRef<'a>,
+    entity: Entity,
+    attribute: &str,
+) -> Result<Value<'a>, ()> {
+    let attributes = match (entity, attribute) {
+        (Entity::Action, "noun") => return Ok(Value::String(context.action.noun)),
+        (Entity::Action, "scope") => return Ok(Value::String(context.action.scope)),
+        (Entity::Action, "verb") => return Ok(Value::String(context.action.verb)),
+        (Entity::Principal, "id") => return Ok(Value::String(context.principal.id)),
+        (Entity::Principal, "noun") => return Ok(Value::String(context.principal.noun)),
+        (Entity::Principal, "scope") => return Ok(Value::String(context.principal.scope)),
+        (Entity::Resource, "id") => return Ok(Value::String(context.resource.id)),
+        (Entity::Resource, "noun") => return Ok(Value::String(context.resource.noun)),
+        (Entity::Resource, "scope") => return Ok(Value::String(context.resource.scope)),
+        (Entity::Action, _) => return Err(()),
+        (Entity::Principal, _) => context.principal.attributes,
+        (Entity::Resource, _) => context.resource.attributes,
+    };
+
+    attributes.get(attribute).map(from_values).ok_or(())
+}
+
+/// Compares two values.
+///
+/// A string compared with a bool, number, IP address or timestamp is parsed into its type.
+fn compare(comparison: Comparison, left: Value, right: Value) -> Result<bool, ()> {
+    let ordering = match (left, right) {
+        (left @ (Value::List(_) | Value::Set(_)), right @ (Value::List(_) | Value::Set(_))) => {
+            return equality(comparison, same_elements(&left, &right)?)
+        }
+        (Value::Set(set), other) => return compare(comparison, single(set)?, other),
+        (other, Value::Set(set)) => return compare(comparison, other, single(set)?),
+        (Value::Record(left), Value::Record(right)) => return equality(comparison, left == right),
+        (Value::String(left), Value::String(right)) => left.cmp(right),
+        (Value::String(left), right) => return compare(comparison, parse(left, &right)?, right),
+        (left, Value::String(right)) => {
+            let right = parse(right, &left)?;
+            return compare(comparison, left, right);
+        }
+        (Value::Bool(left), Value::Bool(right)) => left.cmp(&right),
+        (Value::Decimal(left), Value::Decimal(right)) => left.cmp(&right),
+        (Value::Decimal(left), Value::Int(right)) => compare_decimal(left, right),
+        (Value::Int(left), Value::Decimal(right)) => compare_decimal(right, left).reverse(),
+        (Value::Int(left), Value::Int(right)) => left.cmp(&right),
+        (Value::Ip(left), Value::Ip(right)) => left.cmp(&right),
+        (Value::Timestamp(left), Value::Timestamp(right)) => left.cmp(&right),
+        _ => return Err(()),
+    };
+
+    Ok(match comparison {
+        Comparison::Equal => ordering.is_eq(),
+        Comparison::GreaterThan => ordering.is_gt(),
+        Comparison::GreaterThanOrEqual => ordering.is_ge(),
+        Comparison::LessThan => ordering.is_lt(),
+        Comparison::LessThanOrEqual => ordering.is_le(),
+        Comparison::NotEqual => ordering.is_ne(),
+    })
+}
+
+/// Compares a decimal with an integer.
+fn compare_decimal(decimal: Decimal, int: i64) -> Ordering {
+    i128::from(decimal.units()).cmp(&(i128::from(int) * i128::from(Decimal::SCALE)))
+}
+
+/// Returns true if the collection contains the value.
+fn contains(collection: &Value, value: &Value) -> Result<bool, ()> {
+    match (collection, value) {
+        (Value::Record(record), Value::String(key)) => Ok(record.contains_key(*key)),
+        (Value::List(_) | Value::Set(_), Value::List(_)) => Err(()),
+        (Value::List(_) | Value::Set(_), Value::Set(set)) => match single(*set) {
+            Ok(value) => any(collection, |item| equals(item, value.clone())),
+            Err(()) => Ok(false),
+        },
+        (Value::List(_) | Value::Set(_), _) => any(collection, |item| equals(item, value.clone())),
+        _ => Err(()),
+    }
+}
+
+/// Returns true if two values are equal, and false if they cannot be compared.
+fn equals(left: Value, right: Value) -> bool {
+    compare(Comparison::Equal, left, right).unwrap_or(false)
+}
+
+/// Returns the result of an equality comparison.
+fn equality(comparison: Comparison, equal: bool) -> Result<bool, ()> {
+    match comparison {
+        Comparison::Equal => Ok(equal),
+        Comparison::NotEqual => Ok(!equal),
+        _ => Err(()),
+    }
+}
+
+/// Converts a data [`value::Value`] into a runtime value.
+fn from_data(data: &value::Value) -> Value<'_> {
+    match data {
+        value::Value::Bool(value) => Value::Bool(*value),
+        value::Value::Decimal(value) => Value::Decimal(*value),
+        value::Value::Int(value) => Value::Int(*value),
+        value::Value::Ip(value) => Value::Ip(*value),
+        value::Value::Record(record) => Value::Record(record),
+        value::Value::Set(_) => Value::Set(Values::Value(data)),
+        value::Value::String(value) => Value::String(value),
+        value::Value::Timestamp(value) => Value::Timestamp(*value),
+    }
+}
+
+/// Converts [`Values`] into a runtime value.
+fn from_values(values: Values) -> Value {
+    match values {
+        Values::Value(value) => from_data(value),
+        values => Value::Set(values),
+    }
+}
+
+/// Parses a string into the type of another value.
+fn parse<'a>(value: &str, like: &Value) -> Result<Value<'a>, ()> {
+    match like {
+        Value::Bool(_) => value.parse().map(Value::Bool).map_err(|_| ()),
+        Value::Decimal(_) => value.parse().map(Value::Decimal).map_err(|_| ()),
+        Value::Int(_) => value.parse().map(Value::Int).map_err(|_| ()),
+        Value::Ip(_) => value.parse().map(Value::Ip).map_err(|_| ()),
+        Value::Timestamp(_) => value.parse().map(Value::Timestamp).map_err(|_| ()),
+        _ => Err(()),
+    }
+}
+
+/// Returns true if every element of each collection equals an element of the other.
+fn same_elements(left: &Value, right: &Value) -> Result<bool, ()> {
+    let within = |outer: &Value, inner: &Value| {
+        Ok::<_, ()>(!any(outer, |x| {
+            !any(inner, |y| equals(x.clone(), y)).unwrap_or(false)
+        })?)
+    };
+
+    Ok(within(left, right)? && within(right, left)?)
+}
+
+/// Returns the only element of a set.
+fn single(set: Values) -> Result<Value, ()> {
+    if set.len() != 1 {
+        return Err(());
+    }
+
+    let mut only = None;
+    any(&Value::Set(set), |item| only.replace(item).is_some())?;
+    only.ok_or(())
+}
+
+/// Returns the size of a value.
+fn size(value: &Value) -> Result<i64, ()> {
+    let size = match value {
+        Value::List(items) => items.len(),
+        Value::Record(record) => record.len(),
+        Value::Set(set) => set.len(),
+        Value::String(value) => value.chars().count(),
+        _ => return Err(()),
+    };
+
+    i64::try_from(size).map_err(|_| ())
+}
+
+/// Converts a value into a boolean.
+fn to_bool(value: &Value) -> Result<bool, ()> {
+    match value {
+        Value::Bool(value) => Ok(*value),
+        Value::Set(set) => match single(*set)? {
+            Value::Bool(value) => Ok(value),
+            Value::String(value) => value.parse().map_err(|_| ()),
+            _ => Err(()),
+        },
+        _ => Err(()),
+    }
+}
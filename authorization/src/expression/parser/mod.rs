@@ -0,0 +1,588 @@
+//! Parser.
+//!
+//! Lexes, parses and type-checks an expression in a single pass.
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{Comparison, Entity, Error, Expr, MAX_DEPTH, MAX_LENGTH, MAX_NODES};
+use crate::value;
+
+/// Parses and type-checks an expression.
+pub(super) fn parse(source: &str) -> Result<Expr, Error> {
+    if source.len() > MAX_LENGTH {
+        return Err(error_at(
+            source,
+            0,
+            format!("expression exceeds {MAX_LENGTH} bytes"),
+        ));
+    }
+
+    let tokens = lex(source)?;
+    let mut parser = Parser {
+        depth: 0,
+        nodes: 0,
+        position: 0,
+        source,
+        tokens,
+    };
+
+    let (expr, ty) = parser.parse_or()?;
+
+    let token = parser.peek();
+    if token.kind != Kind::End {
+        return Err(parser.error(token.offset, "unexpected token".to_string()));
+    }
+
+    if !matches!(ty, Type::Bool | Type::Dyn) {
+        return Err(error_at(
+            source,
+            0,
+            format!("expression must be bool, found {ty}"),
+        ));
+    }
+
+    Ok(expr)
+}
+
+/// Token kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    And,
+    Bang,
+    Comma,
+    Dot,
+    End,
+    Equal,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Identifier(String),
+    Int(i64),
+    LeftBracket,
+    LeftParen,
+    LessThan,
+    LessThanOrEqual,
+    NotEqual,
+    Or,
+    RightBracket,
+    RightParen,
+    String(String),
+}
+
+/// Token.
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    offset: usize,
+}
+
+/// Type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Bool,
+    Decimal,
+    Dyn,
+    Int,
+    Ip,
+    List,
+    String,
+    Timestamp,
+}
+
+impl Type {
+    /// Returns true if values of the type can be ordered against values of another type.
+    fn is_ordered_with(self, other: Type) -> bool {
+        match (self, other) {
+            (Type::Dyn, ty) | (ty, Type::Dyn) => matches!(
+                ty,
+                Type::Decimal | Type::Dyn | Type::Int | Type::Ip | Type::String | Type::Timestamp
+            ),
+            _ => matches!(
+                (self, other),
+                (Type::Decimal | Type::Int, Type::Decimal | Type::Int)
+                    | (Type::Ip, Type::Ip)
+                    | (Type::String, Type::String)
+                    | (Type::Timestamp, Type::Timestamp)
+            ),
+        }
+    }
+
+    /// Returns true if values of the type can be a list item or the left side of `in`.
+    fn is_scalar(self) -> bool {
+        matches!(
+            self,
+            Type::Bool | Type::Decimal | Type::Int | Type::Ip | Type::String | Type::Timestamp
+        )
+    }
+}
+
+impl core::fmt::Display for Type {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Type::Bool => "bool",
+            Type::Decimal => "decimal",
+            Type::Dyn => "data value",
+            Type::Int => "int",
+            Type::Ip => "ip",
+            Type::List => "list",
+            Type::String => "string",
+            Type::Timestamp => "timestamp",
+        })
+    }
+}
+
+/// Parser.
+struct Parser<'a> {
+    depth: usize,
+    nodes: usize,
+    position: usize,
+    source: &'a str,
+    tokens: Vec<Token>,
+}
+
+impl Parser<'_> {
+    /// `or := and ("||" and)*`
+    fn parse_or(&mut self) -> Result<(Expr, Type), Error> {
+        let (mut left, mut left_ty) = self.parse_and()?;
+
+        while self.peek().kind == Kind::Or {
+            let offset = self.next().offset;
+            let (right, right_ty) = self.parse_and()?;
+            self.expect_bool(offset, left_ty, right_ty)?;
+            self.node(offset)?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+            left_ty = Type::Bool;
+        }
+
+        Ok((left, left_ty))
+    }
+
+    /// `and := unary ("&&" unary)*`
+    fn parse_and(&mut self) -> Result<(Expr, Type), Error> {
+        let (mut left, mut left_ty) = self.parse_unary()?;
+
+        while self.peek().kind == Kind::And {
+            let offset = self.next().offset;
+            let (right, right_ty) = self.parse_unary()?;
+            self.expect_bool(offset, left_ty, right_ty)?;
+            self.node(offset)?;
+            left = Expr::And(Box::new(left), Box::new(right));
+            left_ty = Type::Bool;
+        }
+
+        Ok((left, left_ty))
+    }
+
+    /// `unary := "!" unary | comparison`
+    fn parse_unary(&mut self) -> Result<(Expr, Type), Error> {
+        if self.peek().kind != Kind::Bang {
+            return self.parse_comparison();
+        }
+
+        let offset = self.next().offset;
+        self.enter(offset)?;
+        let (expr, ty) = self.parse_unary()?;
+        self.depth -= 1;
+        self.expect_bool(offset, ty, Type::Bool)?;
+        self.node(offset)?;
+
+        Ok((Expr::Not(Box::new(expr)), Type::Bool))
+    }
+
+    /// `comparison := primary (("==" | "!=" | "<" | "<=" | ">" | ">=" | "in") primary)?`
+    fn parse_comparison(&mut self) -> Result<(Expr, Type), Error> {
+        let (left, left_ty) = self.parse_primary()?;
+
+        let token = self.peek().clone();
+        let comparison = match &token.kind {
+            Kind::Equal => Comparison::Equal,
+            Kind::GreaterThan => Comparison::GreaterThan,
+            Kind::GreaterThanOrEqual => Comparison::GreaterThanOrEqual,
+            Kind::LessThan => Comparison::LessThan,
+            Kind::LessThanOrEqual => Comparison::LessThanOrEqual,
+            Kind::NotEqual => Comparison::NotEqual,
+            Kind::Identifier(identifier) if identifier == "in" => {
+                self.next();
+                let (right, right_ty) = self.parse_primary()?;
+                if !(left_ty.is_scalar() || left_ty == Type::Dyn)
+                    || !matches!(right_ty, Type::Dyn | Type::List)
+                {
+                    return Err(self.error(
+                        token.offset,
+                        format!("cannot check if {left_ty} is in {right_ty}"),
+                    ));
+                }
+                self.node(token.offset)?;
+                return Ok((Expr::In(Box::new(left), Box::new(right)), Type::Bool));
+            }
+            _ => return Ok((left, left_ty)),
+        };
+
+        self.next();
+        let (right, right_ty) = self.parse_primary()?;
+
+        let valid = match comparison {
+            Comparison::Equal | Comparison::NotEqual => {
+                left_ty == right_ty
+                    || left_ty == Type::Dyn
+                    || right_ty == Type::Dyn
+                    || matches!(
+                        (left_ty, right_ty),
+                        (Type::Decimal, Type::Int) | (Type::Int, Type::Decimal)
+                    )
+            }
+            _ => left_ty.is_ordered_with(right_ty),
+        };
+        if !valid {
+            return Err(self.error(
+                token.offset,
+                format!("cannot compare {left_ty} with {right_ty}"),
+            ));
+        }
+        self.node(token.offset)?;
+
+        Ok((
+            Expr::Compare(comparison, Box::new(left), Box::new(right)),
+            Type::Bool,
+        ))
+    }
+
+    /// `primary := literal | list | "(" or ")" | identifier ("." identifier | "[" string "]" | "(" or ")")? ("[" string "]")*`
+    fn parse_primary(&mut self) -> Result<(Expr, Type), Error> {
+        let token = self.next();
+        self.node(token.offset)?;
+
+        match token.kind {
+            Kind::Int(value) => Ok((Expr::Int(value), Type::Int)),
+            Kind::String(value) => Ok((Expr::String(value), Type::String)),
+            Kind::LeftParen => {
+                self.enter(token.offset)?;
+                let result = self.parse_or()?;
+                self.depth -= 1;
+                self.expect(&Kind::RightParen, "`)`")?;
+                Ok(result)
+            }
+            Kind::LeftBracket => {
+                self.enter(token.offset)?;
+                let mut items = Vec::new();
+                if self.peek().kind != Kind::RightBracket {
+                    loop {
+                        let item = self.peek().offset;
+                        let (expr, ty) = self.parse_primary()?;
+                        if !ty.is_scalar() {
+                            return Err(self.error(item, format!("list cannot contain {ty}")));
+                        }
+                        items.push(expr);
+                        if self.peek().kind != Kind::Comma {
+                            break;
+                        }
+                        self.next();
+                    }
+                }
+                self.depth -= 1;
+                self.expect(&Kind::RightBracket, "`]`")?;
+                Ok((Expr::List(items), Type::List))
+            }
+            Kind::Identifier(identifier) => self.parse_identifier(&identifier, token.offset),
+            _ => Err(self.error(token.offset, "expected expression".to_string())),
+        }
+    }
+
+    /// Parses the remainder of an expression starting with an identifier.
+    fn parse_identifier(&mut self, identifier: &str, offset: usize) -> Result<(Expr, Type), Error> {
+        let entity = match identifier {
+            "true" => return Ok((Expr::Bool(true), Type::Bool)),
+            "false" => return Ok((Expr::Bool(false), Type::Bool)),
+            "data" => {
+                let expr = Expr::Data(self.parse_data_key()?);
+                return Ok((self.parse_fields(expr)?, Type::Dyn));
+            }
+            "decimal" | "has" | "ip" | "size" | "timestamp" => {
+                return self.parse_function(identifier, offset)
+            }
+            "action" => Entity::Action,
+            "principal" => Entity::Principal,
+            "resource" => Entity::Resource,
+            _ => return Err(self.error(offset, format!("unknown identifier `{identifier}`"))),
+        };
+
+        self.expect(&Kind::Dot, "`.`")?;
+        let token = self.next();
+        let Kind::Identifier(attribute) = token.kind else {
+            return Err(self.error(token.offset, "expected attribute".to_string()));
+        };
+
+        let known = match entity {
+            Entity::Action => ["noun", "scope", "verb"].contains(&attribute.as_str()),
+            Entity::Principal | Entity::Resource => {
+                ["id", "noun", "scope"].contains(&attribute.as_str())
+            }
+        };
+        if known {
+            return Ok((Expr::Attribute(entity, attribute), Type::String));
+        }
+        if entity == Entity::Action {
+            return Err(self.error(
+                token.offset,
+                format!("unknown attribute `{identifier}.{attribute}`"),
+            ));
+        }
+
+        let expr = Expr::Attribute(entity, attribute);
+        Ok((self.parse_fields(expr)?, Type::Dyn))
+    }
+
+    /// Parses `("[" string "]")*` following a data value or entity attribute.
+    fn parse_fields(&mut self, mut expr: Expr) -> Result<Expr, Error> {
+        while self.peek().kind == Kind::LeftBracket {
+            let offset = self.peek().offset;
+            self.node(offset)?;
+            expr = Expr::Field(Box::new(expr), self.parse_data_key()?);
+        }
+        Ok(expr)
+    }
+
+    /// Parses `"[" string "]"`.
+    fn parse_data_key(&mut self) -> Result<String, Error> {
+        self.expect(&Kind::LeftBracket, "`[`")?;
+        let token = self.next();
+        let Kind::String(key) = token.kind else {
+            return Err(self.error(
+                token.offset,
+                "data key must be a string literal".to_string(),
+            ));
+        };
+        self.expect(&Kind::RightBracket, "`]`")?;
+        Ok(key)
+    }
+
+    /// Parses the arguments of a built-in function.
+    fn parse_function(&mut self, function: &str, offset: usize) -> Result<(Expr, Type), Error> {
+        self.expect(&Kind::LeftParen, "`(`")?;
+        self.enter(offset)?;
+
+        let result = if function == "has" {
+            let token = self.next();
+            if token.kind != Kind::Identifier("data".to_string()) {
+                return Err(self.error(token.offset, "`has` expects `data[...]`".to_string()));
+            }
+            (Expr::Has(self.parse_data_key()?), Type::Bool)
+        } else if function != "size" {
+            let token = self.next();
+            let Kind::String(literal) = token.kind else {
+                return Err(self.error(
+                    token.offset,
+                    format!("`{function}` expects a string literal"),
+                ));
+            };
+            let error = |error: value::Error| self.error(token.offset, error.message);
+            match function {
+                "decimal" => (
+                    Expr::Decimal(literal.parse().map_err(error)?),
+                    Type::Decimal,
+                ),
+                "ip" => (
+                    Expr::Ip(literal.parse().map_err(|_| {
+                        self.error(token.offset, format!("invalid IP address `{literal}`"))
+                    })?),
+                    Type::Ip,
+                ),
+                _ => (
+                    Expr::Timestamp(literal.parse().map_err(error)?),
+                    Type::Timestamp,
+                ),
+            }
+        } else {
+            let argument = self.peek().offset;
+            let (expr, ty) = self.parse_or()?;
+            if !matches!(ty, Type::Dyn | Type::List | Type::String) {
+                return Err(self.error(argument, format!("`size` cannot be applied to {ty}")));
+            }
+            (Expr::Size(Box::new(expr)), Type::Int)
+        };
+
+        self.depth -= 1;
+        self.expect(&Kind::RightParen, "`)`")?;
+        Ok(result)
+    }
+
+    /// Increases the nesting depth.
+    fn enter(&mut self, offset: usize) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(
+                offset,
+                format!("expression exceeds {MAX_DEPTH} levels of nesting"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Consumes a token of the expected kind.
+    fn expect(&mut self, kind: &Kind, description: &str) -> Result<(), Error> {
+        let token = self.next();
+        if &token.kind != kind {
+            return Err(self.error(token.offset, format!("expected {description}")));
+        }
+        Ok(())
+    }
+
+    /// Checks the operands of a logical operator.
+    fn expect_bool(&self, offset: usize, left: Type, right: Type) -> Result<(), Error> {
+        for ty in [left, right] {
+            if !matches!(ty, Type::Bool | Type::Dyn) {
+                return Err(self.error(offset, format!("expected bool, found {ty}")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts a node.
+    fn node(&mut self, offset: usize) -> Result<(), Error> {
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return Err(self.error(offset, format!("expression exceeds {MAX_NODES} nodes")));
+        }
+        Ok(())
+    }
+
+    /// Returns the next token and advances.
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token.kind != Kind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// Returns the next token.
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    /// Returns an [`Error`] at an offset.
+    fn error(&self, offset: usize, message: String) -> Error {
+        error_at(self.source, offset, message)
+    }
+}
+
+/// Returns an [`Error`] at an offset of the source.
+fn error_at(source: &str, offset: usize, message: String) -> Error {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before, |index| &before[index + 1..])
+        .chars()
+        .count()
+        + 1;
+
+    Error {
+        column,
+        line,
+        message,
+    }
+}
+
+/// Splits the source into tokens.
+fn lex(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((offset, char)) = chars.next() {
+        let mut pair = |second: char, kind: Kind| -> Option<Kind> {
+            chars.next_if(|(_, c)| *c == second).map(|_| kind)
+        };
+
+        let kind = match char {
+            c if c.is_whitespace() => continue,
+            '(' => Kind::LeftParen,
+            ')' => Kind::RightParen,
+            '[' => Kind::LeftBracket,
+            ']' => Kind::RightBracket,
+            ',' => Kind::Comma,
+            '.' => Kind::Dot,
+            '&' => pair('&', Kind::And)
+                .ok_or_else(|| error_at(source, offset, "expected `&&`".to_string()))?,
+            '|' => pair('|', Kind::Or)
+                .ok_or_else(|| error_at(source, offset, "expected `||`".to_string()))?,
+            '=' => pair('=', Kind::Equal)
+                .ok_or_else(|| error_at(source, offset, "expected `==`".to_string()))?,
+            '!' => pair('=', Kind::NotEqual).unwrap_or(Kind::Bang),
+            '<' => pair('=', Kind::LessThanOrEqual).unwrap_or(Kind::LessThan),
+            '>' => pair('=', Kind::GreaterThanOrEqual).unwrap_or(Kind::GreaterThan),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '"')) => value.push('"'),
+                            Some((_, '\\')) => value.push('\\'),
+                            Some((_, 'n')) => value.push('\n'),
+                            Some((_, 't')) => value.push('\t'),
+                            Some((escape, _)) => {
+                                return Err(error_at(
+                                    source,
+                                    escape,
+                                    "unknown escape sequence".to_string(),
+                                ))
+                            }
+                            None => {
+                                return Err(error_at(
+                                    source,
+                                    offset,
+                                    "unterminated string".to_string(),
+                                ))
+                            }
+                        },
+                        Some((_, c)) => value.push(c),
+                        None => {
+                            return Err(error_at(source, offset, "unterminated string".to_string()))
+                        }
+                    }
+                }
+                Kind::String(value)
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let mut end = offset + c.len_utf8();
+                while let Some((index, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = index + 1;
+                }
+                let value = source[offset..end]
+                    .parse()
+                    .map_err(|_| error_at(source, offset, "invalid integer".to_string()))?;
+                Kind::Int(value)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = offset + 1;
+                while let Some((index, _)) =
+                    chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    end = index + 1;
+                }
+                Kind::Identifier(source[offset..end].to_string())
+            }
+            _ => {
+                return Err(error_at(
+                    source,
+                    offset,
+                    format!("unexpected character `{char}`"),
+                ))
+            }
+        };
+
+        tokens.push(Token { kind, offset });
+    }
+
+    tokens.push(Token {
+        kind: Kind::End,
+        offset: source.len(),
+    });
+
+    Ok(tokens)
+}
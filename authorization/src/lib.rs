@@ -1,17 +1,65 @@
 #![deny(clippy::pedantic, missing_docs)]
 #![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! Authorization.
-
-#[cfg(test)]
+//!
+//! # Features
+//!
+//! - `binary`: encodes a [`PolicySet`] in a compact binary format.
+//! - `derive`: derives [`Actions`], [`AsPrincipal`] and [`AsResource`] for domain types.
+//! - `std` (default): uses the standard library. Without it, the crate only requires `alloc`,
+//!   and [`collections`] are ordered rather than hashed.
+//! - `schemars`: implements `JsonSchema` for [`Policy`], [`Context`] and [`PolicyDocument`],
+//!   producing a JSON Schema (Draft 2020-12). Expression syntax and the features of older
+//!   document versions are only checked when deserializing.
+//! - `toml`: loads and saves a [`PolicyDocument`] as TOML.
+//! - `yaml`: loads and saves a [`PolicyDocument`] as YAML.
+
+extern crate alloc;
+#[cfg(all(test, feature = "derive"))]
+extern crate self as authorization;
+
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod borrowed;
+pub mod builder;
+pub mod collections;
+pub mod document;
+pub mod dsl;
+pub mod expression;
+pub mod partial;
+pub mod schema;
+pub mod set;
+pub mod store;
+#[cfg(all(test, feature = "std"))]
 mod tests;
+pub mod urn;
+pub mod value;
 
-use std::collections::{HashMap, HashSet};
+use alloc::{string::String, vec::Vec};
 
 use serde::{Deserialize, Serialize};
 
+use borrowed::ActionRef;
+use collections::{Map, Set};
+use value::Timestamp;
+
+#[cfg(feature = "derive")]
+pub use authorization_derive::{Actions, AsPrincipal, AsResource};
+pub use borrowed::{ContextRef, Data};
+pub use document::PolicyDocument;
+pub use expression::Expression;
+pub use partial::{partial_evaluate, PartialContext};
+pub use schema::Schema;
+pub use set::PolicySet;
+pub use store::{AsyncPolicyStore, MemoryStore, PolicyStore};
+pub use value::Value;
+
 /// Action.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+///
+/// Deserializes from either its object form or its [`urn`] string form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Action {
     /// Noun.
     #[serde(rename = "noun")]
@@ -28,22 +76,31 @@ pub struct Action {
 
 /// Condition.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
 pub struct Condition {
+    /// Expression.
+    #[serde(rename = "expression")]
+    pub expression: Option<Expression>,
+
     /// String Equals.
     #[serde(rename = "string_equals")]
-    pub string_equals: Option<HashMap<String, HashSet<String>>>,
+    pub string_equals: Option<Map<String, Set<String>>>,
 }
 
 /// Context.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Context {
     /// Action.
     #[serde(rename = "action")]
     pub action: Action,
 
     /// Data.
+    ///
+    /// A set of strings is accepted wherever a typed [`Value`] is.
     #[serde(rename = "data")]
-    pub data: HashMap<String, HashSet<String>>,
+    pub data: Map<String, Value>,
 
     /// Principal.
     #[serde(rename = "principal")]
@@ -56,6 +113,7 @@ pub struct Context {
 
 /// Effect.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Effect {
     /// Allow.
     #[serde(rename = "allow")]
@@ -66,8 +124,57 @@ pub enum Effect {
     Deny,
 }
 
+/// Metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
+pub struct Metadata {
+    /// Created At.
+    #[serde(rename = "created_at")]
+    pub created_at: Option<Timestamp>,
+
+    /// Description.
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    /// Owner.
+    #[serde(rename = "owner")]
+    pub owner: Option<String>,
+
+    /// Tags.
+    #[serde(default, rename = "tags")]
+    pub tags: Vec<String>,
+
+    /// Updated At.
+    #[serde(rename = "updated_at")]
+    pub updated_at: Option<Timestamp>,
+
+    /// Version.
+    #[serde(rename = "version")]
+    pub version: Option<u64>,
+}
+
+impl Metadata {
+    /// Returns true if no metadata has been set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self == &Metadata::default()
+    }
+
+    /// Returns true if the metadata contains the tag.
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
 /// Policy.
+///
+/// Unknown fields are ignored, so that a service can read policies written by a newer release,
+/// except in a [`PolicyDocument`], which rejects them.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
 pub struct Policy {
     /// Actions.
     #[serde(rename = "actions")]
@@ -85,6 +192,14 @@ pub struct Policy {
     #[serde(rename = "id")]
     pub id: String,
 
+    /// Metadata.
+    #[serde(
+        default,
+        rename = "metadata",
+        skip_serializing_if = "Metadata::is_empty"
+    )]
+    pub metadata: Metadata,
+
     /// Principals.
     #[serde(rename = "principals")]
     pub principals: Vec<Principal>,
@@ -95,8 +210,16 @@ pub struct Policy {
 }
 
 /// Principal.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+///
+/// Deserializes from either its object form or its [`urn`] string form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Principal {
+    /// Attributes.
+    ///
+    /// Addressed by expressions as `principal.<attribute>`, and ignored when matching policies.
+    #[serde(default, rename = "attributes", skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+
     /// Id.
     #[serde(rename = "id")]
     pub id: String,
@@ -111,8 +234,16 @@ pub struct Principal {
 }
 
 /// Resource.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+///
+/// Deserializes from either its object form or its [`urn`] string form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Resource {
+    /// Attributes.
+    ///
+    /// Addressed by expressions as `resource.<attribute>`, and ignored when matching policies.
+    #[serde(default, rename = "attributes", skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+
     /// Id.
     #[serde(rename = "id")]
     pub id: String,
@@ -126,13 +257,107 @@ pub struct Resource {
     pub scope: String,
 }
 
+/// Actions.
+///
+/// An enum of the actions on a noun, listing every [`Action`] so that typos are caught at compile
+/// time.
+///
+/// With the `derive` feature, `#[derive(Actions)]` implements it for an enum of unit variants,
+/// along with `From<T>` for [`Action`] and [`ActionRef`]:
+///
+/// - `#[authorization(noun = "..", scope = "..")]` on the enum sets the noun and scope.
+/// - `#[authorization(verb = "..")]` on a variant sets its verb, which otherwise is the name of
+///   the variant in `snake_case`.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, Actions)]
+/// #[authorization(noun = "user", scope = "identity")]
+/// enum UserAction {
+///     Delete,
+///     Get,
+///     List,
+///     Put,
+/// }
+/// ```
+pub trait Actions: Sized + 'static {
+    /// Every action, in declaration order.
+    const ALL: &'static [Self];
+
+    /// Returns the [`ActionRef`].
+    fn action(&self) -> ActionRef<'static>;
+}
+
+/// As Principal.
+///
+/// Converts a domain type into a [`Principal`].
+///
+/// With the `derive` feature, `#[derive(AsPrincipal)]` implements it along with
+/// `From<&T> for Principal`:
+///
+/// - `#[authorization(noun = "..", scope = "..")]` on the struct sets a fixed noun or scope.
+/// - `#[authorization(id)]`, `#[authorization(noun)]` and `#[authorization(scope)]` on a field
+///   take the segment from the field, converted with
+///   [`to_string`](alloc::string::ToString::to_string).
+/// - `#[authorization(attribute)]` or `#[authorization(attribute = "..")]` on a field exports it
+///   as an attribute, converted with [`Value::from`], so `u64`, `usize` and `i128` fields, which
+///   may not fit an [`i64`], are not accepted.
+///
+/// ```ignore
+/// #[derive(AsPrincipal)]
+/// #[authorization(noun = "user", scope = "identity")]
+/// struct User {
+///     #[authorization(id)]
+///     id: String,
+///
+///     #[authorization(attribute)]
+///     level: i64,
+/// }
+/// ```
+pub trait AsPrincipal {
+    /// Returns the [`Principal`].
+    fn as_principal(&self) -> Principal;
+}
+
+/// As Resource.
+///
+/// Converts a domain type into a [`Resource`].
+///
+/// With the `derive` feature, `#[derive(AsResource)]` implements it along with
+/// `From<&T> for Resource`, accepting the same attributes as [`AsPrincipal`].
+pub trait AsResource {
+    /// Returns the [`Resource`].
+    fn as_resource(&self) -> Resource;
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{boxed::Box, string::ToString, vec::Vec};
+
+    use alloc::string::String;
+
+    use crate::{expression::Expr, Expression};
+
+    /// Creates an [`Expression`] from a syntax tree type-checked by a macro at compile time.
+    #[must_use]
+    pub fn expression(expr: Expr, source: &str) -> Expression {
+        Expression::from_parts(expr, String::from(source))
+    }
+}
+
 /// Evaluate.
 ///
 /// Returns the first policy that matches the context.
 ///
 /// Returns `None` if no matches were found.
+///
+/// Accepts either a [`&Context`](Context) or a [`ContextRef`].
 #[must_use]
-pub fn evaluate<'a>(context: &Context, policies: &'a [Policy]) -> Option<&'a Policy> {
+pub fn evaluate<'a, 'c>(
+    context: impl Into<ContextRef<'c>>,
+    policies: &'a [Policy],
+) -> Option<&'a Policy> {
+    let context = &context.into();
+
     let deny_policies = policies
         .iter()
         .filter(|policy| policy.effect == Effect::Deny);
@@ -156,7 +381,7 @@ pub fn evaluate<'a>(context: &Context, policies: &'a [Policy]) -> Option<&'a Pol
 ///
 /// Searches for a policy of a policy iterator that satisfies a context.
 fn find<'a>(
-    context: &Context,
+    context: &ContextRef,
     policies: impl IntoIterator<Item = &'a Policy>,
 ) -> Option<&'a Policy> {
     policies.into_iter().find(|policy| {
@@ -199,7 +424,7 @@ fn find<'a>(
 }
 
 /// Returns true if [`Action`] matches [`Context`].
-fn matches_action(context: &Context, action: &Action) -> bool {
+fn matches_action(context: &ContextRef, action: &Action) -> bool {
     if action.noun != "*" && action.noun != context.action.noun {
         return false;
     }
@@ -216,7 +441,13 @@ fn matches_action(context: &Context, action: &Action) -> bool {
 }
 
 /// Returns true if [`Condition`] matches [`Context`].
-fn matches_condition(context: &Context, condition: &Condition) -> bool {
+fn matches_condition(context: &ContextRef, condition: &Condition) -> bool {
+    if let Some(expression) = &condition.expression {
+        if !expression::matches(expression.expr(), context) {
+            return false;
+        }
+    }
+
     if let Some(string_equals) = &condition.string_equals {
         if !matches_condition_string_equals(context, string_equals) {
             return false;
@@ -228,20 +459,19 @@ fn matches_condition(context: &Context, condition: &Condition) -> bool {
 
 /// Returns true if [`Context`] is a subset of criteria.
 fn matches_condition_string_equals(
-    context: &Context,
-    criteria: &HashMap<String, HashSet<String>>,
+    context: &ContextRef,
+    criteria: &Map<String, Set<String>>,
 ) -> bool {
     criteria.iter().all(|(key, criteria)| {
         context
             .data
             .get(key)
-            .map(|context| context.is_subset(criteria))
-            .unwrap_or_default()
+            .is_some_and(|values| values.is_subset(criteria))
     })
 }
 
 /// Returns true if [`Principal`] matches [`Context`].
-fn matches_principal(context: &Context, principal: &Principal) -> bool {
+fn matches_principal(context: &ContextRef, principal: &Principal) -> bool {
     if principal.id != "*" && principal.id != context.principal.id {
         return false;
     }
@@ -258,7 +488,7 @@ fn matches_principal(context: &Context, principal: &Principal) -> bool {
 }
 
 /// Returns true if [`Resource`] matches [`Context`].
-fn match_resource(context: &Context, resource: &Resource) -> bool {
+fn match_resource(context: &ContextRef, resource: &Resource) -> bool {
     if resource.id != "*" && resource.id != context.resource.id {
         return false;
     }
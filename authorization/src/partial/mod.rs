@@ -0,0 +1,449 @@
+//! Partial.
+//!
+//! Partial evaluation of policies when the [`Resource`] of a [`Context`](crate::Context) is unknown, such as when
+//! listing the resources a principal may act on.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    borrowed::{ContextRef, ResourceRef, Values},
+    collections::{Map, Set},
+    expression::{self, Comparison, Entity, Expr},
+    matches_action, matches_principal, Action, Condition, Effect, Policy, Principal, Resource,
+    Value,
+};
+
+/// Partial Context.
+///
+/// A [`Context`](crate::Context) without a [`Resource`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PartialContext {
+    /// Action.
+    #[serde(rename = "action")]
+    pub action: Action,
+
+    /// Data.
+    ///
+    /// Keys that are not present are unresolved, and are left in the residual [`Filter`].
+    #[serde(rename = "data")]
+    pub data: Map<String, Value>,
+
+    /// Principal.
+    #[serde(rename = "principal")]
+    pub principal: Principal,
+}
+
+/// Filter.
+///
+/// A residual predicate over an unknown [`Resource`] and unresolved data keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// All filters are true.
+    And(Vec<Filter>),
+
+    /// Constant.
+    Bool(bool),
+
+    /// Field of the resource equals the value.
+    Equals(Field, String),
+
+    /// Expression that could not be resolved.
+    ///
+    /// Principal and action attributes have been substituted with their values.
+    Expr(Expr),
+
+    /// Filter is false.
+    Not(Box<Filter>),
+
+    /// Any filter is true.
+    Or(Vec<Filter>),
+
+    /// Values of the data key are a subset of the values.
+    Subset(String, Set<String>),
+}
+
+/// Field of a [`Resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    /// Id.
+    Id,
+
+    /// Noun.
+    Noun,
+
+    /// Scope.
+    Scope,
+}
+
+/// Partially evaluates policies.
+///
+/// Returns a [`Filter`] that is true for exactly the resources that [`evaluate`](crate::evaluate)
+/// would explicitly allow.
+#[must_use]
+pub fn partial_evaluate(context: &PartialContext, policies: &[Policy]) -> Filter {
+    let context = ContextRef {
+        action: (&context.action).into(),
+        data: &context.data,
+        principal: (&context.principal).into(),
+        resource: ResourceRef {
+            attributes: &(),
+            id: "",
+            noun: "",
+            scope: "",
+        },
+    };
+
+    let filter = |effect: Effect| {
+        or(policies
+            .iter()
+            .filter(|policy| policy.effect == effect)
+            .map(|policy| residual_policy(&context, policy))
+            .collect())
+    };
+
+    let deny = filter(Effect::Deny);
+    let allow = filter(Effect::Allow);
+
+    and(vec![allow, not(deny)])
+}
+
+/// Returns the residual [`Filter`] of a [`Policy`].
+fn residual_policy(context: &ContextRef, policy: &Policy) -> Filter {
+    let any = policy
+        .actions
+        .iter()
+        .any(|action| matches_action(context, action));
+    if !any {
+        return Filter::Bool(false);
+    }
+
+    let any = policy
+        .principals
+        .iter()
+        .any(|principal| matches_principal(context, principal));
+    if !any {
+        return Filter::Bool(false);
+    }
+
+    let resources = or(policy.resources.iter().map(residual_resource).collect());
+
+    if policy.conditions.is_empty() {
+        return resources;
+    }
+
+    let conditions = or(policy
+        .conditions
+        .iter()
+        .map(|condition| residual_condition(context, condition))
+        .collect());
+
+    and(vec![resources, conditions])
+}
+
+/// Returns the residual [`Filter`] of a [`Condition`].
+fn residual_condition(context: &ContextRef, condition: &Condition) -> Filter {
+    let mut filters = Vec::new();
+
+    if let Some(expression) = &condition.expression {
+        filters.push(residual_expr(context, expression.expr()));
+    }
+
+    if let Some(string_equals) = &condition.string_equals {
+        filters.extend(
+            string_equals
+                .iter()
+                .map(|(key, criteria)| match context.data.get(key) {
+                    Some(values) => Filter::Bool(values.is_subset(criteria)),
+                    None => Filter::Subset(key.clone(), criteria.clone()),
+                }),
+        );
+    }
+
+    and(filters)
+}
+
+/// Returns the residual [`Filter`] of an [`Expr`].
+///
+/// Keeps the semantics of [`Expression::evaluate`](crate::Expression::evaluate), where a runtime
+/// error is `false`. The operands of `&&` and `||` are split apart, as an operand that fails is
+/// `false` in either. The operand of `!` is only split apart when it cannot fail, as the negation
+/// of an error is still `false`.
+fn residual_expr(context: &ContextRef, expr: &Expr) -> Filter {
+    if is_resolved(context, expr) {
+        return Filter::Bool(expression::matches(expr, context));
+    }
+
+    if contains_data(expr) {
+        return Filter::Expr(substitute(context, expr));
+    }
+
+    match expr {
+        Expr::And(left, right) => and(vec![
+            residual_expr(context, left),
+            residual_expr(context, right),
+        ]),
+        Expr::Or(left, right) => or(vec![
+            residual_expr(context, left),
+            residual_expr(context, right),
+        ]),
+        Expr::Not(operand) if is_infallible(&substitute(context, operand)) => {
+            not(residual_expr(context, operand))
+        }
+        Expr::Compare(comparison @ (Comparison::Equal | Comparison::NotEqual), left, right) => {
+            let equals = match (left.as_ref(), right.as_ref()) {
+                (Expr::Attribute(Entity::Resource, attribute), other)
+                | (other, Expr::Attribute(Entity::Resource, attribute)) => field(attribute)
+                    .zip(resolve_string(context, other))
+                    .map(|(field, value)| Filter::Equals(field, value)),
+                _ => None,
+            };
+
+            match (equals, comparison) {
+                (Some(equals), Comparison::Equal) => equals,
+                (Some(equals), _) => not(equals),
+                (None, _) => Filter::Expr(substitute(context, expr)),
+            }
+        }
+        Expr::In(left, right) => match (left.as_ref(), right.as_ref()) {
+            (Expr::Attribute(Entity::Resource, attribute), Expr::List(items)) => {
+                let values = items
+                    .iter()
+                    .map(|item| resolve_string(context, item))
+                    .collect::<Option<Vec<_>>>();
+
+                match (field(attribute), values) {
+                    (Some(field), Some(values)) => or(values
+                        .into_iter()
+                        .map(|value| Filter::Equals(field, value))
+                        .collect()),
+                    _ => Filter::Expr(substitute(context, expr)),
+                }
+            }
+            _ => Filter::Expr(substitute(context, expr)),
+        },
+        _ => Filter::Expr(substitute(context, expr)),
+    }
+}
+
+/// Returns true if an [`Expr`] references a data value.
+fn contains_data(expr: &Expr) -> bool {
+    match expr {
+        Expr::Data(_) => true,
+        Expr::And(left, right)
+        | Expr::Compare(_, left, right)
+        | Expr::In(left, right)
+        | Expr::Or(left, right) => contains_data(left) || contains_data(right),
+        Expr::List(items) => items.iter().any(contains_data),
+        Expr::Field(expr, _) | Expr::Not(expr) | Expr::Size(expr) => contains_data(expr),
+        Expr::Attribute(..)
+        | Expr::Bool(_)
+        | Expr::Decimal(_)
+        | Expr::Has(_)
+        | Expr::Int(_)
+        | Expr::Ip(_)
+        | Expr::String(_)
+        | Expr::Timestamp(_) => false,
+    }
+}
+
+/// Returns true if an [`Expr`] evaluates to a boolean without failing at runtime.
+///
+/// Conservative: only literals, `has`, and equality and membership of strings are infallible.
+fn is_infallible(expr: &Expr) -> bool {
+    match expr {
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            is_infallible(left) && is_infallible(right)
+        }
+        Expr::Bool(_) | Expr::Has(_) => true,
+        Expr::Compare(Comparison::Equal | Comparison::NotEqual, left, right) => {
+            is_string(left) && is_string(right)
+        }
+        Expr::In(left, right) => {
+            is_string(left)
+                && matches!(right.as_ref(), Expr::List(items) if items.iter().all(is_string))
+        }
+        Expr::Not(expr) => is_infallible(expr),
+        _ => false,
+    }
+}
+
+/// Returns true if an [`Expr`] is a string literal or an attribute that always holds a string.
+fn is_string(expr: &Expr) -> bool {
+    match expr {
+        Expr::Attribute(Entity::Action, attribute) => {
+            matches!(attribute.as_str(), "noun" | "scope" | "verb")
+        }
+        Expr::Attribute(_, attribute) => matches!(attribute.as_str(), "id" | "noun" | "scope"),
+        Expr::String(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if an [`Expr`] references neither the resource nor unresolved data keys.
+fn is_resolved(context: &ContextRef, expr: &Expr) -> bool {
+    match expr {
+        Expr::Attribute(entity, _) => *entity != Entity::Resource,
+        Expr::Data(key) | Expr::Has(key) => context.data.get(key).is_some(),
+        Expr::And(left, right)
+        | Expr::Compare(_, left, right)
+        | Expr::In(left, right)
+        | Expr::Or(left, right) => is_resolved(context, left) && is_resolved(context, right),
+        Expr::List(items) => items.iter().all(|item| is_resolved(context, item)),
+        Expr::Field(expr, _) | Expr::Not(expr) | Expr::Size(expr) => is_resolved(context, expr),
+        Expr::Bool(_)
+        | Expr::Decimal(_)
+        | Expr::Int(_)
+        | Expr::Ip(_)
+        | Expr::String(_)
+        | Expr::Timestamp(_) => true,
+    }
+}
+
+/// Returns the [`Field`] of a resource attribute.
+fn field(attribute: &str) -> Option<Field> {
+    match attribute {
+        "id" => Some(Field::Id),
+        "noun" => Some(Field::Noun),
+        "scope" => Some(Field::Scope),
+        _ => None,
+    }
+}
+
+/// Returns the value of a string literal or a resolved attribute.
+fn resolve_string(context: &ContextRef, expr: &Expr) -> Option<String> {
+    match substitute(context, expr) {
+        Expr::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Substitutes principal and action attributes with their values.
+fn substitute(context: &ContextRef, expr: &Expr) -> Expr {
+    let boxed = |expr: &Expr| Box::new(substitute(context, expr));
+
+    match expr {
+        Expr::Attribute(Entity::Action, attribute) => Expr::String(
+            match attribute.as_str() {
+                "noun" => context.action.noun,
+                "scope" => context.action.scope,
+                _ => context.action.verb,
+            }
+            .to_string(),
+        ),
+        Expr::Attribute(Entity::Principal, attribute) => match attribute.as_str() {
+            "id" => Expr::String(context.principal.id.to_string()),
+            "noun" => Expr::String(context.principal.noun.to_string()),
+            "scope" => Expr::String(context.principal.scope.to_string()),
+            _ => context
+                .principal
+                .attributes
+                .get(attribute)
+                .and_then(literal)
+                .unwrap_or_else(|| expr.clone()),
+        },
+        Expr::And(left, right) => Expr::And(boxed(left), boxed(right)),
+        Expr::Compare(comparison, left, right) => {
+            Expr::Compare(*comparison, boxed(left), boxed(right))
+        }
+        Expr::Field(expr, field) => Expr::Field(boxed(expr), field.clone()),
+        Expr::In(left, right) => Expr::In(boxed(left), boxed(right)),
+        Expr::List(items) => {
+            Expr::List(items.iter().map(|item| substitute(context, item)).collect())
+        }
+        Expr::Not(expr) => Expr::Not(boxed(expr)),
+        Expr::Or(left, right) => Expr::Or(boxed(left), boxed(right)),
+        Expr::Size(expr) => Expr::Size(boxed(expr)),
+        Expr::Attribute(..)
+        | Expr::Bool(_)
+        | Expr::Data(_)
+        | Expr::Decimal(_)
+        | Expr::Has(_)
+        | Expr::Int(_)
+        | Expr::Ip(_)
+        | Expr::String(_)
+        | Expr::Timestamp(_) => expr.clone(),
+    }
+}
+
+/// Returns the literal of a scalar value.
+fn literal(values: Values) -> Option<Expr> {
+    match values {
+        Values::One(value) => Some(Expr::String(value.to_string())),
+        Values::Value(Value::String(value)) => Some(Expr::String(value.clone())),
+        Values::Value(Value::Bool(value)) => Some(Expr::Bool(*value)),
+        Values::Value(Value::Decimal(value)) => Some(Expr::Decimal(*value)),
+        Values::Value(Value::Int(value)) => Some(Expr::Int(*value)),
+        Values::Value(Value::Ip(value)) => Some(Expr::Ip(*value)),
+        Values::Value(Value::Timestamp(value)) => Some(Expr::Timestamp(*value)),
+        _ => None,
+    }
+}
+
+/// Returns the residual [`Filter`] of a [`Resource`] pattern.
+fn residual_resource(resource: &Resource) -> Filter {
+    and([
+        (Field::Id, &resource.id),
+        (Field::Noun, &resource.noun),
+        (Field::Scope, &resource.scope),
+    ]
+    .into_iter()
+    .filter(|(_, value)| *value != "*")
+    .map(|(field, value)| Filter::Equals(field, value.clone()))
+    .collect())
+}
+
+/// Returns a [`Filter`] that is true when all filters are true.
+fn and(filters: Vec<Filter>) -> Filter {
+    let mut flattened = Vec::new();
+
+    for filter in filters {
+        match filter {
+            Filter::Bool(true) => {}
+            Filter::Bool(false) => return Filter::Bool(false),
+            Filter::And(filters) => flattened.extend(filters),
+            filter => flattened.push(filter),
+        }
+    }
+
+    match flattened.len() {
+        0 => Filter::Bool(true),
+        1 => flattened.remove(0),
+        _ => Filter::And(flattened),
+    }
+}
+
+/// Returns a [`Filter`] that is true when the filter is false.
+fn not(filter: Filter) -> Filter {
+    match filter {
+        Filter::Bool(value) => Filter::Bool(!value),
+        Filter::Not(filter) => *filter,
+        filter => Filter::Not(Box::new(filter)),
+    }
+}
+
+/// Returns a [`Filter`] that is true when any filter is true.
+fn or(filters: Vec<Filter>) -> Filter {
+    let mut flattened = Vec::new();
+
+    for filter in filters {
+        match filter {
+            Filter::Bool(false) => {}
+            Filter::Bool(true) => return Filter::Bool(true),
+            Filter::Or(filters) => flattened.extend(filters),
+            filter => flattened.push(filter),
+        }
+    }
+
+    match flattened.len() {
+        0 => Filter::Bool(false),
+        1 => flattened.remove(0),
+        _ => Filter::Or(flattened),
+    }
+}
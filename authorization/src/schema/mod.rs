@@ -0,0 +1,541 @@
+//! Schema.
+//!
+//! Declares the valid scopes, nouns, verbs and data keys, so that a typo in a policy is reported
+//! instead of making the policy silently never match.
+//!
+//! ```json
+//! {
+//!   "data": { "player:level": "int" },
+//!   "scopes": {
+//!     "world": {
+//!       "nouns": {
+//!         "monster": { "verbs": ["attack"] },
+//!         "player": { "verbs": ["attack", "take"] }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    borrowed::Values,
+    collections::{Map, Set},
+    expression::Expr,
+    Actions, ContextRef, Policy, Value,
+};
+
+/// Schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    /// Data keys and the types of their values.
+    #[serde(default, rename = "data")]
+    pub data: Map<String, Type>,
+
+    /// Scopes.
+    #[serde(default, rename = "scopes")]
+    pub scopes: Map<String, Scope>,
+}
+
+/// Scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct Scope {
+    /// Nouns.
+    #[serde(default, rename = "nouns")]
+    pub nouns: Map<String, Noun>,
+}
+
+/// Noun.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct Noun {
+    /// Verbs.
+    #[serde(default, rename = "verbs")]
+    pub verbs: Set<String>,
+}
+
+/// Type of a data value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Type {
+    /// Bool.
+    #[serde(rename = "bool")]
+    Bool,
+
+    /// Decimal.
+    #[serde(rename = "decimal")]
+    Decimal,
+
+    /// Int.
+    #[serde(rename = "int")]
+    Int,
+
+    /// IP address.
+    #[serde(rename = "ip")]
+    Ip,
+
+    /// Record.
+    #[serde(rename = "record")]
+    Record,
+
+    /// Set.
+    #[serde(rename = "set")]
+    Set,
+
+    /// String.
+    #[serde(rename = "string")]
+    String,
+
+    /// Timestamp.
+    #[serde(rename = "timestamp")]
+    Timestamp,
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Bool => "bool",
+            Type::Decimal => "decimal",
+            Type::Int => "int",
+            Type::Ip => "ip",
+            Type::Record => "record",
+            Type::Set => "set",
+            Type::String => "string",
+            Type::Timestamp => "timestamp",
+        })
+    }
+}
+
+impl Type {
+    /// Returns true if the values are of the type.
+    ///
+    /// A set holding a single string is accepted when the string parses into the type, as it is
+    /// when evaluated.
+    fn accepts(self, values: Values) -> bool {
+        let parses = |value: &str| match self {
+            Type::Bool => value.parse::<bool>().is_ok(),
+            Type::Decimal => value.parse::<crate::value::Decimal>().is_ok(),
+            Type::Int => value.parse::<i64>().is_ok(),
+            Type::Ip => value.parse::<core::net::IpAddr>().is_ok(),
+            Type::Record => false,
+            Type::Set | Type::String => true,
+            Type::Timestamp => value.parse::<crate::value::Timestamp>().is_ok(),
+        };
+
+        match values {
+            Values::One(value) => parses(value),
+            Values::Set(set) if self != Type::Set => {
+                set.len() == 1 && set.iter().all(|value| parses(value))
+            }
+            Values::Slice(slice) if self != Type::Set => {
+                slice.len() == 1 && slice.iter().all(|value| parses(value))
+            }
+            Values::Set(_) | Values::Slice(_) => true,
+            Values::Value(value) => match (self, value) {
+                (Type::Bool, Value::Bool(_))
+                | (Type::Decimal, Value::Decimal(_) | Value::Int(_))
+                | (Type::Int, Value::Int(_))
+                | (Type::Ip, Value::Ip(_))
+                | (Type::Record, Value::Record(_))
+                | (Type::Set, Value::Set(_))
+                | (Type::String, Value::String(_))
+                | (Type::Timestamp, Value::Timestamp(_)) => true,
+                (_, Value::Set(set)) => match set.first() {
+                    Some(Value::String(value)) => set.len() == 1 && parses(value),
+                    _ => false,
+                },
+                (_, Value::String(value)) => self != Type::Record && parses(value),
+                _ => false,
+            },
+        }
+    }
+
+    /// Returns true if values of the type can be compared with a literal of a type.
+    ///
+    /// String literals are parsed into the type, integers and decimals compare with each other,
+    /// and a set compares through its single element.
+    fn compares_with(self, literal: Type) -> bool {
+        match (self, literal) {
+            (Type::Record, _) => false,
+            (_, Type::String)
+            | (Type::Decimal | Type::Int, Type::Decimal | Type::Int)
+            | (Type::Set, _) => true,
+            (ty, literal) => ty == literal,
+        }
+    }
+}
+
+/// Error.
+///
+/// Returned when a policy or context does not conform to a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Message.
+    pub message: String,
+
+    /// Path of the offending value, such as `attack-monsters.actions[0].verb`.
+    pub path: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl Schema {
+    /// Declares every action of an [`Actions`] enum, along with its scope and noun.
+    pub fn insert_actions<A: Actions>(&mut self) {
+        for action in A::ALL.iter().map(Actions::action) {
+            self.scopes
+                .entry(action.scope.to_string())
+                .or_default()
+                .nouns
+                .entry(action.noun.to_string())
+                .or_default()
+                .verbs
+                .insert(action.verb.to_string());
+        }
+    }
+
+    /// Validates policies.
+    ///
+    /// Checks the scopes, nouns and verbs of actions, the scopes and nouns of principals and
+    /// resources, and the data keys referenced by conditions. Wildcards are always valid.
+    ///
+    /// Expressions are checked to compare declared data values only with literals of their type,
+    /// and to test only boolean data values. Other uses of data values are not type checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`Error`] found.
+    pub fn validate_policies(&self, policies: &[Policy]) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
+
+        for policy in policies {
+            for (index, action) in policy.actions.iter().enumerate() {
+                let path = format!("{}.actions[{index}]", policy.id);
+                self.check_entity(
+                    &path,
+                    &action.scope,
+                    &action.noun,
+                    Some(&action.verb),
+                    &mut errors,
+                );
+            }
+
+            for (index, principal) in policy.principals.iter().enumerate() {
+                let path = format!("{}.principals[{index}]", policy.id);
+                self.check_entity(&path, &principal.scope, &principal.noun, None, &mut errors);
+            }
+
+            for (index, resource) in policy.resources.iter().enumerate() {
+                let path = format!("{}.resources[{index}]", policy.id);
+                self.check_entity(&path, &resource.scope, &resource.noun, None, &mut errors);
+            }
+
+            for (index, condition) in policy.conditions.iter().enumerate() {
+                let path = format!("{}.conditions[{index}]", policy.id);
+
+                let mut keys = Vec::new();
+                if let Some(expression) = &condition.expression {
+                    data_keys(expression.expr(), &mut keys);
+                    self.check_types(expression.expr(), true, &path, &mut errors);
+                }
+                if let Some(string_equals) = &condition.string_equals {
+                    keys.extend(string_equals.keys().map(String::as_str));
+                }
+                keys.sort_unstable();
+                keys.dedup();
+
+                for key in keys {
+                    if !self.data.contains_key(key) {
+                        errors.push(unknown(
+                            &path,
+                            "data key",
+                            key,
+                            self.data.keys().map(String::as_str),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validates a context.
+    ///
+    /// Checks the scope, noun and verb of the action, the scopes and nouns of the principal and
+    /// resource, and the types of the declared data keys that are present.
+    ///
+    /// Accepts either a [`&Context`](crate::Context) or a [`ContextRef`].
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`Error`] found.
+    pub fn validate_context<'c>(
+        &self,
+        context: impl Into<ContextRef<'c>>,
+    ) -> Result<(), Vec<Error>> {
+        let context = context.into();
+        let mut errors = Vec::new();
+
+        let action = context.action;
+        self.check_entity(
+            "action",
+            action.scope,
+            action.noun,
+            Some(action.verb),
+            &mut errors,
+        );
+
+        let principal = context.principal;
+        self.check_entity(
+            "principal",
+            principal.scope,
+            principal.noun,
+            None,
+            &mut errors,
+        );
+
+        let resource = context.resource;
+        self.check_entity("resource", resource.scope, resource.noun, None, &mut errors);
+
+        // `Map` is a `HashMap` with `std`, so sort to report errors in a stable order.
+        let mut data = self.data.iter().collect::<Vec<_>>();
+        data.sort_unstable_by_key(|(key, _)| *key);
+        for (key, ty) in data {
+            if let Some(values) = context.data.get(key) {
+                if !ty.accepts(values) {
+                    errors.push(Error {
+                        message: format!("expected {ty}"),
+                        path: format!("data[\"{key}\"]"),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks the types of the declared data values of an [`Expr`], tested as a boolean if
+    /// `boolean` is true.
+    fn check_types(&self, expr: &Expr, boolean: bool, path: &str, errors: &mut Vec<Error>) {
+        match expr {
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                self.check_types(left, true, path, errors);
+                self.check_types(right, true, path, errors);
+            }
+            Expr::Compare(_, left, right) => {
+                for (data, literal) in [(left, right), (right, left)] {
+                    if let (Expr::Data(key), Some(literal)) = (&**data, literal_type(literal)) {
+                        match self.data.get(key) {
+                            Some(ty) if !ty.compares_with(literal) => errors.push(Error {
+                                message: format!(
+                                    "`data[\"{key}\"]` is {ty}, compared with {literal}"
+                                ),
+                                path: path.to_string(),
+                            }),
+                            _ => {}
+                        }
+                    }
+                }
+                self.check_types(left, false, path, errors);
+                self.check_types(right, false, path, errors);
+            }
+            Expr::Data(key) if boolean => match self.data.get(key) {
+                Some(ty) if *ty != Type::Bool => errors.push(Error {
+                    message: format!("`data[\"{key}\"]` is {ty}, tested as bool"),
+                    path: path.to_string(),
+                }),
+                _ => {}
+            },
+            Expr::In(left, right) => {
+                self.check_types(left, false, path, errors);
+                self.check_types(right, false, path, errors);
+            }
+            Expr::List(items) => {
+                for item in items {
+                    self.check_types(item, false, path, errors);
+                }
+            }
+            Expr::Not(expr) => self.check_types(expr, true, path, errors),
+            Expr::Field(expr, _) | Expr::Size(expr) => self.check_types(expr, false, path, errors),
+            Expr::Attribute(..)
+            | Expr::Bool(_)
+            | Expr::Data(_)
+            | Expr::Decimal(_)
+            | Expr::Has(_)
+            | Expr::Int(_)
+            | Expr::Ip(_)
+            | Expr::String(_)
+            | Expr::Timestamp(_) => {}
+        }
+    }
+
+    /// Checks the scope, noun and verb of an entity.
+    fn check_entity(
+        &self,
+        path: &str,
+        scope: &str,
+        noun: &str,
+        verb: Option<&str>,
+        errors: &mut Vec<Error>,
+    ) {
+        let scopes = if scope == "*" {
+            self.scopes.values().collect::<Vec<_>>()
+        } else if let Some(found) = self.scopes.get(scope) {
+            vec![found]
+        } else {
+            errors.push(unknown(
+                &format!("{path}.scope"),
+                "scope",
+                scope,
+                self.scopes.keys().map(String::as_str),
+            ));
+            return;
+        };
+
+        let nouns = if noun == "*" {
+            scopes
+                .iter()
+                .flat_map(|scope| scope.nouns.values())
+                .collect::<Vec<_>>()
+        } else {
+            let found = scopes
+                .iter()
+                .filter_map(|scope| scope.nouns.get(noun))
+                .collect::<Vec<_>>();
+            if found.is_empty() {
+                errors.push(unknown(
+                    &format!("{path}.noun"),
+                    "noun",
+                    noun,
+                    scopes
+                        .iter()
+                        .flat_map(|scope| scope.nouns.keys().map(String::as_str)),
+                ));
+                return;
+            }
+            found
+        };
+
+        if let Some(verb) = verb.filter(|verb| *verb != "*") {
+            if !nouns.iter().any(|noun| noun.verbs.contains(verb)) {
+                errors.push(unknown(
+                    &format!("{path}.verb"),
+                    "verb",
+                    verb,
+                    nouns
+                        .iter()
+                        .flat_map(|noun| noun.verbs.iter().map(String::as_str)),
+                ));
+            }
+        }
+    }
+}
+
+/// Collects the data keys referenced by an [`Expr`].
+fn data_keys<'a>(expr: &'a Expr, keys: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Data(key) | Expr::Has(key) => keys.push(key),
+        Expr::And(left, right)
+        | Expr::Compare(_, left, right)
+        | Expr::In(left, right)
+        | Expr::Or(left, right) => {
+            data_keys(left, keys);
+            data_keys(right, keys);
+        }
+        Expr::List(items) => {
+            for item in items {
+                data_keys(item, keys);
+            }
+        }
+        Expr::Field(expr, _) | Expr::Not(expr) | Expr::Size(expr) => data_keys(expr, keys),
+        Expr::Attribute(..)
+        | Expr::Bool(_)
+        | Expr::Decimal(_)
+        | Expr::Int(_)
+        | Expr::Ip(_)
+        | Expr::String(_)
+        | Expr::Timestamp(_) => {}
+    }
+}
+
+/// Returns the type of a literal [`Expr`], or of the size of any expression.
+fn literal_type(expr: &Expr) -> Option<Type> {
+    match expr {
+        Expr::Bool(_) => Some(Type::Bool),
+        Expr::Decimal(_) => Some(Type::Decimal),
+        Expr::Int(_) | Expr::Size(_) => Some(Type::Int),
+        Expr::Ip(_) => Some(Type::Ip),
+        Expr::String(_) => Some(Type::String),
+        Expr::Timestamp(_) => Some(Type::Timestamp),
+        _ => None,
+    }
+}
+
+/// Returns an [`Error`] for an unknown name, suggesting the closest known name.
+fn unknown<'a>(
+    path: &str,
+    kind: &str,
+    name: &str,
+    known: impl IntoIterator<Item = &'a str>,
+) -> Error {
+    let suggestion = known
+        .into_iter()
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min();
+
+    Error {
+        message: match suggestion {
+            Some((_, candidate)) => format!("unknown {kind} `{name}`, did you mean `{candidate}`?"),
+            None => format!("unknown {kind} `{name}`"),
+        },
+        path: path.to_string(),
+    }
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
@@ -0,0 +1,203 @@
+//! Set.
+//!
+//! A compiled set of policies, indexed by the scope of their actions.
+
+use alloc::{string::String, vec::Vec};
+use core::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{collections::Map, find, schema, ContextRef, Effect, Policy, Schema};
+
+/// Policy set.
+///
+/// Evaluates like [`evaluate`](crate::evaluate), but only visits the policies with an action in
+/// the scope of the context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicySet {
+    /// Allow index.
+    pub(crate) allow: Index,
+
+    /// Deny index.
+    pub(crate) deny: Index,
+
+    /// Policies.
+    pub(crate) policies: Vec<Policy>,
+
+    /// Schema the policies were validated against.
+    pub(crate) schema: Option<Schema>,
+}
+
+impl PolicySet {
+    /// Compiles a policy set.
+    #[must_use]
+    pub fn new(policies: Vec<Policy>) -> Self {
+        PolicySet {
+            allow: Index::new(&policies, &Effect::Allow),
+            deny: Index::new(&policies, &Effect::Deny),
+            policies,
+            schema: None,
+        }
+    }
+
+    /// Compiles a policy set, validating the policies against a schema.
+    ///
+    /// [`evaluate_checked`](PolicySet::evaluate_checked) also validates each context against the
+    /// schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`schema::Error`] found in the policies.
+    pub fn with_schema(policies: Vec<Policy>, schema: Schema) -> Result<Self, Vec<schema::Error>> {
+        schema.validate_policies(&policies)?;
+
+        Ok(PolicySet {
+            schema: Some(schema),
+            ..PolicySet::new(policies)
+        })
+    }
+
+    /// Evaluate.
+    ///
+    /// Returns the first policy that matches the context.
+    ///
+    /// Returns `None` if no matches were found.
+    ///
+    /// Accepts either a [`&Context`](crate::Context) or a [`ContextRef`].
+    #[must_use]
+    pub fn evaluate<'c>(&self, context: impl Into<ContextRef<'c>>) -> Option<&Policy> {
+        let context = &context.into();
+
+        if let Some(policy) = find(context, self.deny.candidates(context, &self.policies)) {
+            return Some(policy);
+        }
+
+        if let Some(policy) = find(context, self.allow.candidates(context, &self.policies)) {
+            return Some(policy);
+        }
+
+        None
+    }
+
+    /// Evaluates like [`evaluate`](PolicySet::evaluate), after validating the context against the
+    /// schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`schema::Error`] found in the context.
+    pub fn evaluate_checked<'c>(
+        &self,
+        context: impl Into<ContextRef<'c>>,
+    ) -> Result<Option<&Policy>, Vec<schema::Error>> {
+        let context = context.into();
+        self.validate(context)?;
+        Ok(self.evaluate(context))
+    }
+
+    /// Validates a context against the schema.
+    ///
+    /// Any context is valid if the set has no schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`schema::Error`] found in the context.
+    pub fn validate<'c>(
+        &self,
+        context: impl Into<ContextRef<'c>>,
+    ) -> Result<(), Vec<schema::Error>> {
+        match &self.schema {
+            Some(schema) => schema.validate_context(context),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the policies.
+    #[must_use]
+    pub fn policies(&self) -> &[Policy] {
+        &self.policies
+    }
+
+    /// Returns the schema, if any.
+    #[must_use]
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+}
+
+impl From<Vec<Policy>> for PolicySet {
+    fn from(policies: Vec<Policy>) -> Self {
+        PolicySet::new(policies)
+    }
+}
+
+/// Index.
+///
+/// Positions of the policies of an effect, in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct Index {
+    /// Policies with an action of a scope.
+    #[serde(rename = "scopes")]
+    pub(crate) scopes: Map<String, Vec<usize>>,
+
+    /// Policies with an action of any scope.
+    #[serde(rename = "wildcard")]
+    pub(crate) wildcard: Vec<usize>,
+}
+
+impl Index {
+    /// Indexes the policies of an effect.
+    fn new(policies: &[Policy], effect: &Effect) -> Self {
+        let mut index = Index::default();
+
+        for (position, policy) in policies.iter().enumerate() {
+            if &policy.effect != effect {
+                continue;
+            }
+
+            for action in &policy.actions {
+                let positions = if action.scope == "*" {
+                    &mut index.wildcard
+                } else {
+                    index.scopes.entry(action.scope.clone()).or_default()
+                };
+
+                if positions.last() != Some(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Returns the candidate policies of a context, in order.
+    fn candidates<'a>(
+        &'a self,
+        context: &ContextRef,
+        policies: &'a [Policy],
+    ) -> impl Iterator<Item = &'a Policy> {
+        let mut scoped = self
+            .scopes
+            .get(context.action.scope)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .peekable();
+        let mut wildcard = self.wildcard.iter().peekable();
+
+        core::iter::from_fn(move || {
+            let position = match (scoped.peek(), wildcard.peek()) {
+                (Some(a), Some(b)) => match a.cmp(b) {
+                    Ordering::Less => scoped.next(),
+                    Ordering::Equal => {
+                        wildcard.next();
+                        scoped.next()
+                    }
+                    Ordering::Greater => wildcard.next(),
+                },
+                (Some(_), None) => scoped.next(),
+                (None, _) => wildcard.next(),
+            }?;
+            policies.get(*position)
+        })
+    }
+}
@@ -0,0 +1,415 @@
+//! Conformance.
+//!
+//! Checks that a [`PolicyStore`] or [`AsyncPolicyStore`] behaves like the
+//! [`MemoryStore`](super::MemoryStore), for use in the tests of a backend.
+//!
+//! ```
+//! # use authorization::store::{conformance, MemoryStore};
+//! conformance::run(MemoryStore::new);
+//! ```
+
+use alloc::vec::Vec;
+use core::{
+    future::{self, Future},
+    pin::pin,
+    task::{self, Poll, Waker},
+};
+
+use super::{AsyncPolicyStore, PolicyStore};
+use crate::{Action, Condition, Expression, Metadata, Policy, Principal, Resource};
+
+/// Runs every check against empty stores created by `new`.
+///
+/// # Panics
+///
+/// If the store does not conform, naming the failed check.
+pub fn run<S>(mut new: impl FnMut() -> S)
+where
+    S: PolicyStore,
+    S::Error: Send,
+{
+    let run = pin!(run_async(|| future::ready(new())));
+
+    // A synchronous store completes every call of its `AsyncPolicyStore` when first polled.
+    match run.poll(&mut task::Context::from_waker(Waker::noop())) {
+        Poll::Ready(()) => {}
+        Poll::Pending => unreachable!("synchronous stores do not suspend"),
+    }
+}
+
+/// Runs every check against empty stores created by `new`, on the runtime of the caller.
+///
+/// # Panics
+///
+/// If the store does not conform, naming the failed check.
+pub async fn run_async<S, F>(mut new: impl FnMut() -> F)
+where
+    S: AsyncPolicyStore,
+    F: Future<Output = S>,
+{
+    get_should_return_none_when_missing(&mut new().await).await;
+    insert_should_be_returned_by_get(&mut new().await).await;
+    insert_should_round_trip_every_field(&mut new().await).await;
+    insert_should_fail_when_id_exists(&mut new().await).await;
+    update_should_replace_policy(&mut new().await).await;
+    update_should_fail_when_missing(&mut new().await).await;
+    delete_should_return_deleted_policy(&mut new().await).await;
+    delete_should_return_none_when_missing(&mut new().await).await;
+    list_should_return_policies_ordered_by_id(&mut new().await).await;
+    query_by_action_should_match_wildcards(&mut new().await).await;
+    query_by_principal_should_match_wildcards(&mut new().await).await;
+    query_by_resource_should_match_wildcards(&mut new().await).await;
+    query_should_return_policy_once_when_entities_match(&mut new().await).await;
+    query_should_order_by_id(&mut new().await).await;
+    query_should_follow_updates_and_deletes(&mut new().await).await;
+    revision_should_increase_with_writes(&mut new().await).await;
+}
+
+async fn get_should_return_none_when_missing<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = store.get("missing").await.expect("get");
+
+    assert_eq!(policy, None, "get_should_return_none_when_missing");
+}
+
+async fn insert_should_be_returned_by_get<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = given_policy("a", "world:player:*", "world:*:attack", "world:monster:*");
+    store.insert(policy.clone()).await.expect("insert");
+
+    let result = store.get("a").await.expect("get");
+
+    assert_eq!(result, Some(policy), "insert_should_be_returned_by_get");
+}
+
+async fn insert_should_round_trip_every_field<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = given_full_policy();
+    store.insert(policy.clone()).await.expect("insert");
+
+    let result = store.get("full").await.expect("get");
+    let queried = store
+        .query_by_resource(&parse::<Resource>(r"world:chest:a\:b"))
+        .await
+        .expect("query_by_resource");
+
+    assert_eq!(
+        result,
+        Some(policy.clone()),
+        "insert_should_round_trip_every_field"
+    );
+    assert_eq!(
+        queried,
+        [policy],
+        "insert_should_round_trip_every_field: query_by_resource"
+    );
+}
+
+async fn insert_should_fail_when_id_exists<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = given_policy("a", "world:player:*", "world:*:attack", "world:monster:*");
+    store.insert(policy.clone()).await.expect("insert");
+
+    let result = store
+        .insert(given_policy("a", "*:*:*", "*:*:*", "*:*:*"))
+        .await;
+
+    assert!(result.is_err(), "insert_should_fail_when_id_exists");
+    assert_eq!(
+        store.get("a").await.expect("get"),
+        Some(policy),
+        "insert_should_fail_when_id_exists: policy was replaced"
+    );
+}
+
+async fn update_should_replace_policy<S: AsyncPolicyStore>(store: &mut S) {
+    store
+        .insert(given_policy(
+            "a",
+            "world:player:*",
+            "world:*:attack",
+            "world:monster:*",
+        ))
+        .await
+        .expect("insert");
+    let policy = given_policy("a", "world:monster:*", "world:*:attack", "world:player:*");
+
+    store.update(policy.clone()).await.expect("update");
+
+    assert_eq!(
+        store.get("a").await.expect("get"),
+        Some(policy),
+        "update_should_replace_policy"
+    );
+}
+
+async fn update_should_fail_when_missing<S: AsyncPolicyStore>(store: &mut S) {
+    let result = store
+        .update(given_policy("a", "*:*:*", "*:*:*", "*:*:*"))
+        .await;
+
+    assert!(result.is_err(), "update_should_fail_when_missing");
+    assert_eq!(
+        store.get("a").await.expect("get"),
+        None,
+        "update_should_fail_when_missing: policy was inserted"
+    );
+}
+
+async fn delete_should_return_deleted_policy<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = given_policy("a", "world:player:*", "world:*:attack", "world:monster:*");
+    store.insert(policy.clone()).await.expect("insert");
+
+    let result = store.delete("a").await.expect("delete");
+
+    assert_eq!(result, Some(policy), "delete_should_return_deleted_policy");
+    assert_eq!(
+        store.get("a").await.expect("get"),
+        None,
+        "delete_should_return_deleted_policy: policy was kept"
+    );
+}
+
+async fn delete_should_return_none_when_missing<S: AsyncPolicyStore>(store: &mut S) {
+    let result = store.delete("missing").await.expect("delete");
+
+    assert_eq!(result, None, "delete_should_return_none_when_missing");
+}
+
+async fn list_should_return_policies_ordered_by_id<S: AsyncPolicyStore>(store: &mut S) {
+    given_policies(store).await;
+
+    let result = store.list().await.expect("list");
+
+    assert_eq!(
+        ids(&result),
+        ["any", "attack", "attack-monster", "identity", "player-1"],
+        "list_should_return_policies_ordered_by_id"
+    );
+}
+
+async fn query_by_action_should_match_wildcards<S: AsyncPolicyStore>(store: &mut S) {
+    given_policies(store).await;
+
+    let result = store
+        .query_by_action(&parse::<Action>("world:monster:attack"))
+        .await
+        .expect("query_by_action");
+
+    assert_eq!(
+        ids(&result),
+        ["any", "attack", "attack-monster"],
+        "query_by_action_should_match_wildcards"
+    );
+}
+
+async fn query_by_principal_should_match_wildcards<S: AsyncPolicyStore>(store: &mut S) {
+    given_policies(store).await;
+
+    let result = store
+        .query_by_principal(&parse::<Principal>("world:player:1"))
+        .await
+        .expect("query_by_principal");
+
+    assert_eq!(
+        ids(&result),
+        ["any", "attack", "player-1"],
+        "query_by_principal_should_match_wildcards"
+    );
+}
+
+async fn query_by_resource_should_match_wildcards<S: AsyncPolicyStore>(store: &mut S) {
+    given_policies(store).await;
+
+    let result = store
+        .query_by_resource(&parse::<Resource>("world:monster:2"))
+        .await
+        .expect("query_by_resource");
+
+    assert_eq!(
+        ids(&result),
+        ["any", "attack", "attack-monster"],
+        "query_by_resource_should_match_wildcards"
+    );
+}
+
+async fn query_should_return_policy_once_when_entities_match<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = Policy::allow("a")
+        .action(parse::<Action>("world:*:attack"))
+        .action(parse::<Action>("world:monster:attack"))
+        .principal(parse::<Principal>("*:*:*"))
+        .principal(parse::<Principal>("world:player:*"))
+        .principal(parse::<Principal>("world:player:1"))
+        .resource(parse::<Resource>("world:monster:*"))
+        .resource(parse::<Resource>("world:monster:2"));
+    store.insert(policy).await.expect("insert");
+
+    let result = (
+        store
+            .query_by_action(&parse::<Action>("world:monster:attack"))
+            .await
+            .expect("query_by_action"),
+        store
+            .query_by_principal(&parse::<Principal>("world:player:1"))
+            .await
+            .expect("query_by_principal"),
+        store
+            .query_by_resource(&parse::<Resource>("world:monster:2"))
+            .await
+            .expect("query_by_resource"),
+    );
+
+    assert_eq!(
+        [ids(&result.0), ids(&result.1), ids(&result.2)],
+        [["a"], ["a"], ["a"]],
+        "query_should_return_policy_once_when_entities_match"
+    );
+}
+
+async fn query_should_order_by_id<S: AsyncPolicyStore>(store: &mut S) {
+    for id in ["c", "a", "d", "b"] {
+        store
+            .insert(given_policy(id, "*:*:*", "*:*:*", "*:*:*"))
+            .await
+            .expect("insert");
+    }
+
+    let result = store
+        .query_by_principal(&parse::<Principal>("world:player:1"))
+        .await
+        .expect("query_by_principal");
+
+    assert_eq!(
+        ids(&result),
+        ["a", "b", "c", "d"],
+        "query_should_order_by_id"
+    );
+}
+
+async fn query_should_follow_updates_and_deletes<S: AsyncPolicyStore>(store: &mut S) {
+    given_policies(store).await;
+    let principal = parse::<Principal>("world:player:1");
+
+    store
+        .update(given_policy("player-1", "world:player:2", "*:*:*", "*:*:*"))
+        .await
+        .expect("update");
+    store.delete("any").await.expect("delete");
+    let result = store
+        .query_by_principal(&principal)
+        .await
+        .expect("query_by_principal");
+
+    assert_eq!(
+        ids(&result),
+        ["attack"],
+        "query_should_follow_updates_and_deletes"
+    );
+}
+
+async fn revision_should_increase_with_writes<S: AsyncPolicyStore>(store: &mut S) {
+    let policy = given_policy("a", "*:*:*", "*:*:*", "*:*:*");
+    let mut revisions = Vec::new();
+
+    revisions.push(store.revision().await.expect("revision"));
+    store.insert(policy.clone()).await.expect("insert");
+    revisions.push(store.revision().await.expect("revision"));
+    store.update(policy.clone()).await.expect("update");
+    revisions.push(store.revision().await.expect("revision"));
+    let _ = store.insert(policy).await;
+    let _ = store
+        .update(given_policy("missing", "*:*:*", "*:*:*", "*:*:*"))
+        .await;
+    store.delete("missing").await.expect("delete");
+    store.get("a").await.expect("get");
+    revisions.push(store.revision().await.expect("revision"));
+    store.delete("a").await.expect("delete");
+    revisions.push(store.revision().await.expect("revision"));
+
+    assert!(
+        revisions[0] < revisions[1] && revisions[1] < revisions[2],
+        "revision_should_increase_with_writes: {revisions:?}"
+    );
+    assert_eq!(
+        revisions[2], revisions[3],
+        "revision_should_increase_with_writes: changed without a write"
+    );
+    assert!(
+        revisions[3] < revisions[4],
+        "revision_should_increase_with_writes: {revisions:?}"
+    );
+}
+
+async fn given_policies<S: AsyncPolicyStore>(store: &mut S) {
+    let policies = [
+        given_policy("any", "*:*:*", "*:*:*", "*:*:*"),
+        given_policy("attack", "world:*:*", "world:*:attack", "world:*:*"),
+        given_policy(
+            "attack-monster",
+            "world:monster:*",
+            "world:monster:attack",
+            "world:monster:*",
+        ),
+        given_policy(
+            "identity",
+            "identity:user:*",
+            "identity:user:get",
+            "identity:user:*",
+        ),
+        given_policy(
+            "player-1",
+            "world:player:1",
+            "world:loot:take",
+            "world:loot:*",
+        ),
+    ];
+
+    for policy in policies {
+        store.insert(policy).await.expect("insert");
+    }
+}
+
+fn given_policy(id: &str, principal: &str, action: &str, resource: &str) -> Policy {
+    Policy::allow(id)
+        .action(parse::<Action>(action))
+        .principal(parse::<Principal>(principal))
+        .resource(parse::<Resource>(resource))
+}
+
+/// Returns a policy with every field set, and `:` escaped in segments.
+fn given_full_policy() -> Policy {
+    Policy::deny("full")
+        .action(parse::<Action>("world:monster:attack"))
+        .action(parse::<Action>(r"world:chest:open\:lock"))
+        .principal(
+            parse::<Principal>(r"world:player:a\:b")
+                .attribute("admin", true)
+                .attribute("level", 10)
+                .attribute("name", "alice"),
+        )
+        .principal(parse::<Principal>("world:monster:*"))
+        .resource(parse::<Resource>(r"world:chest:a\:b"))
+        .resource(parse::<Resource>("world:loot:*").attribute("rarity", "rare"))
+        .when(
+            Condition::new()
+                .expression(parse::<Expression>(r#"data["player:level"] >= 10"#))
+                .string_equals("request:host", ["a.example", "b.example"]),
+        )
+        .when(Condition::new().string_equals("request:region", ["eu"]))
+        .metadata(
+            Metadata::new()
+                .created_at(parse("2024-01-01T00:00:00Z"))
+                .description("Denies everything it names.")
+                .owner("platform")
+                .tag("pve")
+                .tag("pvp")
+                .updated_at(parse("2024-02-01T12:30:00Z"))
+                .version(2),
+        )
+}
+
+fn parse<T: core::str::FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| unreachable!("conformance values are valid"))
+}
+
+fn ids(policies: &[Policy]) -> Vec<&str> {
+    policies.iter().map(|policy| policy.id.as_str()).collect()
+}
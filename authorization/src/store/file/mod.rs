@@ -0,0 +1,399 @@
+//! File.
+//!
+//! A [`FileStore`] serves the policy documents of a directory, reloading them when they change
+//! on disk.
+//!
+//! Every file with an extension of an enabled format is loaded, while other files are ignored.
+//! A reload replaces all policies at once, and only if every file loads and validates, so readers
+//! never see a mix of old and new files.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use super::{Error, MemoryStore, PolicyStore};
+use crate::{
+    document::{self, SourceError},
+    Action, Policy, Principal, Resource, Schema,
+};
+
+/// File store.
+///
+/// Clones share the same policies, so a [`Watcher`] can reload them while services query.
+/// Writes are rejected with [`Error::ReadOnly`], as the files are the source of truth.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    /// State shared by clones.
+    shared: Arc<Shared>,
+}
+
+/// Event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The policies changed and were swapped in.
+    Reloaded {
+        /// Revision of the new policies.
+        revision: u64,
+    },
+
+    /// A file failed to load or validate, so the previous policies were kept.
+    Rejected(Vec<SourceError>),
+}
+
+/// Watcher.
+///
+/// Polls the directory of a [`FileStore`] and reloads it when a file changes, until dropped.
+#[derive(Debug)]
+pub struct Watcher {
+    /// Set to stop the thread.
+    stop: Arc<AtomicBool>,
+
+    /// Polling thread.
+    thread: Option<JoinHandle<()>>,
+}
+
+/// State shared by the clones of a [`FileStore`].
+#[derive(Debug)]
+struct Shared {
+    /// Directory.
+    directory: PathBuf,
+
+    /// Fingerprint of the directory as of the last reload.
+    fingerprint: Mutex<Fingerprint>,
+
+    /// Schema the policies are validated against.
+    schema: Option<Schema>,
+
+    /// Policies, swapped as a whole on reload.
+    loaded: RwLock<Arc<Loaded>>,
+
+    /// Held for a whole reload, so a stale reload cannot swap in older files after a newer one.
+    reload: Mutex<()>,
+
+    /// Senders of the subscribers.
+    subscribers: Mutex<Vec<Sender<Event>>>,
+}
+
+/// Policies loaded as of a revision.
+#[derive(Debug, Default)]
+struct Loaded {
+    /// Policies.
+    policies: MemoryStore,
+
+    /// Revision.
+    revision: u64,
+}
+
+/// Length and modification time of each loaded file.
+type Fingerprint = BTreeMap<PathBuf, (u64, Option<SystemTime>)>;
+
+impl FileStore {
+    /// Opens a directory of policy documents.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`SourceError`] if a file cannot be loaded, or a policy id is defined twice.
+    pub fn open(directory: impl Into<PathBuf>) -> Result<Self, Vec<SourceError>> {
+        FileStore::new(directory.into(), None)
+    }
+
+    /// Opens a directory of policy documents, validating the policies against a schema on every
+    /// load.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`SourceError`] if a file cannot be loaded, a policy id is defined twice, or
+    /// a policy does not conform to the schema.
+    pub fn with_schema(
+        directory: impl Into<PathBuf>,
+        schema: Schema,
+    ) -> Result<Self, Vec<SourceError>> {
+        FileStore::new(directory.into(), Some(schema))
+    }
+
+    /// Returns the directory.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.shared.directory
+    }
+
+    /// Reloads the policies, returning true if they changed.
+    ///
+    /// Notifies the subscribers of changed or rejected policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`SourceError`] found, keeping the previous policies.
+    pub fn reload(&self) -> Result<bool, Vec<SourceError>> {
+        let shared = &self.shared;
+        let _reload = lock(&shared.reload);
+        let fingerprint = fingerprint(&shared.directory);
+
+        let policies = match load(&shared.directory, shared.schema.as_ref()) {
+            Ok(policies) => policies,
+            Err(errors) => {
+                *lock(&shared.fingerprint) = fingerprint;
+                shared.notify(&Event::Rejected(errors.clone()));
+                return Err(errors);
+            }
+        };
+        *lock(&shared.fingerprint) = fingerprint;
+
+        let mut loaded = shared
+            .loaded
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if loaded.policies.policies().eq(policies.policies()) {
+            return Ok(false);
+        }
+
+        let revision = loaded.revision + 1;
+        *loaded = Arc::new(Loaded { policies, revision });
+        drop(loaded);
+
+        shared.notify(&Event::Reloaded { revision });
+        Ok(true)
+    }
+
+    /// Returns a receiver of the events of every later reload.
+    #[must_use]
+    pub fn subscribe(&self) -> Receiver<Event> {
+        let (sender, receiver) = mpsc::channel();
+        lock(&self.shared.subscribers).push(sender);
+        receiver
+    }
+
+    /// Polls the directory every interval, reloading when a file is added, changed or removed.
+    #[must_use = "the directory is only watched until the watcher is dropped"]
+    pub fn watch(&self, interval: Duration) -> Watcher {
+        let stop = Arc::new(AtomicBool::new(false));
+        let store = self.clone();
+
+        let thread = thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || loop {
+                thread::park_timeout(interval);
+                if stop.load(Ordering::Acquire) {
+                    break;
+                }
+
+                if *lock(&store.shared.fingerprint) != fingerprint(&store.shared.directory) {
+                    // Rejections reach the subscribers, and the next change retries.
+                    let _ = store.reload();
+                }
+            }
+        });
+
+        Watcher {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Loads a directory into a new store.
+    fn new(directory: PathBuf, schema: Option<Schema>) -> Result<Self, Vec<SourceError>> {
+        let fingerprint = fingerprint(&directory);
+        let policies = load(&directory, schema.as_ref())?;
+
+        Ok(FileStore {
+            shared: Arc::new(Shared {
+                directory,
+                fingerprint: Mutex::new(fingerprint),
+                schema,
+                loaded: RwLock::new(Arc::new(Loaded {
+                    policies,
+                    revision: 0,
+                })),
+                reload: Mutex::new(()),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+        })
+    }
+
+    /// Returns the current policies.
+    fn loaded(&self) -> Arc<Loaded> {
+        let loaded = self
+            .shared
+            .loaded
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(&loaded)
+    }
+}
+
+impl PolicyStore for FileStore {
+    type Error = Error;
+
+    fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        self.loaded().policies.get(id)
+    }
+
+    fn insert(&mut self, _: Policy) -> Result<(), Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn update(&mut self, _: Policy) -> Result<(), Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn delete(&mut self, _: &str) -> Result<Option<Policy>, Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn list(&self) -> Result<Vec<Policy>, Error> {
+        self.loaded().policies.list()
+    }
+
+    fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        self.loaded().policies.query_by_action(action)
+    }
+
+    fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        self.loaded().policies.query_by_principal(principal)
+    }
+
+    fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        self.loaded().policies.query_by_resource(resource)
+    }
+
+    fn revision(&self) -> Result<u64, Error> {
+        Ok(self.loaded().revision)
+    }
+}
+
+impl Shared {
+    /// Sends an event to the subscribers, forgetting those which hung up.
+    fn notify(&self, event: &Event) {
+        lock(&self.subscribers).retain(|sender| sender.send(event.clone()).is_ok());
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Loads and validates the policies of every document in a directory.
+fn load(directory: &Path, schema: Option<&Schema>) -> Result<MemoryStore, Vec<SourceError>> {
+    let files = files(directory).map_err(|error| vec![error])?;
+
+    let mut errors = Vec::new();
+    let mut origins = BTreeMap::<String, PathBuf>::new();
+    let mut policies = Vec::new();
+
+    for file in files {
+        let document = match document::from_file(&file) {
+            Ok(document) => document,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        if let Some(Err(schema_errors)) =
+            schema.map(|schema| schema.validate_policies(&document.policies))
+        {
+            errors.extend(
+                schema_errors
+                    .iter()
+                    .map(|error| source_error(&file, error.to_string())),
+            );
+        }
+
+        for policy in document.policies {
+            if let Some(origin) = origins.get(&policy.id) {
+                errors.push(source_error(
+                    &file,
+                    format!(
+                        "duplicate policy id `{}`, first defined in {}",
+                        policy.id,
+                        origin.display()
+                    ),
+                ));
+                continue;
+            }
+
+            origins.insert(policy.id.clone(), file.clone());
+            policies.push(policy);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(policies.into_iter().collect())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Returns the documents of a directory, ordered by path.
+fn files(directory: &Path) -> Result<Vec<PathBuf>, SourceError> {
+    let entries =
+        fs::read_dir(directory).map_err(|error| source_error(directory, error.to_string()))?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|error| source_error(directory, error.to_string()))?
+            .path();
+        if path.is_file() && is_document(&path) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the fingerprint of the documents of a directory, empty if it cannot be read.
+fn fingerprint(directory: &Path) -> Fingerprint {
+    files(directory)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|file| {
+            let metadata = fs::metadata(&file).ok();
+            let len = metadata.as_ref().map_or(0, fs::Metadata::len);
+            let modified = metadata.and_then(|metadata| metadata.modified().ok());
+            (file, (len, modified))
+        })
+        .collect()
+}
+
+/// Returns true if the extension of a file is an enabled format.
+fn is_document(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        #[cfg(feature = "toml")]
+        Some("toml") => true,
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => true,
+        _ => false,
+    }
+}
+
+/// Returns a [`SourceError`] of a file without a position.
+fn source_error(file: &Path, message: String) -> SourceError {
+    SourceError {
+        column: 0,
+        file: Some(file.to_path_buf()),
+        line: 0,
+        message,
+    }
+}
+
+/// Locks a mutex, recovering the data if a holder panicked.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,517 @@
+//! Store.
+//!
+//! A [`PolicyStore`] keeps policies by id and finds the ones that may apply to an action,
+//! principal or resource, so integrations share one notion of wildcard matching. The
+//! [`MemoryStore`] keeps them in memory, indexed by scope.
+//!
+//! An [`AsyncPolicyStore`] mirrors it for backends doing I/O. Every [`PolicyStore`] is also an
+//! [`AsyncPolicyStore`], and a [`Snapshot`] reads an [`AsyncPolicyStore`] from sync contexts.
+//!
+//! With the `toml` or `yaml` features, a [`FileStore`] serves a directory of policy documents,
+//! reloading them when they change.
+//!
+//! Backends check that they behave like the [`MemoryStore`] with [`conformance::run`] or
+//! [`conformance::run_async`].
+
+pub mod conformance;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub mod file;
+mod snapshot;
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub use file::FileStore;
+pub use snapshot::Snapshot;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
+    future::{self, Future},
+};
+
+use crate::{collections::Map, Action, Policy, Principal, Resource};
+
+/// Policy store.
+///
+/// Queries return the policies with any action, principal or resource matching the one given,
+/// where `*` in a policy matches any value, ordered by id.
+pub trait PolicyStore {
+    /// Error.
+    type Error: core::error::Error;
+
+    /// Returns the policy with the id, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn get(&self, id: &str) -> Result<Option<Policy>, Self::Error>;
+
+    /// Inserts a policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a policy with the same id exists, or the store cannot be written.
+    fn insert(&mut self, policy: Policy) -> Result<(), Self::Error>;
+
+    /// Replaces the policy with the same id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no policy with the same id exists, or the store cannot be written.
+    fn update(&mut self, policy: Policy) -> Result<(), Self::Error>;
+
+    /// Deletes the policy with the id, returning it if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be written.
+    fn delete(&mut self, id: &str) -> Result<Option<Policy>, Self::Error>;
+
+    /// Returns every policy, ordered by id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn list(&self) -> Result<Vec<Policy>, Self::Error>;
+
+    /// Returns the policies with an action matching the [`Action`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Self::Error>;
+
+    /// Returns the policies with a principal matching the [`Principal`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Self::Error>;
+
+    /// Returns the policies with a resource matching the [`Resource`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Self::Error>;
+
+    /// Returns the revision, which increases with every write that changes the store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn revision(&self) -> Result<u64, Self::Error>;
+}
+
+/// Async policy store.
+///
+/// Mirrors [`PolicyStore`] for backends doing I/O, with futures that can be sent between
+/// threads.
+pub trait AsyncPolicyStore {
+    /// Error.
+    type Error: core::error::Error + Send;
+
+    /// Returns the policy with the id, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn get(&self, id: &str) -> impl Future<Output = Result<Option<Policy>, Self::Error>> + Send;
+
+    /// Inserts a policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a policy with the same id exists, or the store cannot be written.
+    fn insert(&mut self, policy: Policy) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Replaces the policy with the same id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no policy with the same id exists, or the store cannot be written.
+    fn update(&mut self, policy: Policy) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Deletes the policy with the id, returning it if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be written.
+    fn delete(
+        &mut self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<Policy>, Self::Error>> + Send;
+
+    /// Returns every policy, ordered by id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn list(&self) -> impl Future<Output = Result<Vec<Policy>, Self::Error>> + Send;
+
+    /// Returns the policies with an action matching the [`Action`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_action(
+        &self,
+        action: &Action,
+    ) -> impl Future<Output = Result<Vec<Policy>, Self::Error>> + Send;
+
+    /// Returns the policies with a principal matching the [`Principal`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_principal(
+        &self,
+        principal: &Principal,
+    ) -> impl Future<Output = Result<Vec<Policy>, Self::Error>> + Send;
+
+    /// Returns the policies with a resource matching the [`Resource`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn query_by_resource(
+        &self,
+        resource: &Resource,
+    ) -> impl Future<Output = Result<Vec<Policy>, Self::Error>> + Send;
+
+    /// Returns the revision, which increases with every write that changes the store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn revision(&self) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+}
+
+/// Completes each call when first polled, as the sync store does its work before returning.
+impl<S> AsyncPolicyStore for S
+where
+    S: PolicyStore,
+    S::Error: Send,
+{
+    type Error = S::Error;
+
+    fn get(&self, id: &str) -> impl Future<Output = Result<Option<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::get(self, id))
+    }
+
+    fn insert(&mut self, policy: Policy) -> impl Future<Output = Result<(), S::Error>> + Send {
+        future::ready(PolicyStore::insert(self, policy))
+    }
+
+    fn update(&mut self, policy: Policy) -> impl Future<Output = Result<(), S::Error>> + Send {
+        future::ready(PolicyStore::update(self, policy))
+    }
+
+    fn delete(
+        &mut self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::delete(self, id))
+    }
+
+    fn list(&self) -> impl Future<Output = Result<Vec<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::list(self))
+    }
+
+    fn query_by_action(
+        &self,
+        action: &Action,
+    ) -> impl Future<Output = Result<Vec<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::query_by_action(self, action))
+    }
+
+    fn query_by_principal(
+        &self,
+        principal: &Principal,
+    ) -> impl Future<Output = Result<Vec<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::query_by_principal(self, principal))
+    }
+
+    fn query_by_resource(
+        &self,
+        resource: &Resource,
+    ) -> impl Future<Output = Result<Vec<Policy>, S::Error>> + Send {
+        future::ready(PolicyStore::query_by_resource(self, resource))
+    }
+
+    fn revision(&self) -> impl Future<Output = Result<u64, S::Error>> + Send {
+        future::ready(PolicyStore::revision(self))
+    }
+}
+
+/// Change of a bulk update, for backends applying several writes at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Deletes the policy with the id, if it exists.
+    Delete(String),
+
+    /// Inserts a policy, failing if the id exists.
+    Insert(Policy),
+
+    /// Replaces the policy with the same id, failing if it does not exist.
+    Update(Policy),
+}
+
+/// Error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A policy with the id already exists.
+    Conflict(String),
+
+    /// No policy with the id exists.
+    NotFound(String),
+
+    /// The store cannot be written through, such as a [`Snapshot`].
+    ReadOnly,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Conflict(id) => write!(f, "policy `{id}` already exists"),
+            Error::NotFound(id) => write!(f, "policy `{id}` does not exist"),
+            Error::ReadOnly => write!(f, "store is read-only"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Memory store.
+///
+/// Indexes the policies by the scopes of their actions, principals and resources, starting at
+/// revision zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStore {
+    /// Action index.
+    actions: Index,
+
+    /// Policies by id.
+    policies: BTreeMap<String, Policy>,
+
+    /// Principal index.
+    principals: Index,
+
+    /// Resource index.
+    resources: Index,
+
+    /// Revision.
+    revision: u64,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    /// Returns the number of policies.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.policies.len()
+    }
+
+    /// Returns true if there are no policies.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Returns the policies, ordered by id.
+    pub fn policies(&self) -> impl Iterator<Item = &Policy> {
+        self.policies.values()
+    }
+
+    /// Indexes a policy and stores it, replacing any policy with the same id.
+    fn put(&mut self, policy: Policy) {
+        self.remove(&policy.id);
+
+        let id = &policy.id;
+        self.actions
+            .insert(id, policy.actions.iter().map(|a| &*a.scope));
+        self.principals
+            .insert(id, policy.principals.iter().map(|p| &*p.scope));
+        self.resources
+            .insert(id, policy.resources.iter().map(|r| &*r.scope));
+        self.policies.insert(policy.id.clone(), policy);
+    }
+
+    /// Removes a policy and its index entries.
+    fn remove(&mut self, id: &str) -> Option<Policy> {
+        let policy = self.policies.remove(id)?;
+
+        self.actions
+            .remove(id, policy.actions.iter().map(|a| &*a.scope));
+        self.principals
+            .remove(id, policy.principals.iter().map(|p| &*p.scope));
+        self.resources
+            .remove(id, policy.resources.iter().map(|r| &*r.scope));
+
+        Some(policy)
+    }
+
+    /// Returns the candidates of a scope in the index which satisfy the predicate.
+    fn query(
+        &self,
+        index: &Index,
+        scope: &str,
+        predicate: impl Fn(&Policy) -> bool,
+    ) -> Vec<Policy> {
+        index
+            .candidates(scope)
+            .filter_map(|id| self.policies.get(id))
+            .filter(|policy| predicate(policy))
+            .cloned()
+            .collect()
+    }
+}
+
+impl FromIterator<Policy> for MemoryStore {
+    /// Creates a store of the policies, where a later policy replaces an earlier one with the
+    /// same id.
+    fn from_iter<I: IntoIterator<Item = Policy>>(policies: I) -> Self {
+        let mut store = MemoryStore::new();
+        for policy in policies {
+            store.put(policy);
+        }
+        store
+    }
+}
+
+impl PolicyStore for MemoryStore {
+    type Error = Error;
+
+    fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        Ok(self.policies.get(id).cloned())
+    }
+
+    fn insert(&mut self, policy: Policy) -> Result<(), Error> {
+        if self.policies.contains_key(&policy.id) {
+            return Err(Error::Conflict(policy.id));
+        }
+
+        self.put(policy);
+        self.revision += 1;
+        Ok(())
+    }
+
+    fn update(&mut self, policy: Policy) -> Result<(), Error> {
+        if !self.policies.contains_key(&policy.id) {
+            return Err(Error::NotFound(policy.id));
+        }
+
+        self.put(policy);
+        self.revision += 1;
+        Ok(())
+    }
+
+    fn delete(&mut self, id: &str) -> Result<Option<Policy>, Error> {
+        let policy = self.remove(id);
+        if policy.is_some() {
+            self.revision += 1;
+        }
+        Ok(policy)
+    }
+
+    fn list(&self) -> Result<Vec<Policy>, Error> {
+        Ok(self.policies.values().cloned().collect())
+    }
+
+    fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        Ok(self.query(&self.actions, &action.scope, |policy| {
+            policy.actions.iter().any(|a| {
+                matches(&a.noun, &action.noun)
+                    && matches(&a.scope, &action.scope)
+                    && matches(&a.verb, &action.verb)
+            })
+        }))
+    }
+
+    fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        Ok(self.query(&self.principals, &principal.scope, |policy| {
+            policy.principals.iter().any(|p| {
+                matches(&p.id, &principal.id)
+                    && matches(&p.noun, &principal.noun)
+                    && matches(&p.scope, &principal.scope)
+            })
+        }))
+    }
+
+    fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        Ok(self.query(&self.resources, &resource.scope, |policy| {
+            policy.resources.iter().any(|r| {
+                matches(&r.id, &resource.id)
+                    && matches(&r.noun, &resource.noun)
+                    && matches(&r.scope, &resource.scope)
+            })
+        }))
+    }
+
+    fn revision(&self) -> Result<u64, Error> {
+        Ok(self.revision)
+    }
+}
+
+/// Index.
+///
+/// Ids of the policies with an entity of a scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Index {
+    /// Policies with an entity of a scope.
+    scopes: Map<String, BTreeSet<String>>,
+
+    /// Policies with an entity of any scope.
+    wildcard: BTreeSet<String>,
+}
+
+impl Index {
+    /// Indexes a policy under its scopes.
+    fn insert<'a>(&mut self, id: &str, scopes: impl Iterator<Item = &'a str>) {
+        for scope in scopes {
+            let ids = if scope == "*" {
+                &mut self.wildcard
+            } else {
+                self.scopes.entry(scope.into()).or_default()
+            };
+            ids.insert(id.into());
+        }
+    }
+
+    /// Removes a policy from its scopes.
+    fn remove<'a>(&mut self, id: &str, scopes: impl Iterator<Item = &'a str>) {
+        for scope in scopes {
+            if scope == "*" {
+                self.wildcard.remove(id);
+            } else if let Some(ids) = self.scopes.get_mut(scope) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.scopes.remove(scope);
+                }
+            }
+        }
+    }
+
+    /// Returns the ids of the policies which may match a scope, ordered and without duplicates.
+    fn candidates<'a>(&'a self, scope: &str) -> impl Iterator<Item = &'a String> {
+        static EMPTY: BTreeSet<String> = BTreeSet::new();
+
+        self.scopes
+            .get(scope)
+            .unwrap_or(&EMPTY)
+            .union(&self.wildcard)
+    }
+}
+
+/// Returns true if a segment of a policy matches a value, where `*` matches any value.
+fn matches(segment: &str, value: &str) -> bool {
+    segment == "*" || segment == value
+}
@@ -0,0 +1,127 @@
+//! Snapshot.
+//!
+//! A [`PolicyStore`] over a cached copy of an [`AsyncPolicyStore`], so that sync contexts such
+//! as game loops can query a store doing I/O.
+
+use alloc::vec::Vec;
+
+use super::{AsyncPolicyStore, Error, MemoryStore, PolicyStore};
+use crate::{Action, Policy, Principal, Resource};
+
+/// Snapshot.
+///
+/// Reads the policies of an [`AsyncPolicyStore`] as of its last [`refresh`](Snapshot::refresh).
+/// Writes go to the store itself through [`store_mut`](Snapshot::store_mut), and show once
+/// refreshed.
+#[derive(Debug, Clone)]
+pub struct Snapshot<S> {
+    /// Policies as of the revision.
+    cache: MemoryStore,
+
+    /// Revision of the store when cached.
+    revision: u64,
+
+    /// Store.
+    store: S,
+}
+
+impl<S: AsyncPolicyStore> Snapshot<S> {
+    /// Loads a snapshot of a store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    pub async fn load(store: S) -> Result<Self, S::Error> {
+        let mut snapshot = Snapshot {
+            cache: MemoryStore::new(),
+            revision: 0,
+            store,
+        };
+        snapshot.reload().await?;
+        Ok(snapshot)
+    }
+
+    /// Reloads the policies if the revision of the store changed, returning true if it did.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read, keeping the cached policies.
+    pub async fn refresh(&mut self) -> Result<bool, S::Error> {
+        if self.store.revision().await? == self.revision {
+            return Ok(false);
+        }
+
+        self.reload().await?;
+        Ok(true)
+    }
+
+    /// Returns the store.
+    #[must_use]
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the store, to write through it.
+    #[must_use]
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Returns the store, discarding the cached policies.
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    /// Caches every policy of the store.
+    ///
+    /// The revision is read first, so a write racing the copy is picked up by the next refresh.
+    async fn reload(&mut self) -> Result<(), S::Error> {
+        let revision = self.store.revision().await?;
+        let policies = self.store.list().await?;
+
+        self.cache = policies.into_iter().collect();
+        self.revision = revision;
+        Ok(())
+    }
+}
+
+impl<S> PolicyStore for Snapshot<S> {
+    type Error = Error;
+
+    fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        PolicyStore::get(&self.cache, id)
+    }
+
+    fn insert(&mut self, _: Policy) -> Result<(), Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn update(&mut self, _: Policy) -> Result<(), Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn delete(&mut self, _: &str) -> Result<Option<Policy>, Error> {
+        Err(Error::ReadOnly)
+    }
+
+    fn list(&self) -> Result<Vec<Policy>, Error> {
+        PolicyStore::list(&self.cache)
+    }
+
+    fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        PolicyStore::query_by_action(&self.cache, action)
+    }
+
+    fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        PolicyStore::query_by_principal(&self.cache, principal)
+    }
+
+    fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        PolicyStore::query_by_resource(&self.cache, resource)
+    }
+
+    fn revision(&self) -> Result<u64, Error> {
+        Ok(self.revision)
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::{
+    evaluate,
+    expression::{Comparison, Entity, Expr},
+    partial::Filter,
+    partial_evaluate, Condition, Context, Effect, Expression, Metadata, PartialContext, Policy,
+    Principal, Resource, Value,
+};
+
+#[test]
+fn should_return_policy_when_attributes_match() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(
+        r#"resource.owner == principal.id && principal.level >= resource.level && principal.profile["guild"] == "red""#,
+    )];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_attribute_is_missing() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"resource.rarity == "legendary""#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+#[test]
+fn should_substitute_principal_attributes_when_partially_evaluated() {
+    // Arrange
+    let context = PartialContext {
+        action: "world:loot:take".parse().unwrap(),
+        data: HashMap::new(),
+        principal: given_context().principal,
+    };
+    let policies = [given_policy("resource.level <= principal.level")];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::And(vec![
+            Filter::Equals(crate::partial::Field::Noun, "loot".to_string()),
+            Filter::Equals(crate::partial::Field::Scope, "world".to_string()),
+            Filter::Expr(Expr::Compare(
+                Comparison::LessThanOrEqual,
+                Box::new(Expr::Attribute(Entity::Resource, "level".to_string())),
+                Box::new(Expr::Int(5)),
+            )),
+        ])
+    );
+}
+
+#[test]
+fn should_deserialize_attributes_from_object_form() {
+    // Arrange
+    let json = json!({
+        "attributes": { "level": 5 },
+        "id": "1",
+        "noun": "player",
+        "scope": "world",
+    });
+
+    // Act
+    let result = serde_json::from_value::<Principal>(json.clone()).unwrap();
+
+    // Assert
+    assert_eq!(result.attributes["level"], Value::Int(5));
+    assert_eq!(serde_json::to_value(&result).unwrap(), json);
+}
+
+#[test]
+fn should_omit_attributes_when_empty() {
+    // Arrange
+    let resource = "world:loot:1".parse::<Resource>().unwrap();
+
+    // Act
+    let result = serde_json::to_value(&resource).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        json!({ "id": "1", "noun": "loot", "scope": "world" })
+    );
+}
+
+#[test]
+fn should_return_error_when_action_attribute_is_unknown() {
+    // Arrange
+    let source = "action.level > 1";
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "unknown attribute `action.level`"
+    );
+}
+
+fn given_context() -> Context {
+    serde_json::from_value(json!({
+        "action": "world:loot:take",
+        "data": {},
+        "principal": {
+            "attributes": { "level": 5, "profile": { "guild": "red" } },
+            "id": "1",
+            "noun": "player",
+            "scope": "world",
+        },
+        "resource": {
+            "attributes": { "level": 3, "owner": "1" },
+            "id": "2",
+            "noun": "loot",
+            "scope": "world",
+        },
+    }))
+    .unwrap()
+}
+
+fn given_policy(expression: &str) -> Policy {
+    Policy {
+        actions: vec!["world:loot:take".parse().unwrap()],
+        conditions: vec![Condition {
+            expression: Some(expression.parse().unwrap()),
+            ..Default::default()
+        }],
+        effect: Effect::Allow,
+        id: "policy-1".to_string(),
+        metadata: Metadata::default(),
+        principals: vec!["world:player:*".parse().unwrap()],
+        resources: vec!["world:loot:*".parse().unwrap()],
+    }
+}
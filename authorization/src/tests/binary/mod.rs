@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    binary::{self, Error, MAGIC, VERSION},
+    Action, Condition, Context, Effect, Metadata, Policy, PolicySet, Principal, Value,
+};
+
+#[test]
+fn should_round_trip() {
+    // Arrange
+    let set = given_set();
+
+    // Act
+    let bytes = binary::encode(&set);
+    let result = binary::decode(&bytes);
+
+    // Assert
+    assert_eq!(&bytes[..4], &MAGIC);
+    assert_eq!(result.unwrap(), set);
+}
+
+#[test]
+fn should_round_trip_typed_attributes() {
+    // Arrange
+    let mut principal: Principal = "world:player:*".parse().unwrap();
+    principal.attributes = HashMap::from([(
+        "profile".to_string(),
+        serde_json::from_value(serde_json::json!({
+            "guild": "red",
+            "ip": { "$ip": "::1" },
+            "joined": { "$timestamp": "2023-11-01T12:00:00Z" },
+            "level": 10,
+            "ratio": 0.5,
+            "tags": ["a", true],
+        }))
+        .unwrap(),
+    )]);
+    let mut policies = given_set().policies().to_vec();
+    policies[0].principals = vec![principal];
+    let set = PolicySet::new(policies);
+
+    // Act
+    let result = binary::decode(&binary::encode(&set));
+
+    // Assert
+    assert_eq!(result.unwrap(), set);
+}
+
+#[test]
+fn should_evaluate_decoded_set() {
+    // Arrange
+    let bytes = binary::encode(&given_set());
+    let context = Context {
+        action: "world:monster:attack".parse().unwrap(),
+        data: HashMap::from([(
+            "player:level".to_string(),
+            Value::from(HashSet::from(["10".to_string()])),
+        )]),
+        principal: "world:player:1".parse().unwrap(),
+        resource: "world:monster:2".parse().unwrap(),
+    };
+
+    // Act
+    let result = binary::decode(&bytes).unwrap();
+
+    // Assert
+    assert_eq!(result.evaluate(&context).unwrap().id, "attack-monsters");
+}
+
+#[test]
+fn should_return_error_when_magic_is_invalid() {
+    // Arrange
+    let mut bytes = binary::encode(&given_set());
+    bytes[0] = b'{';
+
+    // Act
+    let result = binary::decode(&bytes);
+
+    // Assert
+    assert_eq!(result.unwrap_err(), Error::Magic);
+}
+
+#[test]
+fn should_return_error_when_version_is_unknown() {
+    // Arrange
+    let mut bytes = binary::encode(&given_set());
+    bytes[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+
+    // Act
+    let result = binary::decode(&bytes);
+
+    // Assert
+    assert_eq!(result.unwrap_err(), Error::UnsupportedVersion(VERSION + 1));
+}
+
+#[test]
+fn should_return_error_when_payload_is_corrupted() {
+    // Arrange
+    let mut bytes = binary::encode(&given_set());
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+
+    // Act
+    let result = binary::decode(&bytes);
+
+    // Assert
+    assert_eq!(result.unwrap_err(), Error::Checksum);
+}
+
+#[test]
+fn should_return_error_when_truncated() {
+    // Arrange
+    let bytes = binary::encode(&given_set());
+
+    // Act
+    let result = binary::decode(&bytes[..6]);
+
+    // Assert
+    assert_eq!(result.unwrap_err(), Error::Truncated);
+}
+
+#[test]
+fn should_return_error_when_index_is_not_ascending() {
+    // Arrange
+    let mut set = given_set_of_scope();
+    set.allow.scopes.insert("world".to_string(), vec![1, 0]);
+
+    // Act
+    let result = binary::decode(&binary::encode(&set));
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error::Payload("index of scope `world` is not in ascending order".to_string())
+    );
+}
+
+#[test]
+fn should_return_error_when_index_is_missing_policy() {
+    // Arrange
+    let mut set = given_set_of_scope();
+    set.allow.scopes.insert("world".to_string(), vec![0]);
+
+    // Act
+    let result = binary::decode(&binary::encode(&set));
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error::Payload("index of scope `world` is missing policy `b`".to_string())
+    );
+}
+
+#[test]
+fn should_return_error_when_index_lists_policy_of_another_scope() {
+    // Arrange
+    let mut set = given_set_of_scope();
+    set.allow.scopes.insert("identity".to_string(), vec![0]);
+
+    // Act
+    let result = binary::decode(&binary::encode(&set));
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error::Payload(
+            "index of scope `identity` lists policy 0, which has no action in it".to_string()
+        )
+    );
+}
+
+fn given_set() -> PolicySet {
+    PolicySet::new(vec![
+        Policy {
+            actions: vec!["world:*:attack".parse().unwrap()],
+            conditions: vec![Condition {
+                expression: Some(r#"data["player:level"] >= 10"#.parse().unwrap()),
+                ..Default::default()
+            }],
+            effect: Effect::Allow,
+            id: "attack-monsters".to_string(),
+            metadata: Metadata {
+                tags: vec!["pve".to_string()],
+                ..Default::default()
+            },
+            principals: vec!["world:player:*".parse().unwrap()],
+            resources: vec!["world:monster:*".parse().unwrap()],
+        },
+        Policy {
+            actions: vec!["*:*:*".parse().unwrap()],
+            conditions: vec![],
+            effect: Effect::Deny,
+            id: "no-pvp".to_string(),
+            metadata: Metadata::default(),
+            principals: vec!["world:player:*".parse().unwrap()],
+            resources: vec!["world:player:*".parse().unwrap()],
+        },
+    ])
+}
+
+/// Returns a set of two policies allowing actions of the `world` scope.
+fn given_set_of_scope() -> PolicySet {
+    PolicySet::new(vec![
+        Policy::allow("a").action("world:monster:attack".parse::<Action>().unwrap()),
+        Policy::allow("b").action("world:loot:take".parse::<Action>().unwrap()),
+    ])
+}
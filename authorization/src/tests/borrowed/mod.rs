@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    borrowed::{ActionRef, PrincipalRef, ResourceRef, Values},
+    evaluate, Condition, Context, ContextRef, Data, Effect, Metadata, Policy, PolicySet, Value,
+};
+
+/// Request headers, looked up without allocating.
+struct Headers<'a> {
+    client_ip: &'a str,
+    host: &'a str,
+    roles: &'a [&'a str],
+}
+
+impl Data for Headers<'_> {
+    fn get(&self, key: &str) -> Option<Values<'_>> {
+        match key {
+            "request:client_ip" => Some(Values::One(self.client_ip)),
+            "request:host" => Some(Values::One(self.host)),
+            "request:roles" => Some(Values::Slice(self.roles)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn should_return_policy_when_string_equals() {
+    // Arrange
+    let headers = given_headers(&["admin"]);
+    let context = given_context(&headers);
+    let policies = [given_policy(Condition {
+        string_equals: Some(HashMap::from([(
+            "request:host".to_string(),
+            HashSet::from(["localhost:3000".to_string()]),
+        )])),
+        ..Default::default()
+    })];
+
+    // Act
+    let result = evaluate(context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_policy_when_expression_is_true() {
+    // Arrange
+    let headers = given_headers(&["admin", "user"]);
+    let context = given_context(&headers);
+    let policies = [given_policy(Condition {
+        expression: Some(
+            r#""admin" in data["request:roles"] && size(data["request:roles"]) == 2 && principal.id == resource.id"#
+                .parse()
+                .unwrap(),
+        ),
+        ..Default::default()
+    })];
+
+    // Act
+    let result = evaluate(context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_data_key_is_missing() {
+    // Arrange
+    let headers = given_headers(&[]);
+    let context = given_context(&headers);
+    let policies = [given_policy(Condition {
+        expression: Some(r#"data["request:referer"] == "localhost""#.parse().unwrap()),
+        ..Default::default()
+    })];
+
+    // Act
+    let result = evaluate(context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+#[test]
+fn should_agree_with_owned_context() {
+    // Arrange
+    let context = Context {
+        action: "identity:user:get".parse().unwrap(),
+        data: HashMap::from([(
+            "request:host".to_string(),
+            Value::from(HashSet::from(["localhost:3000".to_string()])),
+        )]),
+        principal: "local:user:1".parse().unwrap(),
+        resource: "identity:user:1".parse().unwrap(),
+    };
+    let set = PolicySet::new(vec![given_policy(Condition {
+        expression: Some(r#"data["request:host"] == "localhost:3000""#.parse().unwrap()),
+        ..Default::default()
+    })]);
+
+    // Act
+    let owned = set.evaluate(&context);
+    let borrowed = set.evaluate(ContextRef::from(&context));
+
+    // Assert
+    assert_eq!(owned.unwrap().id, "policy-1");
+    assert_eq!(borrowed, owned);
+}
+
+#[test]
+fn values_should_compare_typed_values_by_string_form() {
+    // Arrange
+    let set = HashSet::from(["10".to_string(), "a".to_string()]);
+    let typed = Value::Set([Value::Int(10), Value::from("a")].into());
+
+    // Act
+    let result = (
+        Values::Value(&typed).is_subset(&set),
+        Values::Value(&typed).contains("10"),
+        Values::Value(&Value::Int(10)).len(),
+        Values::Slice(&["a", "b"]).is_subset(&set),
+    );
+
+    // Assert
+    assert_eq!(result, (true, true, 1, false));
+}
+
+fn given_context<'a>(headers: &'a Headers<'a>) -> ContextRef<'a> {
+    ContextRef {
+        action: ActionRef {
+            noun: "user",
+            scope: "identity",
+            verb: "get",
+        },
+        data: headers,
+        principal: PrincipalRef {
+            attributes: &(),
+            id: "1",
+            noun: "user",
+            scope: "local",
+        },
+        resource: ResourceRef {
+            attributes: &(),
+            id: "1",
+            noun: "user",
+            scope: "identity",
+        },
+    }
+}
+
+fn given_headers<'a>(roles: &'a [&'a str]) -> Headers<'a> {
+    Headers {
+        client_ip: "127.0.0.1",
+        host: "localhost:3000",
+        roles,
+    }
+}
+
+fn given_policy(condition: Condition) -> Policy {
+    Policy {
+        actions: vec!["identity:user:*".parse().unwrap()],
+        conditions: vec![condition],
+        effect: Effect::Allow,
+        id: "policy-1".to_string(),
+        metadata: Metadata::default(),
+        principals: vec!["local:user:*".parse().unwrap()],
+        resources: vec!["identity:user:*".parse().unwrap()],
+    }
+}
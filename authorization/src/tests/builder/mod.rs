@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    Action, Condition, Context, Effect, Expression, Metadata, Policy, Principal, Resource, Value,
+};
+
+#[test]
+fn policy_should_build_policy() {
+    // Arrange
+    let expression = r#"data["player:level"] >= 10"#.parse::<Expression>().unwrap();
+
+    // Act
+    let result = Policy::deny("policy-1")
+        .action("world:*:attack".parse::<Action>().unwrap())
+        .principal("world:player:*".parse::<Principal>().unwrap())
+        .resource("world:monster:*".parse::<Resource>().unwrap())
+        .when(Condition::new().string_equals("request:host", ["localhost"]))
+        .when(Condition::from(expression));
+
+    // Assert
+    assert_eq!(
+        result,
+        Policy {
+            actions: vec!["world:*:attack".parse().unwrap()],
+            conditions: vec![
+                Condition {
+                    expression: None,
+                    string_equals: Some(HashMap::from([(
+                        "request:host".to_string(),
+                        HashSet::from(["localhost".to_string()]),
+                    )])),
+                },
+                Condition {
+                    expression: Some(r#"data["player:level"] >= 10"#.parse().unwrap()),
+                    string_equals: None,
+                },
+            ],
+            effect: Effect::Deny,
+            id: "policy-1".to_string(),
+            metadata: Metadata::default(),
+            principals: vec!["world:player:*".parse().unwrap()],
+            resources: vec!["world:monster:*".parse().unwrap()],
+        }
+    );
+}
+
+#[test]
+fn condition_should_merge_string_equals_of_same_key() {
+    // Act
+    let result = Condition::new()
+        .string_equals("geography:city", ["atlantis"])
+        .string_equals("geography:city", ["olympus".to_string()]);
+
+    // Assert
+    assert_eq!(
+        result.string_equals.unwrap()["geography:city"],
+        HashSet::from(["atlantis".to_string(), "olympus".to_string()])
+    );
+}
+
+#[test]
+fn context_should_build_context_with_data() {
+    // Act
+    let result = Context::new(
+        "world:monster:attack".parse::<Action>().unwrap(),
+        "world:player:1".parse::<Principal>().unwrap(),
+        "world:monster:2".parse::<Resource>().unwrap(),
+    )
+    .data("player:level", 10)
+    .data("player:name", "alice");
+
+    // Assert
+    assert_eq!(
+        result.data,
+        HashMap::from([
+            ("player:level".to_string(), Value::Int(10)),
+            ("player:name".to_string(), Value::from("alice")),
+        ])
+    );
+    assert_eq!(result.principal.id, "1");
+}
+
+#[test]
+fn entities_should_build_from_segments() {
+    // Act
+    let result = (
+        Action::new("world", "monster", "attack"),
+        Principal::new("world", "player", "1").attribute("level", 10),
+        Resource::new("world", "monster", "2"),
+    );
+
+    // Assert
+    assert_eq!(result.0, "world:monster:attack".parse().unwrap());
+    assert_eq!(
+        result.1,
+        Principal {
+            attributes: HashMap::from([("level".to_string(), Value::Int(10))]),
+            .."world:player:1".parse().unwrap()
+        }
+    );
+    assert_eq!(result.2, "world:monster:2".parse().unwrap());
+}
+
+#[test]
+fn metadata_should_build_metadata() {
+    // Act
+    let result = Metadata::new()
+        .description("Allows attacks.")
+        .owner("platform")
+        .tag("pve")
+        .tag("pvp")
+        .version(2);
+
+    // Assert
+    assert_eq!(
+        result,
+        Metadata {
+            description: Some("Allows attacks.".to_string()),
+            owner: Some("platform".to_string()),
+            tags: vec!["pve".to_string(), "pvp".to_string()],
+            version: Some(2),
+            ..Metadata::default()
+        }
+    );
+}
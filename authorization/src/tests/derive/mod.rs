@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    borrowed::ActionRef, Action, Actions, AsPrincipal, AsResource, Principal, Resource, Schema,
+    Value,
+};
+
+#[derive(AsPrincipal)]
+#[authorization(noun = "user", scope = "identity")]
+struct User {
+    #[authorization(id)]
+    id: u64,
+
+    #[authorization(attribute)]
+    level: i64,
+
+    #[authorization(attribute = "guild")]
+    team: String,
+
+    #[allow(dead_code)]
+    password: String,
+}
+
+#[derive(AsPrincipal, AsResource)]
+struct Identifier {
+    #[authorization(id)]
+    id: String,
+
+    #[authorization(noun)]
+    noun: String,
+
+    #[authorization(scope)]
+    scope: &'static str,
+}
+
+#[test]
+fn should_return_principal_with_fixed_segments_and_attributes() {
+    // Arrange
+    let user = User {
+        id: 1,
+        level: 5,
+        team: "red".to_string(),
+        password: "secret".to_string(),
+    };
+
+    // Act
+    let result = user.as_principal();
+
+    // Assert
+    assert_eq!(
+        result,
+        Principal {
+            attributes: HashMap::from([
+                ("guild".to_string(), Value::from("red")),
+                ("level".to_string(), Value::Int(5)),
+            ]),
+            id: "1".to_string(),
+            noun: "user".to_string(),
+            scope: "identity".to_string(),
+        }
+    );
+}
+
+#[test]
+fn should_return_resource_with_segments_from_fields() {
+    // Arrange
+    let identifier = Identifier {
+        id: "2".to_string(),
+        noun: "monster".to_string(),
+        scope: "world",
+    };
+
+    // Act
+    let result = Resource::from(&identifier);
+
+    // Assert
+    assert_eq!(result, "world:monster:2".parse().unwrap());
+    assert_eq!(
+        Principal::from(&identifier),
+        "world:monster:2".parse().unwrap()
+    );
+}
+
+#[derive(AsResource)]
+#[authorization(noun = "monster", scope = "world")]
+struct Monster {
+    #[authorization(id)]
+    id: u32,
+
+    #[authorization(attribute)]
+    health: u32,
+}
+
+#[test]
+fn should_return_resource_with_narrow_integer_attribute() {
+    // Arrange
+    let monster = Monster { id: 2, health: 80 };
+
+    // Act
+    let result = monster.as_resource();
+
+    // Assert
+    assert_eq!(
+        result,
+        Resource {
+            attributes: HashMap::from([("health".to_string(), Value::Int(80))]),
+            id: "2".to_string(),
+            noun: "monster".to_string(),
+            scope: "world".to_string(),
+        }
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Actions)]
+#[authorization(noun = "user", scope = "identity")]
+enum UserAction {
+    Delete,
+    Get,
+    #[authorization(verb = "list")]
+    Index,
+    PutAll,
+}
+
+#[test]
+fn should_return_actions_in_declaration_order() {
+    // Arrange
+    let actions = UserAction::ALL;
+
+    // Act
+    let result = actions
+        .iter()
+        .copied()
+        .map(Action::from)
+        .collect::<Vec<_>>();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![
+            "identity:user:delete".parse().unwrap(),
+            "identity:user:get".parse().unwrap(),
+            "identity:user:list".parse().unwrap(),
+            "identity:user:put_all".parse().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn should_return_action_ref() {
+    // Arrange
+    let action = UserAction::Get;
+
+    // Act
+    let result = action.action();
+
+    // Assert
+    assert_eq!(
+        result,
+        ActionRef {
+            noun: "user",
+            scope: "identity",
+            verb: "get",
+        }
+    );
+}
+
+#[test]
+fn should_insert_actions_into_schema() {
+    // Arrange
+    let mut schema = Schema::default();
+
+    // Act
+    schema.insert_actions::<UserAction>();
+
+    // Assert
+    assert_eq!(
+        schema.scopes["identity"].nouns["user"].verbs,
+        HashSet::from(["delete", "get", "list", "put_all"].map(String::from))
+    );
+}
@@ -0,0 +1,241 @@
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+use crate::{Effect, Metadata, Policy, PolicyDocument};
+
+#[test]
+fn should_upgrade_version_1() {
+    // Arrange
+    let json = r#"{
+        "policies": [{
+            "actions": [{ "noun": "user", "scope": "identity", "verb": "get" }],
+            "conditions": [{ "string_equals": { "request:host": ["localhost:3000"] } }],
+            "effect": "allow",
+            "id": "policy-1",
+            "principals": [],
+            "resources": []
+        }],
+        "version": 1
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json).unwrap();
+
+    // Assert
+    assert_eq!(result.version, PolicyDocument::VERSION);
+    assert_eq!(result.policies[0].id, "policy-1");
+    assert_eq!(result.policies[0].metadata, Metadata::default());
+}
+
+#[test]
+fn should_return_error_when_version_1_uses_expression() {
+    // Arrange
+    let json = r#"{
+        "policies": [{
+            "actions": [],
+            "conditions": [{ "expression": "true" }],
+            "effect": "allow",
+            "id": "policy-1",
+            "principals": [],
+            "resources": []
+        }],
+        "version": 1
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("policy `policy-1` uses `expression` which is not supported by version 1"));
+}
+
+#[test]
+fn should_return_error_when_version_1_uses_attributes() {
+    // Arrange
+    let json = r#"{
+        "policies": [{
+            "actions": [],
+            "conditions": [],
+            "effect": "allow",
+            "id": "policy-1",
+            "principals": [],
+            "resources": [{ "attributes": { "owner": "1" }, "id": "*", "noun": "user", "scope": "identity" }]
+        }],
+        "version": 1
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("policy `policy-1` uses `attributes` which is not supported by version 1"));
+}
+
+#[test]
+fn should_return_error_when_version_is_unknown() {
+    // Arrange
+    let json = r#"{ "policies": [], "version": 3 }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("unsupported policy document version 3, expected 1 to 2"));
+}
+
+#[test]
+fn should_return_error_when_version_is_unknown_and_policies_use_its_fields() {
+    // Arrange
+    let json = r#"{
+        "policies": [{
+            "actions": [],
+            "conditions": [],
+            "effect": "allow",
+            "id": "policy-1",
+            "obligations": [{ "log": "audit" }],
+            "principals": [],
+            "resources": []
+        }],
+        "version": 3
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("unsupported policy document version 3, expected 1 to 2"));
+}
+
+#[test]
+fn should_return_error_when_version_is_unknown_and_read_first() {
+    // Arrange
+    let json = r#"{ "version": 3, "policies": [{ "obligations": [] }] }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("unsupported policy document version 3, expected 1 to 2"));
+}
+
+#[test]
+fn should_upgrade_document_without_version_as_version_1() {
+    // Arrange
+    let json = r#"{ "policies": [{ "actions": [], "conditions": [], "effect": "deny", "id": "policy-1", "principals": [], "resources": [] }] }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json).unwrap();
+
+    // Assert
+    assert_eq!(result.version, PolicyDocument::VERSION);
+    assert_eq!(result.policies[0].effect, Effect::Deny);
+}
+
+#[test]
+fn should_upgrade_array_of_policies_as_version_1() {
+    // Arrange
+    let json = r#"[{ "actions": [], "conditions": [], "effect": "allow", "id": "policy-1", "principals": [], "resources": [] }]"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json).unwrap();
+
+    // Assert
+    assert_eq!(result.version, PolicyDocument::VERSION);
+    assert_eq!(result.policies[0].id, "policy-1");
+}
+
+#[test]
+fn should_return_error_when_document_without_version_uses_metadata() {
+    // Arrange
+    let json = r#"[{ "actions": [], "conditions": [], "effect": "allow", "id": "policy-1", "metadata": { "owner": "identity" }, "principals": [], "resources": [] }]"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("policy `policy-1` uses `metadata` which is not supported by version 1"));
+}
+
+#[test]
+fn should_return_error_when_field_is_unknown() {
+    // Arrange
+    let json = r#"{ "policies": [], "revision": 1, "version": 2 }"#;
+
+    // Act
+    let result = serde_json::from_str::<PolicyDocument>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("unknown field `revision`, expected `policies` or `version`"));
+}
+
+#[test]
+fn should_return_error_when_policy_field_is_unknown() {
+    // Arrange
+    let policy = r#"{
+        "actions": [],
+        "conditions": [{ "expression": "true", "obligation": "log" }],
+        "effect": "allow",
+        "id": "policy-1",
+        "principals": [],
+        "resources": []
+    }"#;
+    let json = format!(r#"{{ "policies": [{policy}], "version": 2 }}"#);
+
+    // Act
+    let document = serde_json::from_str::<PolicyDocument>(&json);
+    let policy = serde_json::from_str::<Policy>(policy);
+
+    // Assert
+    assert!(document
+        .unwrap_err()
+        .to_string()
+        .starts_with("unknown field `obligation`, expected `expression` or `string_equals`"));
+    assert_eq!(policy.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_round_trip_current_version() {
+    // Arrange
+    let document = PolicyDocument::new(vec![Policy {
+        actions: vec!["identity:user:get".parse().unwrap()],
+        conditions: vec![],
+        effect: Effect::Deny,
+        id: "policy-1".to_string(),
+        metadata: Metadata {
+            owner: Some("identity".to_string()),
+            ..Default::default()
+        },
+        principals: vec!["*:*:*".parse().unwrap()],
+        resources: vec!["identity:user:*".parse().unwrap()],
+    }]);
+
+    // Act
+    let json = serde_json::to_string(&document).unwrap();
+    let result = serde_json::from_str::<PolicyDocument>(&json);
+
+    // Assert
+    assert_eq!(result.unwrap(), document);
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use crate::{
+    document::{self, SourceError},
+    Effect, PolicyDocument,
+};
+
+const SOURCE: &str = r#"
+version = 2
+
+[[policies]]
+id = "attack-monsters"
+effect = "allow"
+principals = ["world:player:*"]
+actions = ["world:*:attack"]
+resources = [{ scope = "world", noun = "monster", id = "*" }]
+conditions = [{ expression = 'data["player:level"] >= 10' }]
+"#;
+
+#[test]
+fn from_toml_should_return_document() {
+    // Arrange
+    let source = SOURCE;
+
+    // Act
+    let result = document::from_toml(source).unwrap();
+
+    // Assert
+    assert_eq!(result.version, PolicyDocument::VERSION);
+    assert_eq!(result.policies[0].id, "attack-monsters");
+    assert_eq!(result.policies[0].effect, Effect::Allow);
+    assert_eq!(
+        result.policies[0].resources,
+        vec!["world:monster:*".parse().unwrap()]
+    );
+}
+
+#[test]
+fn from_toml_should_return_error_when_field_is_misspelled() {
+    // Arrange
+    let source = SOURCE.replace("effect =", "efect =");
+
+    // Act
+    let result = document::from_toml(&source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        SourceError {
+            column: 1,
+            file: None,
+            line: 6,
+            message: "unknown field `efect`, expected one of `actions`, `conditions`, `effect`, `id`, `metadata`, `principals`, `resources`".to_string(),
+        }
+    );
+}
+
+#[test]
+fn from_toml_should_return_error_when_effect_is_invalid() {
+    // Arrange
+    let source = SOURCE.replace("\"allow\"", "\"permit\"");
+
+    // Act
+    let result = document::from_toml(&source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        SourceError {
+            column: 10,
+            file: None,
+            line: 6,
+            message: "unknown variant `permit`, expected `allow` or `deny`".to_string(),
+        }
+    );
+}
+
+#[test]
+fn to_toml_should_round_trip() {
+    // Arrange
+    let document = document::from_toml(SOURCE).unwrap();
+
+    // Act
+    let source = document::to_toml(&document).unwrap();
+    let result = document::from_toml(&source);
+
+    // Assert
+    assert_eq!(result.unwrap(), document);
+}
+
+#[test]
+fn from_file_should_return_error_with_file() {
+    // Arrange
+    let file = given_file("policies.toml", &SOURCE.replace("effect =", "efect ="));
+
+    // Act
+    let result = document::from_file(&file);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with(&format!("{}:6:1: unknown field `efect`", file.display())));
+}
+
+fn given_file(name: &str, contents: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("authorization-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let file = directory.join(name);
+    std::fs::write(&file, contents).unwrap();
+    file
+}
@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    dsl::{self, Error, FormatError},
+    Action, Condition, Effect, Metadata, Policy, Principal, Resource, Value,
+};
+
+#[test]
+fn parse_should_return_policies() {
+    // Arrange
+    let source = r#"
+        # any player in the world can attack any monster in the world.
+        @id("attack-monsters")
+        @tags("pve")
+        allow player@world:*
+          to world:*:attack
+          on monster@world:*
+          when data["player:level"] >= 10;
+
+        @id("admin")
+        allow user@local:"00000000-0000-0000-0000-000000000000"
+          to *:*:*
+          on *@*:*
+          when {"request:client_ip": ["127.0.0.1"], "request:host": ["localhost:3000"]};
+    "#;
+
+    // Act
+    let result = dsl::parse(source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Policy {
+                actions: vec![given_action("world", "*", "attack")],
+                conditions: vec![Condition {
+                    expression: Some(r#"data["player:level"] >= 10"#.parse().unwrap()),
+                    ..Default::default()
+                }],
+                effect: Effect::Allow,
+                id: "attack-monsters".to_string(),
+                metadata: Metadata {
+                    tags: vec!["pve".to_string()],
+                    ..Default::default()
+                },
+                principals: vec![given_principal("player", "world", "*")],
+                resources: vec![given_resource("monster", "world", "*")],
+            },
+            Policy {
+                actions: vec![given_action("*", "*", "*")],
+                conditions: vec![Condition {
+                    string_equals: Some(HashMap::from([
+                        (
+                            "request:client_ip".to_string(),
+                            HashSet::from(["127.0.0.1".to_string()]),
+                        ),
+                        (
+                            "request:host".to_string(),
+                            HashSet::from(["localhost:3000".to_string()]),
+                        ),
+                    ])),
+                    ..Default::default()
+                }],
+                effect: Effect::Allow,
+                id: "admin".to_string(),
+                metadata: Metadata::default(),
+                principals: vec![given_principal(
+                    "user",
+                    "local",
+                    "00000000-0000-0000-0000-000000000000"
+                )],
+                resources: vec![given_resource("*", "*", "*")],
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_should_return_error_with_position() {
+    // Arrange
+    let source = "@id(\"policy-1\")\nallow player@world:*\n  on monster@world:*;";
+
+    // Act
+    let result = dsl::parse(source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error {
+            column: 3,
+            line: 3,
+            message: "expected `to`".to_string(),
+        }
+    );
+}
+
+#[test]
+fn parse_should_return_error_with_position_within_expression() {
+    // Arrange
+    let source = "@id(\"policy-1\")\nallow player@world:*\n  to world:*:attack\n  on monster@world:*\n  when action.id == \"get\";";
+
+    // Act
+    let result = dsl::parse(source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error {
+            column: 15,
+            line: 5,
+            message: "unknown attribute `action.id`".to_string(),
+        }
+    );
+}
+
+#[test]
+fn parse_should_return_error_when_id_is_missing() {
+    // Arrange
+    let source = "deny player@world:* to world:*:attack on player@world:*;";
+
+    // Act
+    let result = dsl::parse(source);
+
+    // Assert
+    assert_eq!(result.unwrap_err().message, "missing `@id`");
+}
+
+#[test]
+fn parse_should_return_error_when_timestamp_is_invalid() {
+    // Arrange
+    let source = "@id(\"policy-1\")\n@created_at(\"yesterday\")\ndeny player@world:* to world:*:attack on player@world:*;";
+
+    // Act
+    let result = dsl::parse(source);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error {
+            column: 1,
+            line: 2,
+            message: "`@created_at` expects an RFC 3339 timestamp".to_string(),
+        }
+    );
+}
+
+#[test]
+fn format_should_round_trip() {
+    // Arrange
+    let policies = vec![
+        Policy {
+            actions: vec![
+                given_action("world", "*", "attack"),
+                given_action("world", "loot", "take"),
+            ],
+            conditions: vec![
+                Condition {
+                    expression: Some(
+                        r#"data["when"] == "a;b" && principal.scope == resource.scope"#
+                            .parse()
+                            .unwrap(),
+                    ),
+                    string_equals: Some(HashMap::from([(
+                        "geography:city".to_string(),
+                        HashSet::from(["atlantis".to_string(), "olympus".to_string()]),
+                    )])),
+                },
+                Condition::default(),
+            ],
+            effect: Effect::Deny,
+            id: "policy \"1\"".to_string(),
+            metadata: Metadata {
+                created_at: Some("2023-11-01T00:00:00Z".parse().unwrap()),
+                description: Some("no attacking\nat night".to_string()),
+                owner: Some("game-design".to_string()),
+                tags: vec!["pve".to_string(), "pvp".to_string()],
+                updated_at: Some("2023-11-02T00:00:00Z".parse().unwrap()),
+                version: Some(3),
+            },
+            principals: vec![
+                given_principal("none", "world", "*"),
+                given_principal("player", "world", "a b:c"),
+            ],
+            resources: vec![],
+        },
+        Policy {
+            actions: vec![],
+            conditions: vec![],
+            effect: Effect::Allow,
+            id: "policy-2".to_string(),
+            metadata: Metadata::default(),
+            principals: vec![],
+            resources: vec![given_resource("*", "", "*")],
+        },
+    ];
+
+    // Act
+    let source = dsl::format(&policies).unwrap();
+    let result = dsl::parse(&source);
+
+    // Assert
+    assert_eq!(result.unwrap(), policies);
+}
+
+#[test]
+fn format_should_round_trip_expression_with_surrounding_whitespace() {
+    // Arrange
+    let policies = vec![Policy {
+        conditions: vec![Condition {
+            expression: Some("\n  principal.id == resource.id \n".parse().unwrap()),
+            ..Default::default()
+        }],
+        ..Policy::allow("policy-1")
+    }];
+
+    // Act
+    let source = dsl::format(&policies).unwrap();
+    let result = dsl::parse(&source);
+
+    // Assert
+    assert_eq!(result.unwrap(), policies);
+}
+
+#[test]
+fn format_should_return_error_when_principal_has_attributes() {
+    // Arrange
+    let mut principal = given_principal("player", "world", "1");
+    principal
+        .attributes
+        .insert("level".to_string(), Value::from(10));
+    let policies = [Policy::allow("policy-1").principal(principal)];
+
+    // Act
+    let result = dsl::format(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        FormatError {
+            id: "policy-1".to_string(),
+            message: "principals and resources cannot have attributes".to_string(),
+        }
+    );
+}
+
+#[test]
+fn format_should_return_error_when_resource_has_attributes() {
+    // Arrange
+    let mut resource = given_resource("monster", "world", "1");
+    resource
+        .attributes
+        .insert("level".to_string(), Value::from(10));
+    let policies = [Policy::deny("policy-1").resource(resource)];
+
+    // Act
+    let result = dsl::format(&policies);
+
+    // Assert
+    assert_eq!(result.unwrap_err().id, "policy-1");
+}
+
+#[test]
+fn format_should_return_source() {
+    // Arrange
+    let policies = [Policy {
+        actions: vec![given_action("world", "*", "attack")],
+        conditions: vec![Condition {
+            expression: Some(r#"data["player:level"] >= 10"#.parse().unwrap()),
+            ..Default::default()
+        }],
+        effect: Effect::Allow,
+        id: "attack-monsters".to_string(),
+        metadata: Metadata {
+            tags: vec!["pve".to_string()],
+            ..Default::default()
+        },
+        principals: vec![given_principal("player", "world", "*")],
+        resources: vec![given_resource("monster", "world", "*")],
+    }];
+
+    // Act
+    let result = dsl::format(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        r#"@id("attack-monsters")
+@tags("pve")
+allow player@world:*
+  to world:*:attack
+  on monster@world:*
+  when data["player:level"] >= 10;
+"#
+    );
+}
+
+fn given_action(scope: &str, noun: &str, verb: &str) -> Action {
+    Action {
+        noun: noun.to_string(),
+        scope: scope.to_string(),
+        verb: verb.to_string(),
+    }
+}
+
+fn given_principal(noun: &str, scope: &str, id: &str) -> Principal {
+    Principal {
+        attributes: HashMap::new(),
+        id: id.to_string(),
+        noun: noun.to_string(),
+        scope: scope.to_string(),
+    }
+}
+
+fn given_resource(noun: &str, scope: &str, id: &str) -> Resource {
+    Resource {
+        attributes: HashMap::new(),
+        id: id.to_string(),
+        noun: noun.to_string(),
+        scope: scope.to_string(),
+    }
+}
@@ -2,7 +2,7 @@ mod wildcard;
 
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy() {
@@ -110,6 +110,7 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-1".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -129,6 +130,7 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -137,6 +139,7 @@ fn given_policies() -> [Policy; 2] {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -145,6 +148,7 @@ fn given_principal() -> Principal {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
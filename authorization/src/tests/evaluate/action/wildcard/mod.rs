@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy_when_noun() {
@@ -26,6 +26,7 @@ fn should_return_policy_when_noun() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -62,6 +63,7 @@ fn should_return_policy_when_scope() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -98,6 +100,7 @@ fn should_return_policy_when_verb() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -120,6 +123,7 @@ fn given_policy() -> Policy {
         conditions: vec![],
         effect: Effect::Allow,
         id: "policy-1".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![given_resource()],
     }
@@ -127,6 +131,7 @@ fn given_policy() -> Policy {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -135,6 +140,7 @@ fn given_principal() -> Principal {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    evaluate, Action, Condition, Context, Effect, Metadata, Policy, Principal, Resource, Value,
+};
+
+#[test]
+fn should_return_policy_when_expression_is_true() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(
+        r#"data["player:level"] >= 10 && principal.scope == resource.scope"#,
+    )];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_expression_is_false() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"data["player:level"] > 10"#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+#[test]
+fn should_return_policy_when_value_in_list() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(
+        r#"action.verb in ["attack", "take"] && "olympus" in data["geography:city"]"#,
+    )];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_policy_when_has_and_size() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(
+        r#"has(data["geography:city"]) && size(data["geography:city"]) == 2 && !has(data["missing"])"#,
+    )];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_data_key_is_missing() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"data["missing"] == "value""#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+#[test]
+fn should_return_policy_when_or_absorbs_missing_data_key() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"data["missing"] == "value" || true"#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_and_absorbs_missing_data_key() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"data["missing"] == "value" && !true"#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+#[test]
+fn should_return_deny_policy_when_or_absorbs_missing_data_key() {
+    // Arrange
+    let context = given_context();
+    let policies = [
+        given_policy("true"),
+        given_deny_policy(r#"data["missing"] == "value" || principal.id == "1""#),
+    ];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "deny-1");
+}
+
+#[test]
+fn should_return_deny_policy_when_missing_data_key_is_guarded_by_has() {
+    // Arrange
+    let context = given_context();
+    let policies = [
+        given_policy("true"),
+        given_deny_policy(r#"!has(data["missing"]) || data["missing"] != "trusted""#),
+    ];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "deny-1");
+}
+
+#[test]
+fn should_skip_deny_policy_when_missing_data_key_decides_result() {
+    // Arrange
+    let context = given_context();
+    let policies = [
+        given_policy("true"),
+        given_deny_policy(r#"data["missing"] == "value" && principal.id == "1""#),
+    ];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "policy-1");
+}
+
+#[test]
+fn should_return_none_when_data_value_is_not_an_integer() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(r#"data["geography:city"] >= 10"#)];
+
+    // Act
+    let result = evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+fn given_action() -> Action {
+    Action {
+        noun: "monster".to_string(),
+        scope: "world".to_string(),
+        verb: "attack".to_string(),
+    }
+}
+
+fn given_context() -> Context {
+    Context {
+        action: given_action(),
+        data: HashMap::from([
+            (
+                "geography:city".to_string(),
+                Value::from(HashSet::from([
+                    "atlantis".to_string(),
+                    "olympus".to_string(),
+                ])),
+            ),
+            (
+                "player:level".to_string(),
+                Value::from(HashSet::from(["10".to_string()])),
+            ),
+        ]),
+        principal: given_principal(),
+        resource: given_resource(),
+    }
+}
+
+fn given_policy(expression: &str) -> Policy {
+    Policy {
+        actions: vec![given_action()],
+        conditions: vec![Condition {
+            expression: Some(expression.parse().unwrap()),
+            ..Default::default()
+        }],
+        effect: Effect::Allow,
+        id: "policy-1".to_string(),
+        metadata: Metadata::default(),
+        principals: vec![given_principal()],
+        resources: vec![given_resource()],
+    }
+}
+
+fn given_deny_policy(expression: &str) -> Policy {
+    Policy {
+        effect: Effect::Deny,
+        id: "deny-1".to_string(),
+        ..given_policy(expression)
+    }
+}
+
+fn given_principal() -> Principal {
+    Principal {
+        attributes: HashMap::new(),
+        id: "1".to_string(),
+        noun: "player".to_string(),
+        scope: "world".to_string(),
+    }
+}
+
+fn given_resource() -> Resource {
+    Resource {
+        attributes: HashMap::new(),
+        id: "2".to_string(),
+        noun: "monster".to_string(),
+        scope: "world".to_string(),
+    }
+}
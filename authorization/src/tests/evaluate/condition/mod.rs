@@ -1 +1,2 @@
+mod expression;
 mod string_equals;
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{evaluate, Action, Condition, Context, Effect, Policy, Principal, Resource};
+use crate::{
+    evaluate, Action, Condition, Context, Effect, Metadata, Policy, Principal, Resource, Value,
+};
 
 #[test]
 fn should_return_policy() {
@@ -10,11 +12,14 @@ fn should_return_policy() {
         data: HashMap::from([
             (
                 "geography:city".to_string(),
-                HashSet::from(["atlantis".to_string(), "olympus".to_string()]),
+                Value::from(HashSet::from([
+                    "atlantis".to_string(),
+                    "olympus".to_string(),
+                ])),
             ),
             (
                 "geography:planet".to_string(),
-                HashSet::from(["earth".to_string(), "mars".to_string()]),
+                Value::from(HashSet::from(["earth".to_string(), "mars".to_string()])),
             ),
         ]),
         principal: given_principal(),
@@ -43,6 +48,7 @@ fn should_return_policy() {
             }],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -63,11 +69,18 @@ fn should_return_none_when_not_subset() {
         data: HashMap::from([
             (
                 "geography:city".to_string(),
-                HashSet::from(["atlantis".to_string(), "olympus".to_string()]),
+                Value::from(HashSet::from([
+                    "atlantis".to_string(),
+                    "olympus".to_string(),
+                ])),
             ),
             (
                 "geography:planet".to_string(),
-                HashSet::from(["earth".to_string(), "mars".to_string(), "venus".to_string()]),
+                Value::from(HashSet::from([
+                    "earth".to_string(),
+                    "mars".to_string(),
+                    "venus".to_string(),
+                ])),
             ),
         ]),
         principal: given_principal(),
@@ -92,6 +105,7 @@ fn should_return_none_when_not_subset() {
             }],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -124,6 +138,7 @@ fn given_policy() -> Policy {
         }],
         effect: Effect::Allow,
         id: "policy-1".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![given_resource()],
     }
@@ -131,6 +146,7 @@ fn given_policy() -> Policy {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -139,6 +155,7 @@ fn given_principal() -> Principal {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{evaluate, Action, Condition, Context, Effect, Policy, Principal, Resource};
+use crate::{
+    evaluate, Action, Condition, Context, Effect, Metadata, Policy, Principal, Resource, Value,
+};
 
 #[test]
 fn should_return_policy() {
@@ -9,7 +11,10 @@ fn should_return_policy() {
         action: given_action(),
         data: HashMap::from([(
             "geography:city".to_string(),
-            HashSet::from(["atlantis".to_string(), "olympus".to_string()]),
+            Value::from(HashSet::from([
+                "atlantis".to_string(),
+                "olympus".to_string(),
+            ])),
         )]),
         principal: given_principal(),
         resource: given_resource(),
@@ -31,6 +36,7 @@ fn should_return_policy() {
             }],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -50,14 +56,15 @@ fn should_return_none_when_not_subset() {
         action: given_action(),
         data: HashMap::from([(
             "geography:city".to_string(),
-            HashSet::from([
+            Value::from(HashSet::from([
                 "atlantis".to_string(),
                 "elysium".to_string(),
                 "olympus".to_string(),
-            ]),
+            ])),
         )]),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
@@ -76,6 +83,7 @@ fn should_return_none_when_not_subset() {
             }],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![given_resource()],
         },
@@ -108,6 +116,7 @@ fn given_policy() -> Policy {
         }],
         effect: Effect::Allow,
         id: "policy-1".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![given_resource()],
     }
@@ -115,6 +124,7 @@ fn given_policy() -> Policy {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -123,6 +133,7 @@ fn given_principal() -> Principal {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_allow_policy() {
@@ -70,6 +70,7 @@ fn given_policy_allow() -> Policy {
         conditions: vec![],
         effect: Effect::Allow,
         id: "allow-id".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![given_resource()],
     }
@@ -81,6 +82,7 @@ fn given_policy_deny() -> Policy {
         conditions: vec![],
         effect: Effect::Deny,
         id: "deny-id".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![given_resource()],
     }
@@ -88,6 +90,7 @@ fn given_policy_deny() -> Policy {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -96,6 +99,7 @@ fn given_principal() -> Principal {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
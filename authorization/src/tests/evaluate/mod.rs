@@ -19,11 +19,13 @@ fn should_return_none_when_no_policies_have_been_provided() {
         },
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "first-party".to_string(),
         },
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
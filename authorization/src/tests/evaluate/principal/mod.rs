@@ -2,7 +2,7 @@ mod wildcard;
 
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy() {
@@ -11,6 +11,7 @@ fn should_return_policy() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "2".to_string(),
             noun: "user".to_string(),
             scope: "first-party".to_string(),
@@ -33,6 +34,7 @@ fn should_return_none_when_id_not_match() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "3".to_string(),
             noun: "user".to_string(),
             scope: "first-party".to_string(),
@@ -55,6 +57,7 @@ fn should_none_when_noun_not_match() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "role".to_string(),
             scope: "first-party".to_string(),
@@ -77,6 +80,7 @@ fn should_none_when_scope_not_match() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "second-party".to_string(),
@@ -107,13 +111,16 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-1".to_string(),
+            metadata: Metadata::default(),
             principals: vec![
                 Principal {
+                    attributes: HashMap::new(),
                     id: "1".to_string(),
                     noun: "user".to_string(),
                     scope: "first-party".to_string(),
                 },
                 Principal {
+                    attributes: HashMap::new(),
                     id: "1".to_string(),
                     noun: "group".to_string(),
                     scope: "third-party".to_string(),
@@ -126,13 +133,16 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![
                 Principal {
+                    attributes: HashMap::new(),
                     id: "2".to_string(),
                     noun: "user".to_string(),
                     scope: "first-party".to_string(),
                 },
                 Principal {
+                    attributes: HashMap::new(),
                     id: "2".to_string(),
                     noun: "group".to_string(),
                     scope: "third-party".to_string(),
@@ -145,6 +155,7 @@ fn given_policies() -> [Policy; 2] {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy_when_id() {
@@ -9,6 +9,7 @@ fn should_return_policy_when_id() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "2".to_string(),
             noun: "user".to_string(),
             scope: "first-party".to_string(),
@@ -22,7 +23,9 @@ fn should_return_policy_when_id() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![Principal {
+                attributes: HashMap::new(),
                 id: "*".to_string(),
                 noun: "user".to_string(),
                 scope: "first-party".to_string(),
@@ -45,6 +48,7 @@ fn should_return_policy_when_noun() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "role".to_string(),
             scope: "first-party".to_string(),
@@ -58,7 +62,9 @@ fn should_return_policy_when_noun() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![Principal {
+                attributes: HashMap::new(),
                 id: "1".to_string(),
                 noun: "*".to_string(),
                 scope: "first-party".to_string(),
@@ -81,6 +87,7 @@ fn should_return_policy_when_scope() {
         action: given_action(),
         data: HashMap::new(),
         principal: Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "role".to_string(),
             scope: "third-party".to_string(),
@@ -94,7 +101,9 @@ fn should_return_policy_when_scope() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![Principal {
+                attributes: HashMap::new(),
                 id: "1".to_string(),
                 noun: "role".to_string(),
                 scope: "*".to_string(),
@@ -128,7 +137,9 @@ fn given_policy() -> Policy {
         conditions: vec![],
         effect: Effect::Allow,
         id: "policy-1".to_string(),
+        metadata: Metadata::default(),
         principals: vec![Principal {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "first-party".to_string(),
@@ -139,6 +150,7 @@ fn given_policy() -> Policy {
 
 fn given_resource() -> Resource {
     Resource {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "identity".to_string(),
@@ -2,7 +2,7 @@ mod wildcard;
 
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy() {
@@ -12,6 +12,7 @@ fn should_return_policy() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "2".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
@@ -34,6 +35,7 @@ fn should_return_none_when_id_not_match() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "3".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
@@ -56,6 +58,7 @@ fn should_return_none_when_noun_not_match() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "role".to_string(),
             scope: "identity".to_string(),
@@ -78,6 +81,7 @@ fn should_none_when_scope_not_match() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "permission".to_string(),
@@ -107,14 +111,17 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-1".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![
                 Resource {
+                    attributes: HashMap::new(),
                     id: "1".to_string(),
                     noun: "user".to_string(),
                     scope: "identity".to_string(),
                 },
                 Resource {
+                    attributes: HashMap::new(),
                     id: "1".to_string(),
                     noun: "password".to_string(),
                     scope: "credential".to_string(),
@@ -126,14 +133,17 @@ fn given_policies() -> [Policy; 2] {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![
                 Resource {
+                    attributes: HashMap::new(),
                     id: "2".to_string(),
                     noun: "user".to_string(),
                     scope: "identity".to_string(),
                 },
                 Resource {
+                    attributes: HashMap::new(),
                     id: "2".to_string(),
                     noun: "password".to_string(),
                     scope: "credential".to_string(),
@@ -145,6 +155,7 @@ fn given_policies() -> [Policy; 2] {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::{evaluate, Action, Context, Effect, Policy, Principal, Resource};
+use crate::{evaluate, Action, Context, Effect, Metadata, Policy, Principal, Resource};
 
 #[test]
 fn should_return_policy_when_id() {
@@ -10,6 +10,7 @@ fn should_return_policy_when_id() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "2".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
@@ -22,8 +23,10 @@ fn should_return_policy_when_id() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![Resource {
+                attributes: HashMap::new(),
                 id: "*".to_string(),
                 noun: "user".to_string(),
                 scope: "identity".to_string(),
@@ -46,6 +49,7 @@ fn should_return_policy_when_noun() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "password".to_string(),
             scope: "identity".to_string(),
@@ -58,8 +62,10 @@ fn should_return_policy_when_noun() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![Resource {
+                attributes: HashMap::new(),
                 id: "1".to_string(),
                 noun: "*".to_string(),
                 scope: "identity".to_string(),
@@ -82,6 +88,7 @@ fn should_return_policy_when_scope() {
         data: HashMap::new(),
         principal: given_principal(),
         resource: Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "credential".to_string(),
@@ -94,8 +101,10 @@ fn should_return_policy_when_scope() {
             conditions: vec![],
             effect: Effect::Allow,
             id: "policy-2".to_string(),
+            metadata: Metadata::default(),
             principals: vec![given_principal()],
             resources: vec![Resource {
+                attributes: HashMap::new(),
                 id: "1".to_string(),
                 noun: "user".to_string(),
                 scope: "*".to_string(),
@@ -124,8 +133,10 @@ fn given_policy() -> Policy {
         conditions: vec![],
         effect: Effect::Allow,
         id: "policy-1".to_string(),
+        metadata: Metadata::default(),
         principals: vec![given_principal()],
         resources: vec![Resource {
+            attributes: HashMap::new(),
             id: "1".to_string(),
             noun: "user".to_string(),
             scope: "identity".to_string(),
@@ -135,6 +146,7 @@ fn given_policy() -> Policy {
 
 fn given_principal() -> Principal {
     Principal {
+        attributes: HashMap::new(),
         id: "1".to_string(),
         noun: "user".to_string(),
         scope: "first-party".to_string(),
@@ -0,0 +1,146 @@
+use crate::{
+    expression::{Error, MAX_DEPTH, MAX_NODES},
+    Expression, Policy,
+};
+
+#[test]
+fn should_parse_expression() {
+    // Arrange
+    let source = r#"data["player:level"] >= 10 && principal.scope == resource.scope"#;
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(result.unwrap().to_string(), source);
+}
+
+#[test]
+fn should_return_error_with_position_when_syntax_is_invalid() {
+    // Arrange
+    let source = "principal.id == \"1\" &&\n  resource.id ==";
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error {
+            column: 17,
+            line: 2,
+            message: "expected expression".to_string(),
+        }
+    );
+}
+
+#[test]
+fn should_return_error_when_attribute_is_unknown() {
+    // Arrange
+    let source = "action.id == \"get\"";
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(result.unwrap_err().message, "unknown attribute `action.id`");
+}
+
+#[test]
+fn should_return_error_when_types_mismatch() {
+    // Arrange
+    let source = "principal.id >= 10";
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "cannot compare string with int"
+    );
+}
+
+#[test]
+fn should_return_error_when_not_bool() {
+    // Arrange
+    let source = "principal.id";
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "expression must be bool, found string"
+    );
+}
+
+#[test]
+fn should_return_error_when_too_deep() {
+    // Arrange
+    let source = format!(
+        "{}true{}",
+        "(".repeat(MAX_DEPTH + 1),
+        ")".repeat(MAX_DEPTH + 1)
+    );
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        format!("expression exceeds {MAX_DEPTH} levels of nesting")
+    );
+}
+
+#[test]
+fn should_return_error_when_too_many_nodes() {
+    // Arrange
+    let source = vec!["true"; MAX_NODES].join(" && ");
+
+    // Act
+    let result = source.parse::<Expression>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        format!("expression exceeds {MAX_NODES} nodes")
+    );
+}
+
+#[test]
+fn should_return_error_when_deserializing_invalid_expression() {
+    // Arrange
+    let json = r#"{
+        "actions": [],
+        "conditions": [{ "expression": "data[\"level\"] >= " }],
+        "effect": "allow",
+        "id": "policy-1",
+        "principals": [],
+        "resources": []
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<Policy>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("line 1, column 18: expected expression"));
+}
+
+#[test]
+fn should_round_trip_expression_through_serde() {
+    // Arrange
+    let json = r#"{"actions":[],"conditions":[{"expression":"has(data[\"level\"])","string_equals":null}],"effect":"allow","id":"policy-1","principals":[],"resources":[]}"#;
+
+    // Act
+    let policy = serde_json::from_str::<Policy>(json).unwrap();
+    let result = serde_json::to_string(&policy).unwrap();
+
+    // Assert
+    assert_eq!(result, json);
+}
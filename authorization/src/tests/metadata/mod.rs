@@ -0,0 +1,78 @@
+use crate::{Effect, Metadata, Policy};
+
+#[test]
+fn should_deserialize_policy_without_metadata() {
+    // Arrange
+    let json = r#"{
+        "actions": [],
+        "conditions": [],
+        "effect": "allow",
+        "id": "policy-1",
+        "principals": [],
+        "resources": []
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<Policy>(json);
+
+    // Assert
+    assert_eq!(result.unwrap().metadata, Metadata::default());
+}
+
+#[test]
+fn should_round_trip_metadata_through_serde() {
+    // Arrange
+    let policy = Policy {
+        actions: vec![],
+        conditions: vec![],
+        effect: Effect::Allow,
+        id: "policy-1".to_string(),
+        metadata: Metadata {
+            created_at: Some("2023-11-01T00:00:00Z".parse().unwrap()),
+            description: Some("any player can attack any player.".to_string()),
+            owner: Some("game-design".to_string()),
+            tags: vec!["pvp".to_string()],
+            updated_at: Some("2023-11-02T00:00:00Z".parse().unwrap()),
+            version: Some(2),
+        },
+        principals: vec![],
+        resources: vec![],
+    };
+
+    // Act
+    let json = serde_json::to_string(&policy).unwrap();
+    let result = serde_json::from_str::<Policy>(&json);
+
+    // Assert
+    assert_eq!(result.unwrap(), policy);
+}
+
+#[test]
+fn should_return_error_when_timestamp_is_invalid() {
+    // Arrange
+    let json = r#"{ "created_at": "2023-11-01" }"#;
+
+    // Act
+    let result = serde_json::from_str::<Metadata>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("invalid timestamp `2023-11-01`"));
+}
+
+#[test]
+fn has_tag_should_return_true_when_tagged() {
+    // Arrange
+    let metadata = Metadata {
+        tags: vec!["pve".to_string(), "pvp".to_string()],
+        ..Default::default()
+    };
+
+    // Act
+    let result = (metadata.has_tag("pvp"), metadata.has_tag("spawn"));
+
+    // Assert
+    assert_eq!(result, (true, false));
+}
@@ -1 +1,20 @@
+mod attributes;
+#[cfg(feature = "binary")]
+mod binary;
+mod borrowed;
+mod builder;
+#[cfg(feature = "derive")]
+mod derive;
+mod document;
+mod dsl;
 mod evaluate;
+mod expression;
+mod metadata;
+mod partial;
+#[cfg(feature = "schemars")]
+mod schema;
+mod set;
+mod store;
+mod urn;
+mod validate;
+mod value;
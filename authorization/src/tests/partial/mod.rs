@@ -0,0 +1,357 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    borrowed::ContextRef,
+    evaluate,
+    expression::{self, Comparison, Entity, Expr},
+    partial::{Field, Filter},
+    partial_evaluate, Action, Condition, Context, Effect, Metadata, PartialContext, Policy,
+    Principal, Resource, Value,
+};
+
+#[test]
+fn should_return_false_when_no_policies_have_been_provided() {
+    // Arrange
+    let context = given_context();
+    let policies = [];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, Filter::Bool(false));
+}
+
+#[test]
+fn should_return_false_when_principal_does_not_match() {
+    // Arrange
+    let context = given_context();
+    let policies = [Policy {
+        principals: vec![Principal {
+            attributes: HashMap::new(),
+            id: "2".to_string(),
+            noun: "user".to_string(),
+            scope: "first-party".to_string(),
+        }],
+        ..given_policy(Effect::Allow, "allow-id")
+    }];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, Filter::Bool(false));
+}
+
+#[test]
+fn should_return_true_when_resource_is_wildcard() {
+    // Arrange
+    let context = given_context();
+    let policies = [Policy {
+        resources: vec![given_resource("*", "*", "*")],
+        ..given_policy(Effect::Allow, "allow-id")
+    }];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, Filter::Bool(true));
+}
+
+#[test]
+fn should_return_resource_filter() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy(Effect::Allow, "allow-id")];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::And(vec![
+            Filter::Equals(Field::Noun, "user".to_string()),
+            Filter::Equals(Field::Scope, "identity".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn should_exclude_denied_resources() {
+    // Arrange
+    let context = given_context();
+    let policies = [
+        given_policy(Effect::Allow, "allow-id"),
+        Policy {
+            resources: vec![given_resource("1", "user", "identity")],
+            ..given_policy(Effect::Deny, "deny-id")
+        },
+    ];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::And(vec![
+            Filter::Equals(Field::Noun, "user".to_string()),
+            Filter::Equals(Field::Scope, "identity".to_string()),
+            Filter::Not(Box::new(Filter::And(vec![
+                Filter::Equals(Field::Id, "1".to_string()),
+                Filter::Equals(Field::Noun, "user".to_string()),
+                Filter::Equals(Field::Scope, "identity".to_string()),
+            ]))),
+        ])
+    );
+}
+
+#[test]
+fn should_resolve_known_and_keep_unresolved_condition_keys() {
+    // Arrange
+    let context = given_context();
+    let policies = [Policy {
+        conditions: vec![Condition {
+            string_equals: Some(HashMap::from([
+                (
+                    "geography:city".to_string(),
+                    HashSet::from(["atlantis".to_string(), "olympus".to_string()]),
+                ),
+                (
+                    "request:host".to_string(),
+                    HashSet::from(["localhost:3000".to_string()]),
+                ),
+            ])),
+            ..Default::default()
+        }],
+        resources: vec![given_resource("*", "*", "*")],
+        ..given_policy(Effect::Allow, "allow-id")
+    }];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::Subset(
+            "request:host".to_string(),
+            HashSet::from(["localhost:3000".to_string()])
+        )
+    );
+}
+
+#[test]
+fn should_translate_resource_expression_into_filter() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy_with_expression(
+        r#"resource.id == principal.id || resource.noun in ["group", "user"]"#,
+    )];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::Or(vec![
+            Filter::Equals(Field::Id, "1".to_string()),
+            Filter::Equals(Field::Noun, "group".to_string()),
+            Filter::Equals(Field::Noun, "user".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn should_keep_resource_expression_that_references_data() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy_with_expression(
+        r#"resource.noun == "user" && data["geography:city"] == ["olympus"]"#,
+    )];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::Expr(Expr::And(
+            Box::new(Expr::Compare(
+                Comparison::Equal,
+                Box::new(Expr::Attribute(Entity::Resource, "noun".to_string())),
+                Box::new(Expr::String("user".to_string())),
+            )),
+            Box::new(Expr::Compare(
+                Comparison::Equal,
+                Box::new(Expr::Data("geography:city".to_string())),
+                Box::new(Expr::List(vec![Expr::String("olympus".to_string())])),
+            )),
+        ))
+    );
+}
+
+#[test]
+fn should_resolve_expression_that_does_not_reference_resource() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy_with_expression(
+        r#"principal.scope == "third-party""#,
+    )];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(result, Filter::Bool(false));
+}
+
+#[test]
+fn should_keep_negated_expression_that_can_fail() {
+    // Arrange
+    let context = given_context();
+    let policies = [given_policy_with_expression("!(resource.level > 5)")];
+
+    // Act
+    let result = partial_evaluate(&context, &policies);
+
+    // Assert
+    assert_eq!(
+        result,
+        Filter::Expr(Expr::Not(Box::new(Expr::Compare(
+            Comparison::GreaterThan,
+            Box::new(Expr::Attribute(Entity::Resource, "level".to_string())),
+            Box::new(Expr::Int(5)),
+        ))))
+    );
+}
+
+#[test]
+fn should_agree_with_evaluate_when_resource_attribute_is_missing() {
+    // Arrange
+    let context = given_context();
+    let policies = [
+        given_policy_with_expression(r#"!(resource.level > 5) || resource.id == "public""#),
+        Policy {
+            effect: Effect::Deny,
+            id: "deny-id".to_string(),
+            ..given_policy_with_expression(r#"resource.banned == "yes""#)
+        },
+    ];
+    let resources = [
+        given_resource("1", "user", "identity"),
+        given_resource("public", "user", "identity"),
+        given_resource_with_attribute("level", Value::from(3)),
+        given_resource_with_attribute("level", Value::from(7)),
+        given_resource_with_attribute("banned", Value::from("yes")),
+    ];
+
+    // Act
+    let filter = partial_evaluate(&context, &policies);
+
+    // Assert
+    for resource in resources {
+        let context = Context {
+            action: context.action.clone(),
+            data: context.data.clone(),
+            principal: context.principal.clone(),
+            resource,
+        };
+        let expected =
+            evaluate(&context, &policies).is_some_and(|policy| policy.effect == Effect::Allow);
+        assert_eq!(
+            matches(&filter, &context),
+            expected,
+            "{:?}",
+            context.resource
+        );
+    }
+}
+
+fn given_action() -> Action {
+    Action {
+        noun: "user".to_string(),
+        scope: "identity".to_string(),
+        verb: "list".to_string(),
+    }
+}
+
+fn given_context() -> PartialContext {
+    PartialContext {
+        action: given_action(),
+        data: HashMap::from([(
+            "geography:city".to_string(),
+            Value::from(HashSet::from(["olympus".to_string()])),
+        )]),
+        principal: given_principal(),
+    }
+}
+
+fn given_policy(effect: Effect, id: &str) -> Policy {
+    Policy {
+        actions: vec![given_action()],
+        conditions: vec![],
+        effect,
+        id: id.to_string(),
+        metadata: Metadata::default(),
+        principals: vec![given_principal()],
+        resources: vec![given_resource("*", "user", "identity")],
+    }
+}
+
+fn given_policy_with_expression(expression: &str) -> Policy {
+    Policy {
+        conditions: vec![Condition {
+            expression: Some(expression.parse().unwrap()),
+            ..Default::default()
+        }],
+        resources: vec![given_resource("*", "*", "*")],
+        ..given_policy(Effect::Allow, "allow-id")
+    }
+}
+
+fn given_principal() -> Principal {
+    Principal {
+        attributes: HashMap::new(),
+        id: "1".to_string(),
+        noun: "user".to_string(),
+        scope: "first-party".to_string(),
+    }
+}
+
+fn given_resource_with_attribute(attribute: &str, value: Value) -> Resource {
+    Resource {
+        attributes: HashMap::from([(attribute.to_string(), value)]),
+        ..given_resource("1", "user", "identity")
+    }
+}
+
+fn given_resource(id: &str, noun: &str, scope: &str) -> Resource {
+    Resource {
+        attributes: HashMap::new(),
+        id: id.to_string(),
+        noun: noun.to_string(),
+        scope: scope.to_string(),
+    }
+}
+
+/// Returns true if a [`Filter`] is true for the resource and data of a context.
+fn matches(filter: &Filter, context: &Context) -> bool {
+    match filter {
+        Filter::And(filters) => filters.iter().all(|filter| matches(filter, context)),
+        Filter::Bool(value) => *value,
+        Filter::Equals(Field::Id, value) => context.resource.id == *value,
+        Filter::Equals(Field::Noun, value) => context.resource.noun == *value,
+        Filter::Equals(Field::Scope, value) => context.resource.scope == *value,
+        Filter::Expr(expr) => expression::matches(expr, &ContextRef::from(context)),
+        Filter::Not(filter) => !matches(filter, context),
+        Filter::Or(filters) => filters.iter().any(|filter| matches(filter, context)),
+        Filter::Subset(key, values) => ContextRef::from(context)
+            .data
+            .get(key)
+            .is_some_and(|data| data.is_subset(values)),
+    }
+}
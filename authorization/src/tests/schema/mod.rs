@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{value, Context, Effect, Metadata, Policy, PolicyDocument};
+
+#[test]
+fn should_use_draft_2020_12() {
+    // Arrange
+    let schema = schemars::schema_for!(PolicyDocument);
+
+    // Act
+    let result = schema.get("$schema");
+
+    // Assert
+    assert_eq!(
+        result,
+        Some(&json!("https://json-schema.org/draft/2020-12/schema"))
+    );
+}
+
+#[test]
+fn should_agree_with_serde_on_context() {
+    // Arrange
+    let instances = [
+        json!({
+            "action": "world:*:attack",
+            "data": { "player:level": ["10"] },
+            "principal": { "id": "1", "noun": "player", "scope": "world" },
+            "resource": "world:monster:2"
+        }),
+        json!({
+            "action": "world:attack",
+            "data": {},
+            "principal": "world:player:1",
+            "resource": "world:monster:2"
+        }),
+        json!({
+            "action": { "noun": "monster", "scope": "world" },
+            "data": {},
+            "principal": "world:player:1",
+            "resource": "world:monster:2"
+        }),
+        json!({
+            "action": "world:monster:attack",
+            "data": { "player:level": "10" },
+            "principal": "world:player:1",
+            "resource": "world:monster:2"
+        }),
+        json!({
+            "action": "world:monster:attack",
+            "principal": "world:player:1",
+            "resource": r"world:monster:\2"
+        }),
+        json!({
+            "action": "world:monster:attack",
+            "data": {},
+            "principal": { "attributes": { "level": 10 }, "id": "1", "noun": "player", "scope": "world" },
+            "resource": { "attributes": [], "id": "2", "noun": "monster", "scope": "world" }
+        }),
+    ];
+
+    // Act & Assert
+    then_schema_and_serde_agree::<Context>(&instances);
+}
+
+#[test]
+fn should_agree_with_serde_on_policy_document() {
+    // Arrange
+    let instances = [
+        json!({ "policies": [], "version": 2 }),
+        json!({ "policies": [], "version": 0 }),
+        json!({ "policies": [], "version": 3 }),
+        json!({ "policies": [] }),
+        json!({ "policies": [], "revision": 1, "version": 2 }),
+        json!([]),
+        json!({
+            "policies": [{
+                "actions": ["identity:user:get"],
+                "conditions": [{
+                    "expression": "principal.id == resource.id",
+                    "string_equals": { "request:host": ["localhost:3000"] }
+                }],
+                "effect": "allow",
+                "id": "policy-1",
+                "metadata": { "owner": "identity", "tags": ["users"], "version": 1 },
+                "principals": ["local:user:*"],
+                "resources": [{ "id": "*", "noun": "user", "scope": "identity" }]
+            }],
+            "version": 2
+        }),
+        json!({
+            "policies": [{
+                "actions": [],
+                "conditions": [],
+                "effect": "permit",
+                "id": "policy-1",
+                "principals": [],
+                "resources": []
+            }],
+            "version": 2
+        }),
+        json!({
+            "policies": [{
+                "actions": [],
+                "conditions": [],
+                "effect": "allow",
+                "principals": [],
+                "resources": []
+            }],
+            "version": 2
+        }),
+        json!({
+            "policies": [{
+                "actions": [],
+                "conditions": [{ "string_equals": { "request:host": "localhost" } }],
+                "effect": "allow",
+                "id": "policy-1",
+                "principals": [],
+                "resources": []
+            }],
+            "version": 2
+        }),
+        json!({
+            "policies": [{
+                "actions": [],
+                "conditions": [],
+                "effect": "allow",
+                "id": "policy-1",
+                "metadata": { "version": -1 },
+                "principals": [],
+                "resources": []
+            }],
+            "version": 2
+        }),
+        json!({
+            "policies": [{
+                "actions": [],
+                "conditions": [{ "expression": "x".repeat(4097) }],
+                "effect": "allow",
+                "id": "policy-1",
+                "principals": [],
+                "resources": []
+            }],
+            "version": 2
+        }),
+    ];
+
+    // Act & Assert
+    then_schema_and_serde_agree::<PolicyDocument>(&instances);
+}
+
+#[test]
+fn should_validate_serialized_values() {
+    // Arrange
+    let context = Context {
+        action: "world:monster:attack".parse().unwrap(),
+        data: HashMap::from([(
+            "player:level".to_string(),
+            value::Value::from(HashSet::from(["10".to_string()])),
+        )]),
+        principal: r"world:player:a\:b".parse().unwrap(),
+        resource: "world:monster:2".parse().unwrap(),
+    };
+    let document = PolicyDocument::new(vec![Policy {
+        actions: vec!["world:*:attack".parse().unwrap()],
+        conditions: vec![],
+        effect: Effect::Deny,
+        id: "policy-1".to_string(),
+        metadata: Metadata {
+            created_at: Some("2023-11-01T00:00:00Z".parse().unwrap()),
+            ..Default::default()
+        },
+        principals: vec!["world:player:*".parse().unwrap()],
+        resources: vec!["world:monster:*".parse().unwrap()],
+    }]);
+
+    // Act
+    let context = serde_json::to_value(&context).unwrap();
+    let document = serde_json::to_value(&document).unwrap();
+
+    // Assert
+    assert!(given_validator::<Context>().is_valid(&context));
+    assert!(given_validator::<PolicyDocument>().is_valid(&document));
+}
+
+fn given_validator<T: schemars::JsonSchema>() -> jsonschema::Validator {
+    let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap();
+    jsonschema::validator_for(&schema).unwrap()
+}
+
+fn then_schema_and_serde_agree<T: schemars::JsonSchema + DeserializeOwned>(instances: &[Value]) {
+    let validator = given_validator::<T>();
+
+    for instance in instances {
+        assert_eq!(
+            validator.is_valid(instance),
+            serde_json::from_value::<T>(instance.clone()).is_ok(),
+            "{instance}"
+        );
+    }
+}
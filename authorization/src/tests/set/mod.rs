@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::{evaluate, Context, Effect, Metadata, Policy, PolicySet};
+
+#[test]
+fn should_return_policy_in_scope() {
+    // Arrange
+    let set = PolicySet::new(given_policies());
+    let context = given_context("world:monster:attack");
+
+    // Act
+    let result = set.evaluate(&context);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "attack");
+}
+
+#[test]
+fn should_return_wildcard_policy() {
+    // Arrange
+    let set = PolicySet::new(given_policies());
+    let context = given_context("identity:user:get");
+
+    // Act
+    let result = set.evaluate(&context);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "any");
+}
+
+#[test]
+fn should_return_deny_policy_when_allow_and_deny_policy_match() {
+    // Arrange
+    let set = PolicySet::new(given_policies());
+    let context = given_context("world:loot:take");
+
+    // Act
+    let result = set.evaluate(&context);
+
+    // Assert
+    assert_eq!(result.unwrap().id, "no-looting");
+}
+
+#[test]
+fn should_agree_with_evaluate() {
+    // Arrange
+    let policies = given_policies();
+    let set = PolicySet::new(policies.clone());
+    let actions = [
+        "identity:user:get",
+        "world:loot:take",
+        "world:monster:attack",
+        "world:player:attack",
+    ];
+
+    // Act & Assert
+    for action in actions {
+        let context = given_context(action);
+        assert_eq!(set.evaluate(&context), evaluate(&context, &policies));
+    }
+}
+
+fn given_context(action: &str) -> Context {
+    Context {
+        action: action.parse().unwrap(),
+        data: HashMap::new(),
+        principal: "world:player:1".parse().unwrap(),
+        resource: "world:monster:2".parse().unwrap(),
+    }
+}
+
+fn given_policies() -> Vec<Policy> {
+    vec![
+        given_policy("any", Effect::Allow, &["*:*:get", "*:loot:take"]),
+        given_policy(
+            "attack",
+            Effect::Allow,
+            &["world:*:attack", "world:*:attack"],
+        ),
+        given_policy("no-looting", Effect::Deny, &["world:loot:*"]),
+        given_policy("no-pvp", Effect::Deny, &["world:player:attack"]),
+    ]
+}
+
+fn given_policy(id: &str, effect: Effect, actions: &[&str]) -> Policy {
+    Policy {
+        actions: actions
+            .iter()
+            .map(|action| action.parse().unwrap())
+            .collect(),
+        conditions: vec![],
+        effect,
+        id: id.to_string(),
+        metadata: Metadata::default(),
+        principals: vec!["*:*:*".parse().unwrap()],
+        resources: vec!["*:*:*".parse().unwrap()],
+    }
+}
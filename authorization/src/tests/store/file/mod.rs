@@ -0,0 +1,305 @@
+use std::{
+    fs,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use tempfile::TempDir;
+
+use crate::{
+    store::{
+        file::{Event, FileStore},
+        Error,
+    },
+    Policy, PolicyStore, Principal, Schema,
+};
+
+#[test]
+fn open_should_load_policies_of_every_document() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:player:*"),
+    );
+    given_file(
+        &directory,
+        "loot.toml",
+        &given_document("loot", "world:*:*"),
+    );
+    given_file(&directory, "README.md", "ignored");
+
+    // Act
+    let store = FileStore::open(directory.path()).unwrap();
+
+    // Assert
+    let result = store
+        .query_by_principal(&"world:player:1".parse::<Principal>().unwrap())
+        .unwrap();
+    assert_eq!(ids(&result), ["attack", "loot"]);
+    assert_eq!(store.revision(), Ok(0));
+}
+
+#[test]
+fn reload_should_swap_policies_and_notify() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:player:*"),
+    );
+    let store = FileStore::open(directory.path()).unwrap();
+    let events = store.subscribe();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:monster:*"),
+    );
+
+    // Act
+    let result = store.reload();
+
+    // Assert
+    assert_eq!(result, Ok(true));
+    assert_eq!(events.try_recv(), Ok(Event::Reloaded { revision: 1 }));
+    assert_eq!(
+        store.get("attack").unwrap().unwrap().principals,
+        vec!["world:monster:*".parse().unwrap()]
+    );
+}
+
+#[test]
+fn reload_should_keep_latest_policies_when_called_from_two_threads() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:0:*"),
+    );
+    let store = FileStore::open(directory.path()).unwrap();
+    let done = AtomicBool::new(false);
+
+    // Act
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            while !done.load(Ordering::Acquire) {
+                let _ = store.reload();
+            }
+        });
+        scope.spawn(|| {
+            for version in 1..=50 {
+                let principal = format!("world:{version}:*");
+                given_file(
+                    &directory,
+                    "attack.toml",
+                    &given_document("attack", &principal),
+                );
+                let _ = store.reload();
+            }
+            done.store(true, Ordering::Release);
+        });
+    });
+
+    // Assert
+    assert_eq!(
+        store.get("attack").unwrap().unwrap().principals,
+        vec!["world:50:*".parse().unwrap()]
+    );
+    assert_eq!(store.reload(), Ok(false));
+}
+
+#[test]
+fn reload_should_return_false_when_policies_are_unchanged() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:player:*"),
+    );
+    let store = FileStore::open(directory.path()).unwrap();
+    let events = store.subscribe();
+
+    // Act
+    let result = store.reload();
+
+    // Assert
+    assert_eq!(result, Ok(false));
+    assert!(events.try_recv().is_err());
+    assert_eq!(store.revision(), Ok(0));
+}
+
+#[test]
+fn reload_should_keep_previous_policies_when_document_is_invalid() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:player:*"),
+    );
+    given_file(
+        &directory,
+        "loot.toml",
+        &given_document("loot", "world:*:*"),
+    );
+    let store = FileStore::open(directory.path()).unwrap();
+    let events = store.subscribe();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:monster:*"),
+    );
+    given_file(
+        &directory,
+        "loot.toml",
+        "version = 2\n[[policies]]\nefect = \"allow\"\n",
+    );
+
+    // Act
+    let result = store.reload();
+
+    // Assert
+    let errors = result.unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].file, Some(directory.path().join("loot.toml")));
+    assert_eq!(events.try_recv(), Ok(Event::Rejected(errors)));
+    assert_eq!(
+        store.get("attack").unwrap().unwrap().principals,
+        vec!["world:player:*".parse().unwrap()]
+    );
+}
+
+#[test]
+fn open_should_return_error_when_id_is_duplicated() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "a.toml",
+        &given_document("attack", "world:player:*"),
+    );
+    given_file(
+        &directory,
+        "b.toml",
+        &given_document("attack", "world:monster:*"),
+    );
+
+    // Act
+    let result = FileStore::open(directory.path());
+
+    // Assert
+    let errors = result.unwrap_err();
+    assert_eq!(
+        errors[0].to_string(),
+        format!(
+            "{}: duplicate policy id `attack`, first defined in {}",
+            directory.path().join("b.toml").display(),
+            directory.path().join("a.toml").display()
+        )
+    );
+}
+
+#[test]
+fn with_schema_should_return_error_when_policy_does_not_conform() {
+    // Arrange
+    let directory = given_directory();
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:mosnter:*"),
+    );
+    let schema = serde_json::from_value::<Schema>(serde_json::json!({
+        "scopes": {
+            "world": {
+                "nouns": {
+                    "monster": { "verbs": ["attack"] },
+                    "player": { "verbs": [] }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    // Act
+    let result = FileStore::with_schema(directory.path(), schema);
+
+    // Assert
+    let errors = result.unwrap_err();
+    assert_eq!(errors[0].file, Some(directory.path().join("attack.toml")));
+    assert!(errors[0].message.contains("did you mean `monster`?"));
+}
+
+#[test]
+fn insert_should_return_read_only() {
+    // Arrange
+    let directory = given_directory();
+    let mut store = FileStore::open(directory.path()).unwrap();
+
+    // Act
+    let result = store.insert(Policy::allow("attack"));
+
+    // Assert
+    assert_eq!(result, Err(Error::ReadOnly));
+}
+
+#[test]
+fn watch_should_reload_when_document_is_added() {
+    // Arrange
+    let directory = given_directory();
+    let store = FileStore::open(directory.path()).unwrap();
+    let events = store.subscribe();
+    let _watcher = store.watch(Duration::from_millis(10));
+
+    // Act
+    given_file(
+        &directory,
+        "attack.toml",
+        &given_document("attack", "world:player:*"),
+    );
+
+    // Assert
+    let event = events.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(event, Event::Reloaded { revision: 1 });
+    assert!(store.get("attack").unwrap().is_some());
+}
+
+/// Returns an empty directory, removed when dropped.
+fn given_directory() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("authorization-store-")
+        .tempdir()
+        .unwrap()
+}
+
+fn given_document(id: &str, principal: &str) -> String {
+    format!(
+        r#"
+version = 2
+
+[[policies]]
+id = "{id}"
+effect = "allow"
+principals = ["{principal}"]
+actions = ["world:monster:attack"]
+resources = ["world:monster:*"]
+conditions = []
+"#
+    )
+}
+
+/// Writes a file atomically, so that a watcher never reads it partly written.
+fn given_file(directory: &TempDir, name: &str, contents: &str) {
+    let path = directory.path().join(name);
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, contents).unwrap();
+    fs::rename(temporary, path).unwrap();
+}
+
+fn ids(policies: &[Policy]) -> Vec<&str> {
+    policies.iter().map(|policy| policy.id.as_str()).collect()
+}
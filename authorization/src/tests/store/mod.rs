@@ -0,0 +1,81 @@
+#[cfg(feature = "toml")]
+mod file;
+mod snapshot;
+
+use crate::{
+    store::{conformance, Error},
+    MemoryStore, Policy, PolicyStore, Principal, Resource,
+};
+
+#[test]
+fn memory_store_should_conform() {
+    conformance::run(MemoryStore::new);
+}
+
+#[test]
+fn insert_should_return_conflict_when_id_exists() {
+    // Arrange
+    let mut store = given_store();
+
+    // Act
+    let result = store.insert(Policy::allow("player"));
+
+    // Assert
+    assert_eq!(result, Err(Error::Conflict("player".to_string())));
+}
+
+#[test]
+fn update_should_return_not_found_when_missing() {
+    // Arrange
+    let mut store = given_store();
+
+    // Act
+    let result = store.update(Policy::allow("missing"));
+
+    // Assert
+    assert_eq!(result, Err(Error::NotFound("missing".to_string())));
+}
+
+#[test]
+fn query_by_principal_should_return_policy_once_when_many_principals_match() {
+    // Arrange
+    let store = given_store();
+
+    // Act
+    let result = store
+        .query_by_principal(&"world:player:1".parse::<Principal>().unwrap())
+        .unwrap();
+
+    // Assert
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, "player");
+}
+
+#[test]
+fn from_iter_should_keep_last_policy_of_id() {
+    // Arrange
+    let policies = [
+        Policy::allow("loot").resource("world:loot:1".parse::<Resource>().unwrap()),
+        Policy::allow("loot").resource("world:loot:2".parse::<Resource>().unwrap()),
+    ];
+
+    // Act
+    let store = policies.into_iter().collect::<MemoryStore>();
+
+    // Assert
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.revision(), Ok(0));
+    assert!(store
+        .query_by_resource(&"world:loot:1".parse::<Resource>().unwrap())
+        .unwrap()
+        .is_empty());
+}
+
+fn given_store() -> MemoryStore {
+    [Policy::allow("player")
+        .principal("world:player:*".parse::<Principal>().unwrap())
+        .principal("*:player:1".parse::<Principal>().unwrap())
+        .principal("world:player:1".parse::<Principal>().unwrap())]
+    .into_iter()
+    .collect()
+}
@@ -0,0 +1,160 @@
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    store::{conformance, Error, Snapshot},
+    Action, AsyncPolicyStore, MemoryStore, Policy, PolicyStore, Principal, Resource,
+};
+
+#[test]
+fn async_store_should_conform() {
+    block_on(conformance::run_async(|| async { Yielding::default() }));
+}
+
+#[test]
+fn load_should_cache_policies_of_store() {
+    // Arrange
+    let store = given_store();
+
+    // Act
+    let snapshot = block_on(Snapshot::load(store)).unwrap();
+
+    // Assert
+    let result = PolicyStore::query_by_principal(&snapshot, &given_principal()).unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, "player");
+}
+
+#[test]
+fn refresh_should_reload_when_store_changed() {
+    // Arrange
+    let mut snapshot = block_on(Snapshot::load(given_store())).unwrap();
+    block_on(snapshot.store_mut().delete("player")).unwrap();
+    let before = PolicyStore::list(&snapshot).unwrap();
+
+    // Act
+    let result = block_on(snapshot.refresh()).unwrap();
+
+    // Assert
+    assert!(result);
+    assert_eq!(before.len(), 1);
+    assert!(PolicyStore::list(&snapshot).unwrap().is_empty());
+}
+
+#[test]
+fn refresh_should_return_false_when_store_unchanged() {
+    // Arrange
+    let mut snapshot = block_on(Snapshot::load(given_store())).unwrap();
+
+    // Act
+    let result = block_on(snapshot.refresh()).unwrap();
+
+    // Assert
+    assert!(!result);
+}
+
+#[test]
+fn insert_should_return_read_only() {
+    // Arrange
+    let mut snapshot = block_on(Snapshot::load(given_store())).unwrap();
+
+    // Act
+    let result = PolicyStore::insert(&mut snapshot, Policy::allow("monster"));
+
+    // Assert
+    assert_eq!(result, Err(Error::ReadOnly));
+}
+
+/// Async store which suspends once before each call completes.
+#[derive(Default)]
+struct Yielding(MemoryStore);
+
+impl AsyncPolicyStore for Yielding {
+    type Error = Error;
+
+    async fn get(&self, id: &str) -> Result<Option<Policy>, Error> {
+        suspend().await;
+        PolicyStore::get(&self.0, id)
+    }
+
+    async fn insert(&mut self, policy: Policy) -> Result<(), Error> {
+        suspend().await;
+        PolicyStore::insert(&mut self.0, policy)
+    }
+
+    async fn update(&mut self, policy: Policy) -> Result<(), Error> {
+        suspend().await;
+        PolicyStore::update(&mut self.0, policy)
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<Option<Policy>, Error> {
+        suspend().await;
+        PolicyStore::delete(&mut self.0, id)
+    }
+
+    async fn list(&self) -> Result<Vec<Policy>, Error> {
+        suspend().await;
+        PolicyStore::list(&self.0)
+    }
+
+    async fn query_by_action(&self, action: &Action) -> Result<Vec<Policy>, Error> {
+        suspend().await;
+        PolicyStore::query_by_action(&self.0, action)
+    }
+
+    async fn query_by_principal(&self, principal: &Principal) -> Result<Vec<Policy>, Error> {
+        suspend().await;
+        PolicyStore::query_by_principal(&self.0, principal)
+    }
+
+    async fn query_by_resource(&self, resource: &Resource) -> Result<Vec<Policy>, Error> {
+        suspend().await;
+        PolicyStore::query_by_resource(&self.0, resource)
+    }
+
+    async fn revision(&self) -> Result<u64, Error> {
+        suspend().await;
+        PolicyStore::revision(&self.0)
+    }
+}
+
+/// Returns pending once, as a store waiting on I/O would.
+async fn suspend() {
+    let mut suspended = false;
+    core::future::poll_fn(|_| {
+        if suspended {
+            Poll::Ready(())
+        } else {
+            suspended = true;
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Polls a future until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn given_principal() -> Principal {
+    "world:player:1".parse().unwrap()
+}
+
+fn given_store() -> Yielding {
+    Yielding(
+        [Policy::allow("player").principal("world:player:*".parse::<Principal>().unwrap())]
+            .into_iter()
+            .collect(),
+    )
+}
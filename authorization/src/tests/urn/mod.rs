@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{urn::Error, Action, Policy, Principal, Resource};
+
+#[test]
+fn should_parse_action() {
+    // Arrange
+    let source = "identity:user:get";
+
+    // Act
+    let result = source.parse::<Action>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        Action {
+            noun: "user".to_string(),
+            scope: "identity".to_string(),
+            verb: "get".to_string(),
+        }
+    );
+}
+
+#[test]
+fn should_parse_principal_with_wildcards() {
+    // Arrange
+    let source = "world:*:*";
+
+    // Act
+    let result = source.parse::<Principal>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        Principal {
+            attributes: HashMap::new(),
+            id: "*".to_string(),
+            noun: "*".to_string(),
+            scope: "world".to_string(),
+        }
+    );
+}
+
+#[test]
+fn should_round_trip_escaped_segments() {
+    // Arrange
+    let resource = Resource {
+        attributes: HashMap::new(),
+        id: r"urn:example\1".to_string(),
+        noun: "user".to_string(),
+        scope: "local".to_string(),
+    };
+
+    // Act
+    let display = resource.to_string();
+    let result = display.parse::<Resource>();
+
+    // Assert
+    assert_eq!(display, r"local:user:urn\:example\\1");
+    assert_eq!(result.unwrap(), resource);
+}
+
+#[test]
+fn should_return_error_when_segments_are_missing() {
+    // Arrange
+    let source = "local:user";
+
+    // Act
+    let result = source.parse::<Principal>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        Error {
+            message: "expected `scope:noun:id`, found `local:user`".to_string(),
+        }
+    );
+}
+
+#[test]
+fn should_return_error_when_escape_is_invalid() {
+    // Arrange
+    let source = r"local:user:\1";
+
+    // Act
+    let result = source.parse::<Principal>();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        r"invalid escape sequence in `local:user:\1`"
+    );
+}
+
+#[test]
+fn should_deserialize_string_and_object_forms() {
+    // Arrange
+    let json = r#"{
+        "actions": ["identity:user:get", { "noun": "user", "scope": "identity", "verb": "list" }],
+        "conditions": [],
+        "effect": "allow",
+        "id": "read",
+        "principals": ["*:*:*"],
+        "resources": [{ "id": "*", "noun": "user", "scope": "identity" }]
+    }"#;
+
+    // Act
+    let result = serde_json::from_str::<Policy>(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result.actions,
+        vec![
+            "identity:user:get".parse().unwrap(),
+            "identity:user:list".parse().unwrap()
+        ]
+    );
+    assert_eq!(result.principals, vec!["*:*:*".parse().unwrap()]);
+    assert_eq!(result.resources, vec!["identity:user:*".parse().unwrap()]);
+}
+
+#[test]
+fn should_return_error_when_deserializing_invalid_string_form() {
+    // Arrange
+    let json = r#""identity:user""#;
+
+    // Act
+    let result = serde_json::from_str::<Action>(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("expected `scope:noun:verb`, found `identity:user`"));
+}
+
+#[test]
+fn should_serialize_string_form_when_opted_in() {
+    // Arrange
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Subject {
+        #[serde(with = "crate::urn")]
+        principal: Principal,
+    }
+    let subject = Subject {
+        principal: "local:user:1".parse().unwrap(),
+    };
+
+    // Act
+    let json = serde_json::to_string(&subject).unwrap();
+    let result = serde_json::from_str::<Subject>(&json).unwrap();
+
+    // Assert
+    assert_eq!(json, r#"{"principal":"local:user:1"}"#);
+    assert_eq!(result, subject);
+}
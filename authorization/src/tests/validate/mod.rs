@@ -0,0 +1,287 @@
+use serde_json::json;
+
+use crate::{schema::Error, Condition, Context, Effect, Metadata, Policy, PolicySet, Schema};
+
+#[test]
+fn should_accept_policies_that_conform() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy(
+        "world:*:attack",
+        "world:player:*",
+        r#"data["player:level"] >= 10"#,
+    )];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn should_return_errors_with_suggestions_when_policies_have_typos() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy(
+        "world:mosnter:attack",
+        "wrold:player:*",
+        r#"data["player:levle"] >= 10"#,
+    )];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        vec![
+            Error {
+                message: "unknown noun `mosnter`, did you mean `monster`?".to_string(),
+                path: "policy-1.actions[0].noun".to_string(),
+            },
+            Error {
+                message: "unknown scope `wrold`, did you mean `world`?".to_string(),
+                path: "policy-1.principals[0].scope".to_string(),
+            },
+            Error {
+                message: "unknown data key `player:levle`, did you mean `player:level`?"
+                    .to_string(),
+                path: "policy-1.conditions[0]".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_return_error_when_verb_is_unknown_for_any_noun() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy("world:*:atack", "world:player:*", "true")];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err()[0].to_string(),
+        "policy-1.actions[0].verb: unknown verb `atack`, did you mean `attack`?"
+    );
+}
+
+#[test]
+fn should_return_errors_when_context_does_not_conform() {
+    // Arrange
+    let schema = given_schema();
+    let context = given_context("world:monster:take", &json!({ "player:level": "ten" }));
+
+    // Act
+    let result = schema.validate_context(&context);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        vec![
+            Error {
+                message: "unknown verb `take`".to_string(),
+                path: "action.verb".to_string(),
+            },
+            Error {
+                message: "expected int".to_string(),
+                path: "data[\"player:level\"]".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_accept_context_with_string_set_data() {
+    // Arrange
+    let schema = given_schema();
+    let context = given_context("world:monster:attack", &json!({ "player:level": ["10"] }));
+
+    // Act
+    let result = schema.validate_context(&context);
+
+    // Assert
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn should_return_errors_when_set_is_compiled_with_schema() {
+    // Arrange
+    let policies = vec![given_policy("world:*:atack", "world:player:*", "true")];
+
+    // Act
+    let result = PolicySet::with_schema(policies, given_schema());
+
+    // Assert
+    assert_eq!(result.unwrap_err().len(), 1);
+}
+
+#[test]
+fn evaluate_checked_should_return_errors_when_context_does_not_conform() {
+    // Arrange
+    let policies = vec![given_policy("world:*:attack", "world:player:*", "true")];
+    let set = PolicySet::with_schema(policies, given_schema()).unwrap();
+    let context = given_context("world:monstr:attack", &json!({}));
+
+    // Act
+    let result = set.evaluate_checked(&context);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err()[0].to_string(),
+        "action.noun: unknown noun `monstr`, did you mean `monster`?"
+    );
+}
+
+#[test]
+fn evaluate_checked_should_evaluate_when_context_conforms() {
+    // Arrange
+    let policies = vec![given_policy("world:*:attack", "world:player:*", "true")];
+    let set = PolicySet::with_schema(policies, given_schema()).unwrap();
+    let context = given_context("world:monster:attack", &json!({}));
+
+    // Act
+    let result = set.evaluate_checked(&context);
+
+    // Assert
+    assert_eq!(
+        result.unwrap().map(|policy| policy.id.as_str()),
+        Some("policy-1")
+    );
+}
+
+#[test]
+fn evaluate_should_not_validate_context() {
+    // Arrange
+    let policies = vec![given_policy("world:*:*", "world:player:*", "true")];
+    let set = PolicySet::with_schema(policies, given_schema()).unwrap();
+    let context = given_context("world:monstr:attack", &json!({}));
+
+    // Act
+    let result = set.evaluate(&context);
+
+    // Assert
+    assert!(result.is_some());
+}
+
+#[test]
+fn validate_should_accept_any_context_without_schema() {
+    // Arrange
+    let set = PolicySet::new(Vec::new());
+    let context = given_context("world:monstr:attack", &json!({ "player:level": "ten" }));
+
+    // Act
+    let result = set.validate(&context);
+
+    // Assert
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn should_return_errors_when_data_is_compared_with_literal_of_another_type() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy(
+        "world:*:attack",
+        "world:player:*",
+        r#"data["player:level"] == true || ip("127.0.0.1") != data["player:level"]"#,
+    )];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        vec![
+            Error {
+                message: "`data[\"player:level\"]` is int, compared with bool".to_string(),
+                path: "policy-1.conditions[0]".to_string(),
+            },
+            Error {
+                message: "`data[\"player:level\"]` is int, compared with ip".to_string(),
+                path: "policy-1.conditions[0]".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_return_error_when_data_of_another_type_is_tested_as_bool() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy(
+        "world:*:attack",
+        "world:player:*",
+        r#"!data["player:level"]"#,
+    )];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err()[0].message,
+        "`data[\"player:level\"]` is int, tested as bool"
+    );
+}
+
+#[test]
+fn should_accept_data_compared_with_string_or_decimal() {
+    // Arrange
+    let schema = given_schema();
+    let policies = [given_policy(
+        "world:*:attack",
+        "world:player:*",
+        r#"data["player:level"] == "10" || data["player:level"] < decimal("2.5")"#,
+    )];
+
+    // Act
+    let result = schema.validate_policies(&policies);
+
+    // Assert
+    assert_eq!(result, Ok(()));
+}
+
+fn given_context(action: &str, data: &serde_json::Value) -> Context {
+    serde_json::from_value(json!({
+        "action": action,
+        "data": data,
+        "principal": "world:player:1",
+        "resource": "world:monster:2",
+    }))
+    .unwrap()
+}
+
+fn given_policy(action: &str, principal: &str, expression: &str) -> Policy {
+    Policy {
+        actions: vec![action.parse().unwrap()],
+        conditions: vec![Condition {
+            expression: Some(expression.parse().unwrap()),
+            ..Default::default()
+        }],
+        effect: Effect::Allow,
+        id: "policy-1".to_string(),
+        metadata: Metadata::default(),
+        principals: vec![principal.parse().unwrap()],
+        resources: vec!["world:monster:*".parse().unwrap()],
+    }
+}
+
+fn given_schema() -> Schema {
+    serde_json::from_value(json!({
+        "data": { "player:level": "int" },
+        "scopes": {
+            "world": {
+                "nouns": {
+                    "monster": { "verbs": ["attack"] },
+                    "player": { "verbs": ["attack"] }
+                }
+            }
+        }
+    }))
+    .unwrap()
+}
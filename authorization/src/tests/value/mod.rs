@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::json;
+
+use crate::{
+    value::{Decimal, Timestamp},
+    Context, Expression, Value,
+};
+
+#[test]
+fn should_deserialize_typed_values() {
+    // Arrange
+    let json = json!({
+        "city": ["atlantis", "olympus"],
+        "client_ip": { "$ip": "127.0.0.1" },
+        "level": 10,
+        "player": { "guild": "red" },
+        "score": 1.5,
+        "time": { "$timestamp": "1970-01-01T00:00:01Z" },
+    });
+
+    // Act
+    let result = serde_json::from_value::<HashMap<String, Value>>(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        HashMap::from([
+            (
+                "city".to_string(),
+                Value::Set([Value::from("atlantis"), Value::from("olympus")].into()),
+            ),
+            (
+                "client_ip".to_string(),
+                Value::Ip("127.0.0.1".parse().unwrap())
+            ),
+            ("level".to_string(), Value::Int(10)),
+            (
+                "player".to_string(),
+                Value::Record(BTreeMap::from([("guild".to_string(), Value::from("red"))])),
+            ),
+            (
+                "score".to_string(),
+                Value::Decimal(Decimal::from_units(15_000))
+            ),
+            (
+                "time".to_string(),
+                Value::Timestamp(Timestamp::from_millis(1_000).unwrap())
+            ),
+        ])
+    );
+}
+
+#[test]
+fn should_round_trip_typed_values() {
+    // Arrange
+    let value = Value::Record(BTreeMap::from([
+        (
+            "decimal".to_string(),
+            Value::Decimal("-0.25".parse().unwrap()),
+        ),
+        ("ip".to_string(), Value::Ip("::1".parse().unwrap())),
+        (
+            "timestamp".to_string(),
+            Value::Timestamp("2023-11-01T12:00:00.5+01:00".parse().unwrap()),
+        ),
+    ]));
+
+    // Act
+    let json = serde_json::to_value(&value).unwrap();
+    let result = serde_json::from_value::<Value>(json.clone()).unwrap();
+
+    // Assert
+    assert_eq!(
+        json,
+        json!({
+            "decimal": { "$decimal": "-0.25" },
+            "ip": { "$ip": "::1" },
+            "timestamp": { "$timestamp": "2023-11-01T11:00:00.500Z" },
+        })
+    );
+    assert_eq!(result, value);
+}
+
+#[test]
+fn should_round_numbers_to_nearest_decimal() {
+    // Arrange
+    let json = json!([19.999_999, 0.300_000_000_000_000_04, 1e-7, -2.000_06]);
+
+    // Act
+    let result = serde_json::from_value::<Vec<Value>>(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        [200_000, 3_000, 0, -20_001].map(|units| Value::Decimal(Decimal::from_units(units)))
+    );
+}
+
+#[test]
+fn should_reject_numbers_out_of_range() {
+    // Arrange
+    let json = json!(1.5e300);
+
+    // Act
+    let result = serde_json::from_value::<Value>(json);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "number `1.5e300` is out of range"
+    );
+}
+
+#[test]
+fn should_reject_invalid_typed_values() {
+    // Arrange
+    let instances = [
+        json!({ "$decimal": "1.23456" }),
+        json!({ "$ip": "localhost" }),
+        json!({ "$ip": "127.0.0.1", "extra": true }),
+        json!({ "$timestamp": "2023-02-29T00:00:00Z" }),
+        json!({ "$unknown": "" }),
+        json!(null),
+    ];
+
+    // Act
+    let results = instances.map(serde_json::from_value::<Value>);
+
+    // Assert
+    assert!(results.iter().all(Result::is_err), "{results:?}");
+}
+
+#[test]
+fn should_convert_integers() {
+    // Arrange
+    let wide = (u64::MAX, usize::MAX, i128::MIN);
+
+    // Act
+    let narrow = [
+        Value::from(u8::MAX),
+        Value::from(u16::MAX),
+        Value::from(u32::MAX),
+        Value::from(i8::MIN),
+        Value::from(i16::MIN),
+        Value::from(i32::MIN),
+    ];
+    let fitting = [
+        Value::try_from(1_u64),
+        Value::try_from(1_usize),
+        Value::try_from(1_i128),
+    ];
+    let overflowing = [
+        Value::try_from(wide.0),
+        Value::try_from(wide.1),
+        Value::try_from(wide.2),
+    ];
+
+    // Assert
+    assert_eq!(
+        narrow,
+        [
+            Value::Int(255),
+            Value::Int(65_535),
+            Value::Int(4_294_967_295),
+            Value::Int(-128),
+            Value::Int(-32_768),
+            Value::Int(-2_147_483_648),
+        ]
+    );
+    assert!(fitting.iter().all(|value| value == &Ok(Value::Int(1))));
+    assert!(overflowing.iter().all(Result::is_err), "{overflowing:?}");
+}
+
+#[test]
+fn should_compare_typed_values_natively() {
+    // Arrange
+    let context = given_context(json!({
+        "client_ip": { "$ip": "10.0.0.7" },
+        "level": 10,
+        "player": { "guild": "red", "rank": 3 },
+        "score": 1.5,
+        "tags": ["gold", "red"],
+        "time": { "$timestamp": "2023-11-01T12:00:00Z" },
+    }));
+    let expressions = [
+        r#"data["level"] >= 10 && data["level"] < decimal("10.5")"#,
+        r#"data["score"] > 1 && data["score"] == decimal("1.50")"#,
+        r#"data["client_ip"] >= ip("10.0.0.0") && data["client_ip"] <= ip("10.0.0.255")"#,
+        r#"data["time"] > timestamp("2023-11-01T12:30:00+01:00")"#,
+        r#"data["player"]["guild"] == "red" && data["player"]["rank"] > 2"#,
+        r#""gold" in data["tags"] && "rank" in data["player"] && size(data["player"]) == 2"#,
+    ];
+
+    // Act
+    let results = expressions.map(|source| given_expression(source).evaluate(&context));
+
+    // Assert
+    assert_eq!(results, [true; 6]);
+}
+
+#[test]
+fn should_compare_string_sets_as_typed_values() {
+    // Arrange
+    let context = given_context(json!({
+        "client_ip": ["127.0.0.1"],
+        "level": ["10"],
+        "time": ["2023-11-01T12:00:00Z"],
+    }));
+    let expressions = [
+        r#"data["level"] >= 10"#,
+        r#"data["client_ip"] == ip("127.0.0.1")"#,
+        r#"data["time"] < timestamp("2024-01-01T00:00:00Z")"#,
+        r#"10 in data["level"]"#,
+    ];
+
+    // Act
+    let results = expressions.map(|source| given_expression(source).evaluate(&context));
+
+    // Assert
+    assert_eq!(results, [true; 4]);
+}
+
+#[test]
+fn should_return_false_when_types_differ_at_runtime() {
+    // Arrange
+    let context = given_context(json!({
+        "level": "ten",
+        "player": { "guild": "red" },
+    }));
+    let expressions = [
+        r#"data["level"] >= 10"#,
+        r#"data["player"]["rank"] > 2"#,
+        r#"data["level"]["guild"] == "red""#,
+    ];
+
+    // Act
+    let results = expressions.map(|source| given_expression(source).evaluate(&context));
+
+    // Assert
+    assert_eq!(results, [false; 3]);
+}
+
+#[test]
+fn should_reject_invalid_literals() {
+    // Arrange
+    let sources = [
+        r#"data["score"] > decimal("1.5.0")"#,
+        r#"data["client_ip"] == ip("localhost")"#,
+        r#"data["time"] > timestamp("yesterday")"#,
+        r#"ip("127.0.0.1") > 10"#,
+    ];
+
+    // Act
+    let results = sources.map(str::parse::<Expression>);
+
+    // Assert
+    assert!(results.iter().all(Result::is_err), "{results:?}");
+}
+
+#[test]
+fn should_format_decimals_and_timestamps() {
+    // Arrange
+    let decimals = ["2", "-0.5", "1.2500"];
+    let timestamps = ["1970-01-01T00:00:00Z", "1969-12-31T23:59:59.999-00:30"];
+
+    // Act
+    let decimals = decimals.map(|s| s.parse::<Decimal>().unwrap().to_string());
+    let timestamps = timestamps.map(|s| s.parse::<Timestamp>().unwrap().to_string());
+
+    // Assert
+    assert_eq!(decimals, ["2.0", "-0.5", "1.25"]);
+    assert_eq!(
+        timestamps,
+        ["1970-01-01T00:00:00Z", "1970-01-01T00:29:59.999Z"]
+    );
+}
+
+#[test]
+fn should_round_trip_timestamps_at_boundaries() {
+    // Arrange
+    let timestamps = [Timestamp::MIN, Timestamp::MAX];
+
+    // Act
+    let strings = timestamps.map(|timestamp| timestamp.to_string());
+    let results = strings.clone().map(|s| s.parse::<Timestamp>().unwrap());
+
+    // Assert
+    assert_eq!(
+        strings,
+        ["0000-01-01T00:00:00Z", "9999-12-31T23:59:59.999Z"]
+    );
+    assert_eq!(results, timestamps);
+}
+
+#[test]
+fn should_reject_timestamps_out_of_range() {
+    // Arrange
+    let millis = [Timestamp::MIN.millis() - 1, Timestamp::MAX.millis() + 1];
+    let strings = ["0000-01-01T00:00:00+00:01", "9999-12-31T23:59:59.999-00:01"];
+
+    // Act
+    let timestamps = millis.map(Timestamp::from_millis);
+    let results = strings.map(str::parse::<Timestamp>);
+
+    // Assert
+    assert_eq!(timestamps, [None, None]);
+    assert!(results.iter().all(Result::is_err), "{results:?}");
+}
+
+fn given_context(data: serde_json::Value) -> Context {
+    Context {
+        action: "world:monster:attack".parse().unwrap(),
+        data: serde_json::from_value(data).unwrap(),
+        principal: "world:player:1".parse().unwrap(),
+        resource: "world:monster:2".parse().unwrap(),
+    }
+}
+
+fn given_expression(source: &str) -> Expression {
+    source.parse().unwrap()
+}
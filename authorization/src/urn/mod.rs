@@ -0,0 +1,402 @@
+//! URN.
+//!
+//! Compact string syntax for [`Action`], [`Principal`] and [`Resource`].
+//!
+//! ```text
+//! identity:user:get                              # scope:noun:verb
+//! local:user:00000000-0000-0000-0000-000000000000 # scope:noun:id
+//! world:*:*                                      # wildcards
+//! ```
+//!
+//! Within a segment, `:` and `\` are escaped with a preceding `\`.
+//!
+//! Deserializing any of the types accepts either the object form or the string form. Fields can
+//! opt into serializing the string form with `#[serde(with = "authorization::urn")]`.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    fmt::{self, Display, Write},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess},
+    Deserialize, Deserializer, Serializer,
+};
+
+use crate::{collections::Map, Action, Principal, Resource, Value};
+
+/// Error.
+///
+/// Returned when a string is not a valid URN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Message.
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Serializes a value in its string form.
+///
+/// # Errors
+///
+/// Returns the error of the serializer.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserializes a value from its string or object form.
+///
+/// # Errors
+///
+/// Returns an error if the value is neither a valid URN nor a valid object.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Urn,
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(Visitor(PhantomData))
+    } else {
+        T::deserialize_object(deserializer)
+    }
+}
+
+/// Urn.
+///
+/// Implemented by types with a string form.
+pub trait Urn: FromStr<Err = Error> + Sized {
+    /// Description of the string form, used in error messages.
+    const EXPECTING: &'static str;
+
+    /// Deserializes the object form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is invalid.
+    fn deserialize_object<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Visitor of the string or object form.
+struct Visitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for Visitor<T>
+where
+    T: Urn,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a string `{}` or an object", T::EXPECTING)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        T::deserialize_object(MapAccessDeserializer::new(map))
+    }
+}
+
+/// Object form of [`Action`].
+#[derive(Deserialize)]
+#[serde(remote = "Action")]
+struct ActionObject {
+    #[serde(rename = "noun")]
+    noun: String,
+
+    #[serde(rename = "scope")]
+    scope: String,
+
+    #[serde(rename = "verb")]
+    verb: String,
+}
+
+/// Object form of [`Principal`].
+#[derive(Deserialize)]
+#[serde(remote = "Principal")]
+struct PrincipalObject {
+    #[serde(default, rename = "attributes")]
+    attributes: Map<String, Value>,
+
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "noun")]
+    noun: String,
+
+    #[serde(rename = "scope")]
+    scope: String,
+}
+
+/// Object form of [`Resource`].
+#[derive(Deserialize)]
+#[serde(remote = "Resource")]
+struct ResourceObject {
+    #[serde(default, rename = "attributes")]
+    attributes: Map<String, Value>,
+
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "noun")]
+    noun: String,
+
+    #[serde(rename = "scope")]
+    scope: String,
+}
+
+impl Urn for Action {
+    const EXPECTING: &'static str = "scope:noun:verb";
+
+    fn deserialize_object<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ActionObject::deserialize(deserializer)
+    }
+}
+
+impl Urn for Principal {
+    const EXPECTING: &'static str = "scope:noun:id";
+
+    fn deserialize_object<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        PrincipalObject::deserialize(deserializer)
+    }
+}
+
+impl Urn for Resource {
+    const EXPECTING: &'static str = "scope:noun:id";
+
+    fn deserialize_object<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ResourceObject::deserialize(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Principal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Action {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Action".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        json_schema("Action.", Self::EXPECTING, ["noun", "scope", "verb"], false)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Principal {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Principal".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        json_schema("Principal.", Self::EXPECTING, ["id", "noun", "scope"], true)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Resource {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Resource".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        json_schema("Resource.", Self::EXPECTING, ["id", "noun", "scope"], true)
+    }
+}
+
+/// Returns the schema of the string or object form.
+#[cfg(feature = "schemars")]
+fn json_schema(
+    description: &str,
+    expecting: &str,
+    fields: [&str; 3],
+    attributes: bool,
+) -> schemars::Schema {
+    let mut object = schemars::json_schema!({
+        "properties": {
+            "attributes": { "type": "object" },
+            (fields[0]): { "type": "string" },
+            (fields[1]): { "type": "string" },
+            (fields[2]): { "type": "string" },
+        },
+        "required": fields,
+        "type": "object",
+    });
+    if !attributes {
+        if let Some(properties) = object.get_mut("properties") {
+            if let Some(properties) = properties.as_object_mut() {
+                properties.remove("attributes");
+            }
+        }
+    }
+
+    schemars::json_schema!({
+        "description": description,
+        "oneOf": [
+            {
+                "description": expecting,
+                "pattern": PATTERN,
+                "type": "string",
+            },
+            object,
+        ],
+    })
+}
+
+/// Pattern of the string form.
+#[cfg(feature = "schemars")]
+const PATTERN: &str = r"^(?:[^:\\]|\\[:\\])*:(?:[^:\\]|\\[:\\])*:(?:[^:\\]|\\[:\\])*$";
+
+impl Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        join(f, [&self.scope, &self.noun, &self.verb])
+    }
+}
+
+impl Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        join(f, [&self.scope, &self.noun, &self.id])
+    }
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        join(f, [&self.scope, &self.noun, &self.id])
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [scope, noun, verb] = split(s, Self::EXPECTING)?;
+        Ok(Action { noun, scope, verb })
+    }
+}
+
+impl FromStr for Principal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [scope, noun, id] = split(s, Self::EXPECTING)?;
+        Ok(Principal {
+            attributes: Map::new(),
+            id,
+            noun,
+            scope,
+        })
+    }
+}
+
+impl FromStr for Resource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [scope, noun, id] = split(s, Self::EXPECTING)?;
+        Ok(Resource {
+            attributes: Map::new(),
+            id,
+            noun,
+            scope,
+        })
+    }
+}
+
+/// Writes escaped segments separated by `:`.
+fn join(f: &mut fmt::Formatter<'_>, segments: [&str; 3]) -> fmt::Result {
+    for (index, segment) in segments.into_iter().enumerate() {
+        if index > 0 {
+            f.write_char(':')?;
+        }
+        for char in segment.chars() {
+            if matches!(char, ':' | '\\') {
+                f.write_char('\\')?;
+            }
+            f.write_char(char)?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a string into three unescaped segments.
+fn split(s: &str, expecting: &str) -> Result<[String; 3], Error> {
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut chars = s.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            ':' => segments.push(core::mem::take(&mut segment)),
+            '\\' => match chars.next() {
+                Some(char @ (':' | '\\')) => segment.push(char),
+                _ => {
+                    return Err(Error {
+                        message: format!("invalid escape sequence in `{s}`"),
+                    })
+                }
+            },
+            char => segment.push(char),
+        }
+    }
+    segments.push(segment);
+
+    <[String; 3]>::try_from(segments).map_err(|_| Error {
+        message: format!("expected `{expecting}`, found `{s}`"),
+    })
+}
@@ -0,0 +1,753 @@
+//! Value.
+//!
+//! Typed values of [`Context::data`](crate::Context::data).
+//!
+//! In JSON, booleans, integers, strings, arrays and objects are read as [`Value::Bool`],
+//! [`Value::Int`], [`Value::String`], [`Value::Set`] and [`Value::Record`], and other numbers as
+//! [`Value::Decimal`], rounded to four fractional digits. Decimals, IP addresses and timestamps are
+//! written as an object with a single `$decimal`, `$ip` or `$timestamp` key.
+//!
+//! ```json
+//! {
+//!   "geography:city": ["atlantis", "olympus"],
+//!   "player:level": 10,
+//!   "request:client_ip": { "$ip": "127.0.0.1" },
+//!   "request:time": { "$timestamp": "2023-11-01T12:00:00Z" }
+//! }
+//! ```
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+};
+use core::{
+    fmt::{self, Display, Write},
+    net::IpAddr,
+    str::FromStr,
+};
+
+use serde::{
+    de::{self, MapAccess, SeqAccess},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::collections::Set;
+
+/// Value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Value {
+    /// Bool.
+    Bool(bool),
+
+    /// Decimal.
+    Decimal(Decimal),
+
+    /// Int.
+    Int(i64),
+
+    /// IP address.
+    Ip(IpAddr),
+
+    /// Record.
+    Record(BTreeMap<String, Value>),
+
+    /// Set.
+    Set(BTreeSet<Value>),
+
+    /// String.
+    String(String),
+
+    /// Timestamp.
+    Timestamp(Timestamp),
+}
+
+impl Value {
+    /// Returns true if the value, in its string form, is one of the criteria.
+    ///
+    /// Records and sets have no string form.
+    pub(crate) fn is_in(&self, criteria: &Set<String>) -> bool {
+        match self {
+            Value::Bool(value) => criteria.contains(&value.to_string()),
+            Value::Decimal(value) => criteria.contains(&value.to_string()),
+            Value::Int(value) => criteria.contains(&value.to_string()),
+            Value::Ip(value) => criteria.contains(&value.to_string()),
+            Value::Record(_) | Value::Set(_) => false,
+            Value::String(value) => criteria.contains(value),
+            Value::Timestamp(value) => criteria.contains(&value.to_string()),
+        }
+    }
+
+    /// Returns true if the value, in its string form, equals a string.
+    ///
+    /// Records and sets have no string form.
+    pub(crate) fn is_str(&self, other: &str) -> bool {
+        match self {
+            Value::Bool(value) => value.to_string() == other,
+            Value::Decimal(value) => value.to_string() == other,
+            Value::Int(value) => value.to_string() == other,
+            Value::Ip(value) => value.to_string() == other,
+            Value::Record(_) | Value::Set(_) => false,
+            Value::String(value) => value == other,
+            Value::Timestamp(value) => value.to_string() == other,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(value: Decimal) -> Self {
+        Value::Decimal(value)
+    }
+}
+
+impl From<i8> for Value {
+    fn from(value: i8) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<i16> for Value {
+    fn from(value: i16) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<IpAddr> for Value {
+    fn from(value: IpAddr) -> Self {
+        Value::Ip(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<Timestamp> for Value {
+    fn from(value: Timestamp) -> Self {
+        Value::Timestamp(value)
+    }
+}
+
+impl From<u8> for Value {
+    fn from(value: u8) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<u16> for Value {
+    fn from(value: u16) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl TryFrom<i128> for Value {
+    type Error = Error;
+
+    fn try_from(value: i128) -> Result<Self, Self::Error> {
+        i64::try_from(value).map(Value::Int).map_err(|_| Error {
+            message: format!("integer `{value}` is out of range"),
+        })
+    }
+}
+
+impl TryFrom<u64> for Value {
+    type Error = Error;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        i64::try_from(value).map(Value::Int).map_err(|_| Error {
+            message: format!("integer `{value}` is out of range"),
+        })
+    }
+}
+
+impl TryFrom<usize> for Value {
+    type Error = Error;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        i64::try_from(value).map(Value::Int).map_err(|_| Error {
+            message: format!("integer `{value}` is out of range"),
+        })
+    }
+}
+
+/// Converts a set of strings, the untyped form of a value.
+#[allow(clippy::implicit_hasher)]
+impl From<Set<String>> for Value {
+    fn from(value: Set<String>) -> Self {
+        Value::Set(value.into_iter().map(Value::String).collect())
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Visitor)
+        } else {
+            Compact::deserialize(deserializer)?
+                .try_into()
+                .map_err(de::Error::custom)
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fn tagged<S: Serializer>(
+            serializer: S,
+            tag: &str,
+            value: &dyn Display,
+        ) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(tag, &format!("{value}"))?;
+            map.end()
+        }
+
+        if !serializer.is_human_readable() {
+            return CompactRef::from(self).serialize(serializer);
+        }
+
+        match self {
+            Value::Bool(value) => value.serialize(serializer),
+            Value::Decimal(value) => tagged(serializer, "$decimal", value),
+            Value::Int(value) => value.serialize(serializer),
+            Value::Ip(value) => tagged(serializer, "$ip", value),
+            Value::Record(value) => value.serialize(serializer),
+            Value::Set(value) => value.serialize(serializer),
+            Value::String(value) => value.serialize(serializer),
+            Value::Timestamp(value) => tagged(serializer, "$timestamp", value),
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Value {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Value".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Value.",
+            "type": ["array", "boolean", "integer", "number", "object", "string"],
+        })
+    }
+}
+
+/// Compact form of a [`Value`], used by formats that are not human-readable.
+#[derive(Deserialize)]
+enum Compact {
+    Bool(bool),
+    Decimal(i64),
+    Int(i64),
+    Ip(IpAddr),
+    Record(BTreeMap<String, Value>),
+    Set(BTreeSet<Value>),
+    String(String),
+    Timestamp(i64),
+}
+
+impl TryFrom<Compact> for Value {
+    type Error = Error;
+
+    fn try_from(compact: Compact) -> Result<Self, Self::Error> {
+        Ok(match compact {
+            Compact::Bool(value) => Value::Bool(value),
+            Compact::Decimal(units) => Value::Decimal(Decimal(units)),
+            Compact::Int(value) => Value::Int(value),
+            Compact::Ip(value) => Value::Ip(value),
+            Compact::Record(value) => Value::Record(value),
+            Compact::Set(value) => Value::Set(value),
+            Compact::String(value) => Value::String(value),
+            Compact::Timestamp(millis) => {
+                Value::Timestamp(Timestamp::from_millis(millis).ok_or_else(|| Error {
+                    message: format!("timestamp `{millis}` is out of range"),
+                })?)
+            }
+        })
+    }
+}
+
+/// Borrowed [`Compact`].
+#[derive(Serialize)]
+enum CompactRef<'a> {
+    Bool(bool),
+    Decimal(i64),
+    Int(i64),
+    Ip(IpAddr),
+    Record(&'a BTreeMap<String, Value>),
+    Set(&'a BTreeSet<Value>),
+    String(&'a str),
+    Timestamp(i64),
+}
+
+impl<'a> From<&'a Value> for CompactRef<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Bool(value) => CompactRef::Bool(*value),
+            Value::Decimal(value) => CompactRef::Decimal(value.0),
+            Value::Int(value) => CompactRef::Int(*value),
+            Value::Ip(value) => CompactRef::Ip(*value),
+            Value::Record(value) => CompactRef::Record(value),
+            Value::Set(value) => CompactRef::Set(value),
+            Value::String(value) => CompactRef::String(value),
+            Value::Timestamp(value) => CompactRef::Timestamp(value.0),
+        }
+    }
+}
+
+/// Visitor of a [`Value`].
+struct Visitor;
+
+impl<'de> de::Visitor<'de> for Visitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a bool, number, string, array or object")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        i64::try_from(v)
+            .map(Value::Int)
+            .map_err(|_| E::custom(format!("integer `{v}` is out of range")))
+    }
+
+    /// Rounds to the nearest [`Decimal`], as a number from JSON often cannot be represented
+    /// exactly, such as `0.1 + 0.2`.
+    #[allow(clippy::cast_possible_truncation)]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // The largest number of units, rounded down so the cast never saturates.
+        const MAX: f64 = 9.2e18;
+
+        #[allow(clippy::cast_precision_loss)]
+        let units = v * Decimal::SCALE as f64;
+        if !(-MAX..=MAX).contains(&units) {
+            return Err(E::custom(format!("number `{v:e}` is out of range")));
+        }
+
+        let half = if units < 0.0 { -0.5 } else { 0.5 };
+        Ok(Value::Decimal(Decimal((units + half) as i64)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut set = BTreeSet::new();
+        while let Some(value) = seq.next_element()? {
+            set.insert(value);
+        }
+        Ok(Value::Set(set))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut record = BTreeMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = match key.as_str() {
+                "$decimal" | "$ip" | "$timestamp" if record.is_empty() => {
+                    let value = map.next_value::<String>()?;
+                    let value = match key.as_str() {
+                        "$decimal" => value.parse().map(Value::Decimal),
+                        "$ip" => value.parse().map(Value::Ip).map_err(|_| Error {
+                            message: format!("invalid IP address `{value}`"),
+                        }),
+                        _ => value.parse().map(Value::Timestamp),
+                    }
+                    .map_err(de::Error::custom)?;
+
+                    if map.next_key::<de::IgnoredAny>()?.is_some() {
+                        return Err(de::Error::custom(format!(
+                            "`{key}` must be the only key of its object"
+                        )));
+                    }
+                    return Ok(value);
+                }
+                _ if key.starts_with('$') => {
+                    return Err(de::Error::custom(format!("unknown type `{key}`")));
+                }
+                _ => map.next_value()?,
+            };
+            record.insert(key, value);
+        }
+        Ok(Value::Record(record))
+    }
+}
+
+/// Error.
+///
+/// Returned when a string is not a valid decimal, IP address or timestamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Message.
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Decimal.
+///
+/// A fixed-point number with four fractional digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(i64);
+
+impl Decimal {
+    /// Number of units in one.
+    pub const SCALE: i64 = 10_000;
+
+    /// Creates a decimal from a number of units, where one is [`Decimal::SCALE`] units.
+    #[must_use]
+    pub const fn from_units(units: i64) -> Self {
+        Decimal(units)
+    }
+
+    /// Returns the number of units, where one is [`Decimal::SCALE`] units.
+    #[must_use]
+    pub const fn units(self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_char('-')?;
+        }
+
+        let units = self.0.unsigned_abs();
+        let scale = Self::SCALE.unsigned_abs();
+        write!(f, "{}.", units / scale)?;
+
+        let fraction = format!("{:04}", units % scale);
+        match fraction.trim_end_matches('0') {
+            "" => f.write_char('0'),
+            fraction => f.write_str(fraction),
+        }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error {
+            message: format!("invalid decimal `{s}`"),
+        };
+
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s),
+        };
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        let digits =
+            |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+        if !digits(whole) || !(fraction.is_empty() || digits(fraction) && fraction.len() <= 4) {
+            return Err(error());
+        }
+
+        let whole = whole.parse::<i64>().map_err(|_| error())?;
+        let fraction = format!("{fraction:0<4}")
+            .parse::<i64>()
+            .map_err(|_| error())?;
+        let units = whole
+            .checked_mul(Self::SCALE)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or_else(error)?;
+
+        Ok(Decimal(if negative { -units } else { units }))
+    }
+}
+
+/// Timestamp.
+///
+/// Milliseconds since the Unix epoch, in UTC, between [`Timestamp::MIN`] and [`Timestamp::MAX`] so
+/// that the year always has the four digits of RFC 3339.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Earliest timestamp, `0000-01-01T00:00:00Z`.
+    pub const MIN: Timestamp = Timestamp(-62_167_219_200_000);
+
+    /// Latest timestamp, `9999-12-31T23:59:59.999Z`.
+    pub const MAX: Timestamp = Timestamp(253_402_300_799_999);
+
+    /// Creates a timestamp from milliseconds since the Unix epoch.
+    ///
+    /// Returns `None` when the timestamp is before [`Timestamp::MIN`] or after [`Timestamp::MAX`].
+    #[must_use]
+    pub const fn from_millis(millis: i64) -> Option<Self> {
+        if millis < Self::MIN.0 || millis > Self::MAX.0 {
+            return None;
+        }
+        Some(Timestamp(millis))
+    }
+
+    /// Returns the milliseconds since the Unix epoch.
+    #[must_use]
+    pub const fn millis(self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.0.div_euclid(86_400_000);
+        let millis = self.0.rem_euclid(86_400_000);
+        let (year, month, day) = civil_from_days(days);
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1_000 % 60
+        )?;
+        if millis % 1_000 != 0 {
+            write!(f, ".{:03}", millis % 1_000)?;
+        }
+        f.write_char('Z')
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    /// Deserializes an RFC 3339 timestamp.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Timestamp {
+    /// Serializes an RFC 3339 timestamp in UTC.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Timestamp {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Timestamp".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "RFC 3339 timestamp.",
+            "format": "date-time",
+            "type": "string",
+        })
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = Error;
+
+    /// Parses an RFC 3339 timestamp, such as `2023-11-01T12:00:00.000+01:00`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error {
+            message: format!("invalid timestamp `{s}`"),
+        };
+
+        let bytes = s.as_bytes();
+        let number = |range: core::ops::Range<usize>| -> Result<i64, Error> {
+            let digits = bytes.get(range).ok_or_else(error)?;
+            if !digits.iter().all(u8::is_ascii_digit) {
+                return Err(error());
+            }
+            Ok(digits
+                .iter()
+                .fold(0, |number, digit| number * 10 + i64::from(digit - b'0')))
+        };
+        let separator = |index: usize, separators: &[u8]| {
+            bytes
+                .get(index)
+                .filter(|byte| separators.contains(byte))
+                .ok_or_else(error)
+        };
+
+        let year = number(0..4)?;
+        separator(4, b"-")?;
+        let month = number(5..7)?;
+        separator(7, b"-")?;
+        let day = number(8..10)?;
+        separator(10, b"Tt ")?;
+        let hour = number(11..13)?;
+        separator(13, b":")?;
+        let minute = number(14..16)?;
+        separator(16, b":")?;
+        let second = number(17..19)?;
+
+        let mut index = 19;
+        let mut millis = 0;
+        if bytes.get(index) == Some(&b'.') {
+            let digits = bytes[index + 1..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                return Err(error());
+            }
+            millis =
+                number(index + 1..index + 1 + digits.min(3))? * [100, 10, 1][digits.min(3) - 1];
+            index += 1 + digits;
+        }
+
+        let offset = match bytes.get(index) {
+            Some(b'Z' | b'z') if index + 1 == bytes.len() => 0,
+            Some(sign @ (b'+' | b'-')) if index + 6 == bytes.len() => {
+                separator(index + 3, b":")?;
+                let hours = number(index + 1..index + 3)?;
+                let minutes = number(index + 4..index + 6)?;
+                if hours > 23 || minutes > 59 {
+                    return Err(error());
+                }
+                let offset = hours * 60 + minutes;
+                if *sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return Err(error()),
+        };
+
+        if !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(error());
+        }
+
+        let days = days_from_civil(year, month, day);
+        let seconds = days * 86_400 + hour * 3_600 + (minute - offset) * 60 + second;
+
+        Timestamp::from_millis(seconds * 1_000 + millis).ok_or_else(error)
+    }
+}
+
+/// Returns the number of days in a month.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the days since the Unix epoch of a civil date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the civil date of days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
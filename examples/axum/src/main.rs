@@ -1,10 +1,12 @@
-use std::{
-    collections::{HashMap, HashSet},
-    net::SocketAddr,
-    sync::Arc,
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use authorization::{
+    borrowed::{ActionRef, ResourceRef},
+    evaluate,
+    partial::Filter,
+    partial_evaluate, Action, Actions, AsResource, AsyncPolicyStore, Condition, ContextRef, Effect,
+    MemoryStore, PartialContext, Policy, Principal, Resource, Value,
 };
-
-use authorization::{evaluate, Action, Condition, Context, Effect, Policy, Principal, Resource};
 use axum::{
     extract::{ConnectInfo, Path, State},
     http::{header, Request, StatusCode},
@@ -18,66 +20,26 @@ use tower::ServiceBuilder;
 #[tokio::main]
 async fn main() {
     let database = Database {
-        policies: Arc::new(Mutex::new(vec![
-            Policy {
-                actions: vec![
-                    Action {
-                        noun: "user".to_string(),
-                        scope: "identity".to_string(),
-                        verb: "get".to_string(),
-                    },
-                    Action {
-                        noun: "user".to_string(),
-                        scope: "identity".to_string(),
-                        verb: "list".to_string(),
-                    },
-                ],
-                conditions: vec![],
-                effect: Effect::Allow,
-                id: "read".to_string(),
-                principals: vec![Principal {
-                    id: "*".to_string(),
-                    noun: "*".to_string(),
-                    scope: "*".to_string(),
-                }],
-                resources: vec![Resource {
-                    id: "*".to_string(),
-                    noun: "user".to_string(),
-                    scope: "identity".to_string(),
-                }],
-            },
-            Policy {
-                actions: vec![Action {
-                    noun: "*".to_string(),
-                    scope: "*".to_string(),
-                    verb: "*".to_string(),
-                }],
-                conditions: vec![Condition {
-                    string_equals: Some(HashMap::from([
-                        (
-                            "request:client_ip".to_string(),
-                            HashSet::from(["127.0.0.1".to_string()]),
-                        ),
-                        (
-                            "request:host".to_string(),
-                            HashSet::from(["localhost:3000".to_string()]),
-                        ),
-                    ])),
-                }],
-                effect: Effect::Allow,
-                id: "admin".to_string(),
-                principals: vec![Principal {
-                    id: "00000000-0000-0000-0000-000000000000".to_string(),
-                    noun: "user".to_string(),
-                    scope: "local".to_string(),
-                }],
-                resources: vec![Resource {
-                    id: "*".to_string(),
-                    noun: "*".to_string(),
-                    scope: "*".to_string(),
-                }],
-            },
-        ])),
+        policies: Arc::new(Mutex::new(MemoryStore::from_iter([
+            Policy::allow("read")
+                .action(UserAction::Get)
+                .action(UserAction::List)
+                .principal("*:*:*".parse::<Principal>().unwrap())
+                .resource("identity:user:*".parse::<Resource>().unwrap()),
+            Policy::allow("admin")
+                .action("*:*:*".parse::<Action>().unwrap())
+                .principal(
+                    "local:user:00000000-0000-0000-0000-000000000000"
+                        .parse::<Principal>()
+                        .unwrap(),
+                )
+                .resource("*:*:*".parse::<Resource>().unwrap())
+                .when(
+                    Condition::new()
+                        .string_equals("request:client_ip", ["127.0.0.1"])
+                        .string_equals("request:host", ["localhost:3000"]),
+                ),
+        ]))),
     };
 
     let app = Router::new()
@@ -103,36 +65,47 @@ async fn main() {
 
 /// [`Database`].
 ///
-/// Contains a searchable collection of policies.
+/// Contains a searchable collection of policies, which any [`AsyncPolicyStore`] could hold.
 #[derive(Clone)]
 struct Database {
-    policies: Arc<Mutex<Vec<Policy>>>,
+    policies: Arc<Mutex<MemoryStore>>,
 }
 
 impl Database {
     /// Returns all policies which references the [`Principal`].
     async fn find_by_principal(&self, p: &Principal) -> Vec<Policy> {
-        self.policies
-            .lock()
-            .await
-            .iter()
-            .filter(|policy| {
-                policy.principals.iter().any(|f| {
-                    (f.scope == p.scope || f.scope == "*")
-                        && (f.noun == p.noun || f.noun == "*")
-                        && (f.id == p.id || f.id == "*")
-                })
-            })
-            .cloned()
-            .collect()
+        let store = self.policies.lock().await;
+        store.query_by_principal(p).await.unwrap_or_default()
     }
 }
 
+/// [`User`]
+///
+/// A user addressed by the request path.
+#[derive(AsResource)]
+#[authorization(noun = "user", scope = "identity")]
+struct User {
+    #[authorization(id)]
+    id: String,
+}
+
+/// [`UserAction`]
+///
+/// Actions on a user.
+#[derive(Clone, Copy, Actions)]
+#[authorization(noun = "user", scope = "identity")]
+enum UserAction {
+    Delete,
+    Get,
+    List,
+    Put,
+}
+
 /// [`RequestContext`]
 ///
 /// Contains contextual information about the current request.
 #[derive(Clone)]
-struct RequestContext(HashMap<String, HashSet<String>>);
+struct RequestContext(HashMap<String, Value>);
 
 /// Deletes a user.
 async fn handler_users_delete(
@@ -141,20 +114,14 @@ async fn handler_users_delete(
     Extension(principal): Extension<Principal>,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
-    let action = Action {
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-        verb: "delete".to_string(),
-    };
+    let action = UserAction::Delete.action();
 
-    let resource = Resource {
-        id: user_id,
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-    };
+    let policies = database.find_by_principal(&principal).await;
+
+    let resource = User { id: user_id }.as_resource();
 
     if let Some(response) =
-        utility_authorize(&database, &context, &principal, action, resource).await
+        utility_authorize(&policies, &context, &principal, action, (&resource).into())
     {
         return response.into_response();
     }
@@ -169,20 +136,14 @@ async fn handler_users_get(
     Extension(principal): Extension<Principal>,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
-    let action = Action {
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-        verb: "get".to_string(),
-    };
+    let action = UserAction::Get.action();
 
-    let resource = Resource {
-        id: user_id,
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-    };
+    let policies = database.find_by_principal(&principal).await;
+
+    let resource = User { id: user_id }.as_resource();
 
     if let Some(response) =
-        utility_authorize(&database, &context, &principal, action, resource).await
+        utility_authorize(&policies, &context, &principal, action, (&resource).into())
     {
         return response.into_response();
     }
@@ -196,23 +157,21 @@ async fn handler_users_list(
     Extension(context): Extension<RequestContext>,
     Extension(principal): Extension<Principal>,
 ) -> impl IntoResponse {
-    let action = Action {
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-        verb: "list".to_string(),
+    let context = PartialContext {
+        action: UserAction::List.into(),
+        data: context.0.clone(),
+        principal: principal.clone(),
     };
 
-    let resource = Resource {
-        id: "".to_string(),
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-    };
+    let policies = database.find_by_principal(&principal).await;
 
-    if let Some(response) =
-        utility_authorize(&database, &context, &principal, action, resource).await
-    {
-        return response.into_response();
+    println!();
+    let filter = partial_evaluate(&context, &policies);
+    if filter == Filter::Bool(false) {
+        println!("deny: {context:?}");
+        return (StatusCode::FORBIDDEN).into_response();
     }
+    println!("filter: {context:?} {filter:?}");
 
     (StatusCode::OK, Json("Hello, World!")).into_response()
 }
@@ -224,20 +183,14 @@ async fn handler_users_put(
     Extension(principal): Extension<Principal>,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
-    let action = Action {
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-        verb: "put".to_string(),
-    };
+    let action = UserAction::Put.action();
 
-    let resource = Resource {
-        id: user_id,
-        noun: "user".to_string(),
-        scope: "identity".to_string(),
-    };
+    let policies = database.find_by_principal(&principal).await;
+
+    let resource = User { id: user_id }.as_resource();
 
     if let Some(response) =
-        utility_authorize(&database, &context, &principal, action, resource).await
+        utility_authorize(&policies, &context, &principal, action, (&resource).into())
     {
         return response.into_response();
     }
@@ -256,22 +209,8 @@ async fn middleware_authentication_context<B>(
         .and_then(|header| header.to_str().ok())
         .and_then(|header| header.split_once(' '))
         .filter(|(scheme, _)| *scheme == "Example")
-        .map(|(_, value)| value.splitn(3, ':'))
-        .and_then(
-            |mut parts| match (parts.next(), parts.next(), parts.next()) {
-                (Some(scope), Some(noun), Some(id)) => Some(Principal {
-                    id: id.to_string(),
-                    noun: noun.to_string(),
-                    scope: scope.to_string(),
-                }),
-                _ => None,
-            },
-        )
-        .unwrap_or_else(|| Principal {
-            id: "".to_string(),
-            noun: "".to_string(),
-            scope: "anonymous".to_string(),
-        });
+        .and_then(|(_, value)| value.parse::<Principal>().ok())
+        .unwrap_or_else(|| Principal::new("anonymous", "", ""));
 
     req.extensions_mut().insert(principal);
 
@@ -283,10 +222,7 @@ async fn middleware_request_context<B>(mut req: Request<B>, next: Next<B>) -> im
     let mut request_context = HashMap::new();
 
     if let Some(ConnectInfo(socket_addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
-        request_context.insert(
-            "request:client_ip".to_string(),
-            HashSet::from([socket_addr.ip().to_string()]),
-        );
+        request_context.insert("request:client_ip".to_string(), Value::Ip(socket_addr.ip()));
     }
 
     if let Some(value) = req
@@ -294,10 +230,7 @@ async fn middleware_request_context<B>(mut req: Request<B>, next: Next<B>) -> im
         .get(header::HOST)
         .and_then(|value| value.to_str().ok())
     {
-        request_context.insert(
-            "request:host".to_string(),
-            HashSet::from([value.to_string()]),
-        );
+        request_context.insert("request:host".to_string(), Value::from(value));
     }
 
     if let Some(value) = req
@@ -305,10 +238,7 @@ async fn middleware_request_context<B>(mut req: Request<B>, next: Next<B>) -> im
         .get(header::USER_AGENT)
         .and_then(|value| value.to_str().ok())
     {
-        request_context.insert(
-            "request:user_agent".to_string(),
-            HashSet::from([value.to_string()]),
-        );
+        request_context.insert("request:user_agent".to_string(), Value::from(value));
     }
 
     req.extensions_mut().insert(RequestContext(request_context));
@@ -321,24 +251,22 @@ async fn middleware_request_context<B>(mut req: Request<B>, next: Next<B>) -> im
 /// Returns [`IntoResponse`] if the request is not authorized.
 ///
 /// Returns [`None`] if the request is authorized.
-async fn utility_authorize(
-    database: &Database,
+fn utility_authorize(
+    policies: &[Policy],
     context: &RequestContext,
     principal: &Principal,
-    action: Action,
-    resource: Resource,
+    action: ActionRef<'_>,
+    resource: ResourceRef<'_>,
 ) -> Option<impl IntoResponse> {
-    let context = Context {
+    let context = ContextRef {
         action,
-        data: context.0.clone(),
-        principal: principal.clone(),
+        data: &context.0,
+        principal: principal.into(),
         resource,
     };
 
-    let policies = database.find_by_principal(principal).await;
-
     println!();
-    match evaluate(&context, &policies) {
+    match evaluate(context, policies) {
         Some(policy) => match policy.effect {
             Effect::Allow => {
                 println!("explicit allow: {context:?} {policy:?}");
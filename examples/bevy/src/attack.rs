@@ -41,29 +41,18 @@ impl IntoUnauthorizedContext for Attack {
         let what = query.get(event.data.what);
 
         if let (Ok(actor), Ok(who), Ok(what)) = (actor, who, what) {
-            Some(authorization::Context {
-                action: authorization::Action {
-                    noun: who.noun.clone(),
-                    scope: who.scope.clone(),
-                    verb: "attack".to_string(),
-                },
-                data: std::collections::HashMap::from([
-                    (
-                        "who:id".to_string(),
-                        std::collections::HashSet::from([who.id.to_string()]),
-                    ),
-                    (
-                        "who:noun".to_string(),
-                        std::collections::HashSet::from([who.noun.to_string()]),
-                    ),
-                    (
-                        "who:scope".to_string(),
-                        std::collections::HashSet::from([who.scope.to_string()]),
-                    ),
-                ]),
-                principal: actor.into(),
-                resource: what.into(),
-            })
+            Some(authorization::Context::new(
+                authorization::Action::new(&who.scope, &who.noun, "attack"),
+                authorization::Principal::from(actor).attribute(
+                    "who",
+                    authorization::Value::Record(std::collections::BTreeMap::from([
+                        ("id".to_string(), who.id.clone().into()),
+                        ("noun".to_string(), who.noun.clone().into()),
+                        ("scope".to_string(), who.scope.clone().into()),
+                    ])),
+                ),
+                what,
+            ))
         } else {
             None
         }
@@ -1,6 +1,7 @@
+use authorization::PolicyStore;
 use authorization_bevy::{
     AuthorizationEventPlugin, AuthorizationSet, Authorized, Identifier, IntoUnauthorizedContext,
-    Unauthorized,
+    Store, Unauthorized,
 };
 use bevy::{prelude::*, utils::Uuid};
 
@@ -35,20 +36,11 @@ impl IntoUnauthorizedContext for SpawnLoot {
         let actor = query.get(event.actor);
 
         if let Ok(actor) = actor {
-            Some(authorization::Context {
-                action: authorization::Action {
-                    noun: "loot".to_string(),
-                    scope: "world".to_string(),
-                    verb: "spawn".to_string(),
-                },
-                data: std::collections::HashMap::new(),
-                principal: actor.into(),
-                resource: authorization::Resource {
-                    id: "".to_string(),
-                    noun: "".to_string(),
-                    scope: "world".to_string(),
-                },
-            })
+            Some(authorization::Context::new(
+                authorization::Action::new("world", "loot", "spawn"),
+                actor,
+                authorization::Resource::new("world", "", ""),
+            ))
         } else {
             None
         }
@@ -66,7 +58,7 @@ struct AbandonedTimer(Timer);
 /// Spawns a [`Loot`].
 fn spawn(
     mut commands: Commands,
-    mut database: ResMut<AuthorizationDatabase>,
+    mut database: ResMut<Store<AuthorizationDatabase>>,
     mut reader: EventReader<Authorized<SpawnLoot>>,
     identifiers: Query<&Identifier>,
 ) {
@@ -78,22 +70,19 @@ fn spawn(
                 scope: "world".to_string(),
             };
 
-            database.insert(authorization::Policy {
-                actions: vec![authorization::Action {
-                    noun: "*".to_string(),
-                    scope: "world".to_string(),
-                    verb: "take".to_string(),
-                }],
-                conditions: Default::default(),
-                effect: authorization::Effect::Allow,
-                id: Uuid::new_v4().to_string(),
-                principals: vec![authorization::Principal {
-                    id: target.id.to_string(),
-                    noun: target.noun.clone(),
-                    scope: target.scope.clone(),
-                }],
-                resources: vec![(&identifier).into()],
-            });
+            database
+                .insert(
+                    authorization::Policy::allow(Uuid::new_v4().to_string())
+                        .action(world_take())
+                        .metadata(
+                            authorization::Metadata::new()
+                                .description("the owner of the loot can take the loot.")
+                                .tag("loot"),
+                        )
+                        .principal(target)
+                        .resource(&identifier),
+                )
+                .unwrap();
 
             commands.spawn((
                 Loot,
@@ -108,33 +97,35 @@ fn spawn(
 /// Allow all [`Entity`] to take abandoned [`Loot`].
 fn allow_all_entity_to_take_abandoned_loot(
     time: Res<Time>,
-    mut database: ResMut<AuthorizationDatabase>,
+    mut database: ResMut<Store<AuthorizationDatabase>>,
     mut query: Query<(&Identifier, &mut AbandonedTimer), With<Loot>>,
 ) {
     for (identifier, mut timer) in &mut query {
         if timer.0.tick(time.delta()).just_finished() {
-            database.insert(authorization::Policy {
-                actions: vec![authorization::Action {
-                    noun: "*".to_string(),
-                    scope: "world".to_string(),
-                    verb: "take".to_string(),
-                }],
-                conditions: Default::default(),
-                effect: authorization::Effect::Allow,
-                id: Uuid::new_v4().to_string(),
-                principals: vec![authorization::Principal {
-                    id: "*".to_string(),
-                    noun: "*".to_string(),
-                    scope: "world".to_string(),
-                }],
-                resources: vec![identifier.into()],
-            });
+            database
+                .insert(
+                    authorization::Policy::allow(Uuid::new_v4().to_string())
+                        .action(world_take())
+                        .metadata(
+                            authorization::Metadata::new()
+                                .description("any entity in the world can take abandoned loot.")
+                                .tag("loot"),
+                        )
+                        .principal(authorization::Principal::new("world", "*", "*"))
+                        .resource(identifier),
+                )
+                .unwrap();
 
             info!("\n[LOOT EXCLUSIVITY EXPIRED]\n  {identifier:?}");
         }
     }
 }
 
+/// Returns the action of taking anything in the world.
+fn world_take() -> authorization::Action {
+    authorization::Action::new("world", "*", "take")
+}
+
 /// Despawn [`Taken`] [`Loot`].
 #[allow(clippy::type_complexity)]
 fn despawn_taken(
@@ -11,13 +11,10 @@ mod take;
 
 use artificial_intelligence::ArtificialIntelligencePlugin;
 use attack::AttackPlugin;
-use authorization::Principal;
-use authorization_bevy::{Audit, AuthorizationPlugin, AuthorizationSet, Database, Identifier};
-use bevy::{
-    log::LogPlugin,
-    prelude::*,
-    utils::{HashMap, Uuid},
-};
+use authorization::{MemoryStore, PolicyStore, Principal};
+use authorization_bevy::{Audit, AuthorizationPlugin, AuthorizationSet, Identifier, Store};
+use authorization_macros::policy;
+use bevy::{log::LogPlugin, prelude::*};
 use game_master::GameMasterPlugin;
 use kill::KillPlugin;
 use loot::LootPlugin;
@@ -39,9 +36,7 @@ pub fn main() {
     app.add_plugins(AuthorizationPlugin)
         .add_systems(Startup, insert_policies_into_database)
         .add_systems(Update, user_interface_authorization.after(AuthorizationSet))
-        .insert_resource(AuthorizationDatabase {
-            ..Default::default()
-        });
+        .insert_resource(Store(AuthorizationDatabase::new()));
 
     app.add_event::<Despawned>()
         .add_plugins((
@@ -71,199 +66,96 @@ pub fn main() {
 struct Despawned(Identifier);
 
 /// Database.
-#[derive(Default, Clone, Resource)]
-pub struct AuthorizationDatabase {
-    /// Data.
-    data: HashMap<String, authorization::Policy>,
-}
-
-impl AuthorizationDatabase {
-    /// Deletes policies by principal.
-    fn delete_by_principal(&mut self, identifier: &Identifier) {
-        self.data.retain(|_, policy| {
-            !policy.principals.iter().all(|p| {
-                p.id == identifier.id && p.noun == identifier.noun && p.scope == identifier.scope
-            })
-        });
-    }
-
-    /// Deletes policies by resource.
-    fn delete_by_resource(&mut self, identifier: &Identifier) {
-        self.data.retain(|_, policy| {
-            !policy.resources.iter().all(|r| {
-                r.id == identifier.id && r.noun == identifier.noun && r.scope == identifier.scope
-            })
-        });
-    }
-
-    /// Inserts a [`authorization::Policy`].
-    fn insert(&mut self, policy: authorization::Policy) {
-        self.data.insert(policy.id.clone(), policy);
-    }
-}
-
-impl Database for AuthorizationDatabase {
-    fn query_by_principal(&self, principal: &Principal) -> Vec<authorization::Policy> {
-        self.data
-            .values()
-            .filter(|policy| {
-                policy.principals.iter().any(|p| {
-                    (p.id == "*" || p.id == principal.id)
-                        && (p.noun == "*" || p.noun == principal.noun)
-                        && (p.scope == "*" || p.scope == principal.scope)
-                })
-            })
-            .cloned()
-            .collect()
-    }
-}
+pub type AuthorizationDatabase = MemoryStore;
 
-/// Insert [`authorization::Policy`] policies into [`authorization_bevy::Database`].
-fn insert_policies_into_database(mut database: ResMut<AuthorizationDatabase>) {
+/// Insert [`authorization::Policy`] policies into [`AuthorizationDatabase`].
+fn insert_policies_into_database(mut database: ResMut<Store<AuthorizationDatabase>>) {
     let allow_player_attacking_monster = true;
     let allow_player_attacking_player = false;
     let allow_monster_attacking_monster = false;
     let allow_monster_attacking_player = false;
 
     // any game master in the world can spawn loot, monsters, and players into the world.
-    database.insert(authorization::Policy {
-        actions: vec![
-            authorization::Action {
-                noun: "loot".to_string(),
-                scope: "world".to_string(),
-                verb: "spawn".to_string(),
-            },
-            authorization::Action {
-                noun: "monster".to_string(),
-                scope: "world".to_string(),
-                verb: "spawn".to_string(),
-            },
-            authorization::Action {
-                noun: "player".to_string(),
-                scope: "world".to_string(),
-                verb: "spawn".to_string(),
-            },
-        ],
-        conditions: vec![],
-        effect: authorization::Effect::Allow,
-        id: Uuid::new_v4().to_string(),
-        principals: vec![authorization::Principal {
-            id: "*".to_string(),
-            noun: "game_master".to_string(),
-            scope: "world".to_string(),
-        }],
-        resources: vec![authorization::Resource {
-            id: "*".to_string(),
-            noun: "*".to_string(),
-            scope: "world".to_string(),
-        }],
-    });
+    database
+        .insert(policy! {
+            @id("spawn")
+            @description("any game master in the world can spawn loot, monsters, and players into the world.")
+            @tags("spawn")
+            allow game_master@world:*
+              to world:loot:spawn, world:monster:spawn, world:player:spawn
+              on *@world:*
+        })
+        .unwrap();
 
     if allow_player_attacking_monster {
         // any player in the world can attack any monster in the world.
-        database.insert(authorization::Policy {
-            actions: vec![authorization::Action {
-                noun: "*".to_string(),
-                scope: "world".to_string(),
-                verb: "attack".to_string(),
-            }],
-            conditions: vec![],
-            effect: authorization::Effect::Allow,
-            id: Uuid::new_v4().to_string(),
-            principals: vec![authorization::Principal {
-                id: "*".to_string(),
-                noun: "player".to_string(),
-                scope: "world".to_string(),
-            }],
-            resources: vec![authorization::Resource {
-                id: "*".to_string(),
-                noun: "monster".to_string(),
-                scope: "world".to_string(),
-            }],
-        });
+        database
+            .insert(policy! {
+                @id("player-attack-monster")
+                @description("any player in the world can attack any monster in the world.")
+                @tags("pve")
+                allow player@world:* to world:*:attack on monster@world:*
+            })
+            .unwrap();
     }
 
     if allow_player_attacking_player {
         // any player in the world can attack any player in the world.
-        database.insert(authorization::Policy {
-            actions: vec![authorization::Action {
-                noun: "*".to_string(),
-                scope: "world".to_string(),
-                verb: "attack".to_string(),
-            }],
-            conditions: vec![],
-            effect: authorization::Effect::Allow,
-            id: Uuid::new_v4().to_string(),
-            principals: vec![authorization::Principal {
-                id: "*".to_string(),
-                noun: "player".to_string(),
-                scope: "world".to_string(),
-            }],
-            resources: vec![authorization::Resource {
-                id: "*".to_string(),
-                noun: "player".to_string(),
-                scope: "world".to_string(),
-            }],
-        });
+        database
+            .insert(policy! {
+                @id("player-attack-player")
+                @description("any player in the world can attack any player in the world.")
+                @tags("pvp")
+                allow player@world:* to world:*:attack on player@world:*
+            })
+            .unwrap();
     }
 
     if allow_monster_attacking_monster {
         // any monster in the world can attack any monster in the world.
-        database.insert(authorization::Policy {
-            actions: vec![authorization::Action {
-                noun: "*".to_string(),
-                scope: "world".to_string(),
-                verb: "attack".to_string(),
-            }],
-            conditions: vec![],
-            effect: authorization::Effect::Allow,
-            id: Uuid::new_v4().to_string(),
-            principals: vec![authorization::Principal {
-                id: "*".to_string(),
-                noun: "monster".to_string(),
-                scope: "world".to_string(),
-            }],
-            resources: vec![authorization::Resource {
-                id: "*".to_string(),
-                noun: "monster".to_string(),
-                scope: "world".to_string(),
-            }],
-        });
+        database
+            .insert(policy! {
+                @id("monster-attack-monster")
+                @description("any monster in the world can attack any monster in the world.")
+                @tags("eve")
+                allow monster@world:* to world:*:attack on monster@world:*
+            })
+            .unwrap();
     }
 
     if allow_monster_attacking_player {
         // any monster in the world can attack any player in the world.
-        database.insert(authorization::Policy {
-            actions: vec![authorization::Action {
-                noun: "*".to_string(),
-                scope: "world".to_string(),
-                verb: "attack".to_string(),
-            }],
-            conditions: vec![],
-            effect: authorization::Effect::Allow,
-            id: Uuid::new_v4().to_string(),
-            principals: vec![authorization::Principal {
-                id: "*".to_string(),
-                noun: "monster".to_string(),
-                scope: "world".to_string(),
-            }],
-            resources: vec![authorization::Resource {
-                id: "*".to_string(),
-                noun: "player".to_string(),
-                scope: "world".to_string(),
-            }],
-        });
+        database
+            .insert(policy! {
+                @id("monster-attack-player")
+                @description("any monster in the world can attack any player in the world.")
+                @tags("pve")
+                allow monster@world:* to world:*:attack on player@world:*
+            })
+            .unwrap();
     }
 }
 
-/// Removes despawned entities from [`authorization_bevy::Database`].
+/// Removes policies of despawned entities from [`AuthorizationDatabase`].
+///
+/// Policies which also apply to other principals or resources are kept.
 fn remove_despawned_from_database(
-    mut database: ResMut<AuthorizationDatabase>,
+    mut database: ResMut<Store<AuthorizationDatabase>>,
     mut reader: EventReader<Despawned>,
 ) {
     for event in reader.read() {
-        database.delete_by_principal(&event.0);
-        database.delete_by_resource(&event.0);
+        let principal = Principal::from(&event.0);
+        let resource = authorization::Resource::from(&event.0);
+
+        let mut policies = database.query_by_principal(&principal).unwrap();
+        policies.retain(|policy| policy.principals.iter().all(|p| *p == principal));
+        let mut by_resource = database.query_by_resource(&resource).unwrap();
+        by_resource.retain(|policy| policy.resources.iter().all(|r| *r == resource));
+        policies.extend(by_resource);
+
+        for policy in policies {
+            database.delete(&policy.id).unwrap();
+        }
     }
 }
 
@@ -273,14 +165,18 @@ fn user_interface_authorization(mut reader: EventReader<Audit>) {
         let context = &event.context;
 
         match &event.policy {
-            Some(policy) => match policy.effect {
-                authorization::Effect::Allow => {
-                    debug!("\n[AUTHORIZATION]\n  explicit allow    \n    context: {context:?}    \n    policy: {policy:?}")
-                }
-                authorization::Effect::Deny => {
-                    warn!("\n[AUTHORIZATION]\n  explicit deny    \n    context: {context:?}    \n    policy: {policy:?}")
+            Some(policy) => {
+                let description = policy.metadata.description.as_deref().unwrap_or(&policy.id);
+
+                match policy.effect {
+                    authorization::Effect::Allow => {
+                        debug!("\n[AUTHORIZATION]\n  explicit allow    \n    context: {context:?}    \n    policy: {description}")
+                    }
+                    authorization::Effect::Deny => {
+                        warn!("\n[AUTHORIZATION]\n  explicit deny    \n    context: {context:?}    \n    policy: {description}")
+                    }
                 }
-            },
+            }
             None => warn!("\n[AUTHORIZATION]\n  implicit deny  \n    context: {context:?}"),
         }
     }
@@ -36,20 +36,11 @@ impl IntoUnauthorizedContext for SpawnMonster {
         let actor = query.get(event.actor);
 
         if let Ok(actor) = actor {
-            Some(authorization::Context {
-                action: authorization::Action {
-                    noun: "monster".to_string(),
-                    scope: "world".to_string(),
-                    verb: "spawn".to_string(),
-                },
-                data: std::collections::HashMap::new(),
-                principal: actor.into(),
-                resource: authorization::Resource {
-                    id: "".to_string(),
-                    noun: "".to_string(),
-                    scope: "world".to_string(),
-                },
-            })
+            Some(authorization::Context::new(
+                authorization::Action::new("world", "monster", "spawn"),
+                actor,
+                authorization::Resource::new("world", "", ""),
+            ))
         } else {
             None
         }
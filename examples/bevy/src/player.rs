@@ -33,20 +33,11 @@ impl IntoUnauthorizedContext for SpawnPlayer {
         let actor = query.get(event.actor);
 
         if let Ok(actor) = actor {
-            Some(authorization::Context {
-                action: authorization::Action {
-                    noun: "player".to_string(),
-                    scope: "world".to_string(),
-                    verb: "spawn".to_string(),
-                },
-                data: std::collections::HashMap::new(),
-                principal: actor.into(),
-                resource: authorization::Resource {
-                    id: "".to_string(),
-                    noun: "".to_string(),
-                    scope: "world".to_string(),
-                },
-            })
+            Some(authorization::Context::new(
+                authorization::Action::new("world", "player", "spawn"),
+                actor,
+                authorization::Resource::new("world", "", ""),
+            ))
         } else {
             None
         }